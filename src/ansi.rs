@@ -0,0 +1,229 @@
+//! handle the ANSI escape sequences that can be found in strings, e.g. in colored logs
+//!
+//! such sequences must never reach the terminal as is, because they would move the cursor or
+//! bleed colors across the cells of the TUI.
+use ratatui::style::{Color, Modifier, Style};
+
+/// the escape character that starts all ANSI sequences
+const ESC: char = '\x1b';
+/// the *bell* character that can end an OSC sequence
+const BEL: char = '\x07';
+
+/// split a string into segments of text and the style given to them by the SGR sequences
+///
+/// all the other escape sequences, e.g. cursor movements or OSC window titles, are removed, as
+/// well as any ESC character that does not start a valid sequence.
+///
+/// > see the tests for detailed examples
+pub(crate) fn parse(input: &str) -> Vec<(String, Style)> {
+    let mut segments = vec![];
+    let mut style = Style::default();
+    let mut text = String::new();
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            text.push(c);
+            continue;
+        }
+
+        match chars.peek() {
+            // CSI: `ESC [ <parameters> <final byte>`
+            Some('[') => {
+                chars.next();
+                let mut params = String::new();
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        if c == 'm' {
+                            if !text.is_empty() {
+                                segments.push((std::mem::take(&mut text), style));
+                            }
+                            style = apply_sgr(style, &params);
+                        }
+                        break;
+                    }
+                    params.push(c);
+                }
+            }
+            // OSC: `ESC ] <text> BEL` or `ESC ] <text> ESC \`
+            Some(']') => {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    if c == BEL {
+                        break;
+                    }
+                    if c == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // a bare ESC, followed by another sequence or at the end of the string
+            Some(&ESC) | None => {}
+            // any other two-character sequence
+            Some(_) => {
+                chars.next();
+            }
+        }
+    }
+
+    if !text.is_empty() {
+        segments.push((text, style));
+    }
+
+    segments
+}
+
+/// remove all the ANSI escape sequences from a string
+///
+/// > see the tests for detailed examples
+pub(crate) fn strip(input: &str) -> String {
+    if !input.contains(ESC) {
+        return input.to_string();
+    }
+
+    parse(input).into_iter().map(|(text, _)| text).collect()
+}
+
+/// apply the semicolon-separated parameters of an SGR sequence, i.e. `ESC [ ... m`, to a style
+pub(crate) fn apply_sgr(style: Style, params: &str) -> Style {
+    let params: Vec<u8> = params
+        .split(';')
+        .map(|p| p.parse::<u8>().unwrap_or(0))
+        .collect();
+
+    let mut style = style;
+    let mut params = params.iter();
+    while let Some(p) = params.next() {
+        style = match p {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            25 => style.remove_modifier(Modifier::SLOW_BLINK),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(Color::Indexed(p - 30)),
+            38 => match extended_color(&mut params) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            39 => style.fg(Color::Reset),
+            40..=47 => style.bg(Color::Indexed(p - 40)),
+            48 => match extended_color(&mut params) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            49 => style.bg(Color::Reset),
+            90..=97 => style.fg(Color::Indexed(p - 90 + 8)),
+            100..=107 => style.bg(Color::Indexed(p - 100 + 8)),
+            _ => style,
+        }
+    }
+
+    style
+}
+
+/// parse the color of a `38` or `48` SGR parameter, either `5;<n>` or `2;<r>;<g>;<b>`
+fn extended_color<'a>(params: &mut impl Iterator<Item = &'a u8>) -> Option<Color> {
+    match params.next() {
+        Some(5) => params.next().map(|n| Color::Indexed(*n)),
+        Some(2) => {
+            let r = params.next()?;
+            let g = params.next()?;
+            let b = params.next()?;
+            Some(Color::Rgb(*r, *g, *b))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::{Color, Modifier, Style};
+
+    use super::{parse, strip};
+
+    #[test]
+    fn strip_sequences() {
+        #[rustfmt::skip]
+        let cases = vec![
+            ("foo", "foo"),
+            ("", ""),
+            // SGR
+            ("\x1b[31mred\x1b[0m", "red"),
+            ("\x1b[1;32mbold green\x1b[m and normal", "bold green and normal"),
+            ("\x1b[38;2;255;0;0mrgb\x1b[39m", "rgb"),
+            // other CSI sequences, e.g. to move the cursor or clear the line
+            ("foo\x1b[2Kbar\x1b[10;20H", "foobar"),
+            // OSC titles, ended by BEL or ST
+            ("\x1b]0;my title\x07text", "text"),
+            ("\x1b]2;my title\x1b\\text", "text"),
+            // bare ESC bytes
+            ("foo\x1b", "foo"),
+            ("foo\x1bcbar", "foobar"),
+            ("\x1b\x1b[31mfoo", "foo"),
+            // unicode is kept untouched
+            ("\x1b[31m日本語\x1b[0m", "日本語"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(strip(input), expected, "stripping {:?}", input);
+        }
+    }
+
+    #[test]
+    fn parse_sgr_sequences() {
+        #[rustfmt::skip]
+        let cases = vec![
+            ("foo", vec![("foo", Style::default())]),
+            (
+                "\x1b[31mred\x1b[0m normal",
+                vec![
+                    ("red", Style::default().fg(Color::Indexed(1))),
+                    (" normal", Style::default()),
+                ],
+            ),
+            (
+                "\x1b[1;4mbold\x1b[22m underlined",
+                vec![
+                    ("bold", Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)),
+                    (
+                        " underlined",
+                        Style::default()
+                            .add_modifier(Modifier::UNDERLINED)
+                            .remove_modifier(Modifier::BOLD | Modifier::DIM),
+                    ),
+                ],
+            ),
+            (
+                "\x1b[38;5;208m\x1b[48;2;1;2;3mcolors",
+                vec![(
+                    "colors",
+                    Style::default().fg(Color::Indexed(208)).bg(Color::Rgb(1, 2, 3)),
+                )],
+            ),
+            (
+                "\x1b[92mbright\x1b]0;title\x07 still",
+                vec![
+                    ("bright still", Style::default().fg(Color::Indexed(10))),
+                ],
+            ),
+        ];
+
+        for (input, expected) in cases {
+            let expected: Vec<(String, Style)> = expected
+                .into_iter()
+                .map(|(t, s)| (t.to_string(), s))
+                .collect();
+            assert_eq!(parse(input), expected, "parsing {:?}", input);
+        }
+    }
+}
@@ -1,10 +1,37 @@
 //! the higher level application
+use std::{collections::HashMap, rc::Rc, time::Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+
 use nu_protocol::{
     ast::{CellPath, PathMember},
-    Span, Value,
+    Record, Span, Value,
+};
+
+use crate::{
+    command::Command,
+    config::{AccelerationConfig, ColumnWidth, DiffConfig},
+    edit::Editor,
+    file::parse_string,
+    log::Logger,
+    navigation,
+    nu::{
+        diff::{diff, Difference},
+        search::Match,
+        source::{source_info, SourceInfo},
+        stats::{column_stats, ColumnStats},
+        strings::Transform,
+        validate::failures,
+        value::{column_member, explorable, follow},
+    },
+    open::{open, openable},
+    paths::PathStyles,
+    ui::{RowsCache, TableCache},
 };
 
-use crate::edit::Editor;
+/// the number of repetitions of the same key before moving twice as fast, see
+/// [`App::accelerate`]
+const REPEATS_PER_STEP: usize = 4;
 
 /// the mode in which the application is
 #[derive(Clone, Debug, PartialEq)]
@@ -16,6 +43,12 @@ pub enum Mode {
     /// the PEEKING mode lets the user *peek* data out of the application, to be reused later
     Peeking,
     Bottom,
+    /// the GOTO mode lets the user type an index or a key to jump to in the current level
+    Goto,
+    /// the SEARCH mode lets the user search the whole data and pick one of the matches to jump to
+    Search,
+    /// the COMMAND mode lets the user type and run a [`Command`]
+    Command,
 }
 
 impl Default for Mode {
@@ -31,11 +64,66 @@ impl std::fmt::Display for Mode {
             Self::Insert => "INSERT",
             Self::Peeking => "PEEKING",
             Self::Bottom => "BOTTOM",
+            Self::Goto => "GOTO",
+            Self::Search => "SEARCH",
+            Self::Command => "COMMAND",
         };
         write!(f, "{}", repr)
     }
 }
 
+impl std::str::FromStr for Mode {
+    type Err = String;
+
+    /// parse the name of a mode to start in, case-insensitively, e.g. `insert` or `PEEKING`
+    ///
+    /// > :bulb: **Note**  
+    /// > the BOTTOM mode is not a valid mode to start in, it can only be reached by going
+    /// > deeper in the data
+    ///
+    /// > see the tests for detailed examples
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "normal" => Ok(Self::Normal),
+            "insert" => Ok(Self::Insert),
+            "peeking" => Ok(Self::Peeking),
+            "goto" => Ok(Self::Goto),
+            "search" => Ok(Self::Search),
+            "command" => Ok(Self::Command),
+            _ => Err(format!(
+                "expected one of normal, insert, peeking, goto, search or command, found `{}`",
+                s
+            )),
+        }
+    }
+}
+
+/// a short message to show in the status bar, until the next key press
+#[derive(Clone, Debug, PartialEq)]
+pub enum StatusMessage {
+    Info(String),
+    Error(String),
+}
+
+/// the key of a macro being waited for, i.e. the name of its register, see
+/// [`crate::config::KeyBindingsMap::record_macro`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PendingMacro {
+    Record,
+    Replay,
+}
+
+/// a [`Command`] that will only run once the user has confirmed it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Confirmation {
+    /// the yes-or-no question shown in the status bar
+    pub question: String,
+    pub command: Command,
+}
+
+/// the index and the reason of the failing rows of a table, see [`App::validate_at`]
+pub type Failures = Vec<(usize, String)>;
+
 /// the complete state of the application
 pub struct App {
     /// the full current path in the data
@@ -45,16 +133,261 @@ pub struct App {
     /// the editor to modify the cells of the data
     pub editor: Editor,
     /// the value that is being explored
-    pub value: Value,
+    ///
+    /// > :bulb: **Note**  
+    /// > the value is shared and never copied as a whole while exploring: a new value is only
+    /// > built on edition, and peeking the whole data gives the original value back
+    pub value: Rc<Value>,
+    /// the scroll offset of the current view, kept from one frame to the next
+    pub offset: usize,
+    /// the last scroll offsets and focused columns of the views that have been left, by *cell
+    /// path* of the view, the least recently left first, see [`App::remember_offset`]
+    pub offsets: Vec<(Vec<PathMember>, usize, Option<usize>)>,
+    /// whether the next frame should center the view around the selected row
+    pub recenter: bool,
+    /// the full cell paths of the elements marked by the user, to be peeked all at once
+    pub marks: Vec<Vec<PathMember>>,
+    /// the *cell path* of the list and the index where the selected range starts, the range going
+    /// from there to the cursor, see [`App::toggle_range`]
+    pub range: Option<(Vec<PathMember>, usize)>,
+    /// the matches of the last deep search, empty while the query is being typed
+    ///
+    /// the matches are kept after jumping to one of them, to jump to the others from NORMAL mode,
+    /// until the next search or until they are cleared.
+    pub matches: Vec<Match>,
+    /// the index of the match under the cursor in the list of matches
+    pub selected_match: usize,
+    /// a message to show in the status bar
+    pub status_message: Option<StatusMessage>,
+    /// whether the simple values are shown raw, i.e. without the formatting of Nushell
+    pub raw: bool,
+    /// the number of levels of the lists and records shown in their preview, see
+    /// [`crate::config::DisplayConfig::preview_depth`]
+    pub preview_depth: usize,
+    /// a command waiting for the confirmation of the user
+    pub confirmation: Option<Confirmation>,
+    /// the full cell path of the element to diff the next one against
+    pub diff_base: Option<Vec<PathMember>>,
+    /// the differences of the last diff, shown in a popup until closed
+    pub differences: Vec<Difference>,
+    /// the index of the difference under the cursor in the popup
+    pub selected_difference: usize,
+    /// whether the data has been edited since the start, to not discard the edits silently
+    pub dirty: bool,
+    /// whether quitting without peeking anything is an error, see [`crate::Options::strict`]
+    pub strict: bool,
+    /// the statistics of a column of a table, shown in a popup until the next key press
+    pub stats: Option<ColumnStats>,
+    /// the index of the column under the cursor in a table, `None` when the whole row is selected
+    pub column: Option<usize>,
+    /// the depths of the *cell path* at which a table cell has been entered from its column, to
+    /// go back to the column instead of the row when going back
+    pub column_depths: Vec<usize>,
+    /// the styles of the strings that are paths to existing files, see
+    /// [`crate::config::DisplayConfig::colorize_values`]
+    pub path_styles: PathStyles,
+    /// the representation of the last table rendered, see [`TableCache`]
+    pub table_cache: Option<TableCache>,
+    /// the rows of the last list or record rendered, see [`RowsCache`]
+    pub rows_cache: Option<RowsCache>,
+    /// the size of the terminal, as *(width, height)*, when the last frame was drawn
+    pub screen_size: (u16, u16),
+    /// the number of rows to move by at once, see [`App::accelerate`]
+    pub step: usize,
+    /// the last key that moved up or down, when it was pressed and how many times it has been
+    /// repeated in a row
+    pub repeat: Option<(KeyCode, Instant, usize)>,
+    /// the log of the key events and the transitions, see [`crate::config::LogConfig`]
+    pub logger: Logger,
+    /// whether the editor of INSERT mode is typing a new element of the current view instead
+    /// of the new content of the cell under the cursor, see [`App::enter_insertion`]
+    pub insertion: bool,
+    /// the settings of the tables explored so far, by *cell path* of the table
+    pub table_settings: Vec<(Vec<PathMember>, TableSettings)>,
+    /// the views the strings parsed as structured data have been explored from, the most
+    /// recent one last, see [`App::parse_string`]
+    pub parsed: Vec<ParsedView>,
+    /// the other pane when the screen is split vertically, see [`App::toggle_split`]
+    pub split: Option<Split>,
+    /// the key events of the recorded macros, by register
+    pub macros: HashMap<char, Vec<KeyEvent>>,
+    /// the register and the key events of the macro being recorded
+    pub recording: Option<(char, Vec<KeyEvent>)>,
+    /// whether the next key is the register of a macro to record or to replay
+    pub pending_macro: Option<PendingMacro>,
+    /// the register of the last macro replayed, to replay it again
+    pub last_macro: Option<char>,
+    /// the number typed before replaying a macro, i.e. the number of times to replay it
+    pub count: usize,
+    /// the schema the rows of the tables are checked against, see [`App::validate_view`]
+    pub validation: Option<Record>,
+    /// the index and the reason of the failing rows of the tables validated so far, by *cell
+    /// path* of the table
+    pub failures: Vec<(Vec<PathMember>, Failures)>,
+    /// the text the data has been parsed from, to show where the values come from
+    pub source: Option<String>,
+    /// where the value under the cursor comes from, shown in a popup until the next key press
+    pub info: Option<SourceInfo>,
+    /// how the widths of the columns of the tables are computed, see
+    /// [`crate::config::DisplayConfig::column_width`]
+    pub column_width: ColumnWidth,
+    /// the items of the top-level list past the `--limit`, neither rendered nor explored but given
+    /// back with the whole data, see [`App::whole_value`]
+    pub hidden: Vec<Value>,
+}
+
+/// the state of one of the panes of a split screen, i.e. where it is in the data
+pub struct Pane {
+    position: CellPath,
+    mode: Mode,
+    offset: usize,
+    column: Option<usize>,
+    column_depths: Vec<usize>,
+    table_cache: Option<TableCache>,
+    rows_cache: Option<RowsCache>,
+}
+
+/// a vertical split of the screen, to explore two parts of the data side by side
+///
+/// the state of the [`App`] is the one of the focused pane, the other one being kept aside until
+/// the panes are switched, see [`App::switch_pane`].
+pub struct Split {
+    /// the pane that is not focused
+    pane: Pane,
+    /// whether the focused pane is the one on the right
+    pub right: bool,
+    /// whether the panes are swapped, i.e. the [`App`] is in the state of the pane that is not
+    /// focused, see [`App::swap_panes`]
+    swapped: bool,
+}
+
+impl Split {
+    /// the *cell path* of the pane that is not focused
+    pub fn other_position(&self) -> &CellPath {
+        &self.pane.position
+    }
+
+    pub(crate) fn is_swapped(&self) -> bool {
+        self.swapped
+    }
+}
+
+/// the state of the exploration before a string was parsed as structured data, to go back to
+/// the string when leaving the parsed data
+pub struct ParsedView {
+    /// the data the string is in
+    value: Rc<Value>,
+    /// the *cell path* of the string in the data
+    position: CellPath,
+    mode: Mode,
+    offset: usize,
+    offsets: Vec<(Vec<PathMember>, usize, Option<usize>)>,
+    marks: Vec<Vec<PathMember>>,
+    column_depths: Vec<usize>,
+    /// the column the table has been grouped by, `None` for a string parsed as data, see
+    /// [`App::group_by`]
+    pub grouped_by: Option<String>,
+}
+
+/// the settings of a table, kept while exploring other parts of the data
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableSettings {
+    /// the column that is always shown at the left edge of the table, whatever the horizontal
+    /// scroll
+    pub pinned: Option<String>,
+    /// the index of the first column shown after scrolling horizontally, the pinned column
+    /// aside
+    pub column_offset: usize,
+}
+
+/// the parts of the state of the application that are shown on the screen, to only draw a frame
+/// when one of them has changed, see [`App::frame_state`]
+#[derive(PartialEq)]
+pub(crate) struct FrameState {
+    position: CellPath,
+    mode: Mode,
+    editor: Editor,
+    /// the data is compared by address, a new value being built on each edit
+    value: *const Value,
+    offset: usize,
+    recenter: bool,
+    marks: Vec<Vec<PathMember>>,
+    range: Option<(Vec<PathMember>, usize)>,
+    matches: Vec<Match>,
+    selected_match: usize,
+    status_message: Option<StatusMessage>,
+    raw: bool,
+    preview_depth: usize,
+    confirmation: Option<Confirmation>,
+    diff_base: Option<Vec<PathMember>>,
+    differences: Vec<Difference>,
+    selected_difference: usize,
+    dirty: bool,
+    stats: Option<ColumnStats>,
+    column: Option<usize>,
+    insertion: bool,
+    table_settings: Vec<(Vec<PathMember>, TableSettings)>,
+    parsed: usize,
+    split: Option<(bool, CellPath)>,
+    recording: Option<char>,
+    pending_macro: Option<PendingMacro>,
+    count: usize,
+    failures: Vec<(Vec<PathMember>, Failures)>,
+    info: Option<SourceInfo>,
+    column_width: ColumnWidth,
 }
 
+/// the number of views whose scroll offset is remembered, see [`App::remember_offset`]
+const MAX_REMEMBERED_OFFSETS: usize = 64;
+
 impl Default for App {
     fn default() -> Self {
         Self {
             position: CellPath { members: vec![] },
             mode: Mode::default(),
             editor: Editor::default(),
-            value: Value::default(),
+            value: Rc::new(Value::default()),
+            offset: 0,
+            recenter: false,
+            marks: vec![],
+            range: None,
+            offsets: vec![],
+            matches: vec![],
+            selected_match: 0,
+            status_message: None,
+            raw: false,
+            preview_depth: 2,
+            confirmation: None,
+            diff_base: None,
+            differences: vec![],
+            dirty: false,
+            strict: false,
+            stats: None,
+            selected_difference: 0,
+            column: None,
+            column_depths: vec![],
+            path_styles: PathStyles::from_env(),
+            table_cache: None,
+            rows_cache: None,
+            screen_size: (80, 24),
+            step: 1,
+            repeat: None,
+            logger: Logger::default(),
+            insertion: false,
+            table_settings: vec![],
+            parsed: vec![],
+            split: None,
+            macros: HashMap::new(),
+            recording: None,
+            pending_macro: None,
+            last_macro: None,
+            count: 0,
+            validation: None,
+            failures: vec![],
+            source: None,
+            info: None,
+            column_width: ColumnWidth::Auto,
+            hidden: vec![],
         }
     }
 }
@@ -63,6 +396,45 @@ impl App {
     /// Handles the tick event of the terminal.
     pub fn tick(&self) {}
 
+    /// the parts of the state that are shown on the screen, see [`FrameState`]
+    pub(crate) fn frame_state(&self) -> FrameState {
+        FrameState {
+            position: self.position.clone(),
+            mode: self.mode.clone(),
+            editor: self.editor.clone(),
+            value: Rc::as_ptr(&self.value),
+            offset: self.offset,
+            recenter: self.recenter,
+            marks: self.marks.clone(),
+            range: self.range.clone(),
+            matches: self.matches.clone(),
+            selected_match: self.selected_match,
+            status_message: self.status_message.clone(),
+            raw: self.raw,
+            preview_depth: self.preview_depth,
+            confirmation: self.confirmation.clone(),
+            diff_base: self.diff_base.clone(),
+            differences: self.differences.clone(),
+            selected_difference: self.selected_difference,
+            dirty: self.dirty,
+            stats: self.stats.clone(),
+            column: self.column,
+            insertion: self.insertion,
+            table_settings: self.table_settings.clone(),
+            parsed: self.parsed.len(),
+            split: self
+                .split
+                .as_ref()
+                .map(|s| (s.right, s.other_position().clone())),
+            recording: self.recording.as_ref().map(|(register, _)| *register),
+            pending_macro: self.pending_macro,
+            count: self.count,
+            failures: self.failures.clone(),
+            info: self.info.clone(),
+            column_width: self.column_width.clone(),
+        }
+    }
+
     pub(super) fn from_value(value: Value) -> Self {
         let mut app = Self::default();
 
@@ -72,19 +444,69 @@ impl App {
                 span: Span::unknown(),
                 optional: vals.is_empty(),
             }),
-            Value::Record { val: rec, .. } => app.position.members.push(PathMember::String {
-                val: rec.cols.first().unwrap_or(&"".to_string()).into(),
-                span: Span::unknown(),
-                optional: rec.cols.is_empty(),
-            }),
+            Value::Record { val: rec, .. } => {
+                app.position.members.push(navigation::first_member(rec))
+            }
             _ => {}
         }
 
-        app.value = value;
+        app.value = Rc::new(value);
 
         app
     }
 
+    /// replace the explored value with a new one, e.g. when the file on disk has changed
+    ///
+    /// the current position is kept if it still exists in the new value, otherwise the deepest
+    /// prefix of the position that still exists is used. the marks that do not exist anymore are
+    /// removed.
+    ///
+    /// > see the tests for detailed examples
+    pub(super) fn reload(&mut self, value: Value) {
+        // NOTE: the strings parsed as data might not exist anymore
+        while self.leave_parsed_view() {}
+        // NOTE: the reloaded data is whole, there is nothing hidden to give back anymore
+        self.hidden.clear();
+
+        let exists = |members: &[PathMember]| follow(&value, members).is_some();
+
+        let n = self.position.members.len();
+        let depth = (1..=n)
+            .rev()
+            .find(|&k| exists(&self.position.members[..k]))
+            .unwrap_or(0);
+        let truncated = depth < n;
+
+        if depth == 0 {
+            // NOTE: nothing of the old position exists anymore, let's start again from the top
+            self.position = Self::from_value(value.clone()).position;
+        } else {
+            self.position.members.truncate(depth);
+        }
+
+        let at_container = matches!(
+            follow(&value, &self.position.members).as_deref(),
+            Some(Value::List { .. } | Value::Record { .. })
+        );
+        if (truncated && self.mode != Mode::Peeking) || (self.is_at_bottom() && at_container) {
+            self.mode = Mode::Normal;
+        }
+
+        self.marks.retain(|m| exists(m));
+        self.matches.retain(|m| exists(&m.path));
+        if self.diff_base.as_ref().is_some_and(|b| !exists(b)) {
+            self.diff_base = None;
+        }
+
+        self.value = Rc::new(value);
+        self.recenter = true;
+        self.column = None;
+        self.column_depths.clear();
+        // NOTE: the lengths of the lists and records might have changed
+        self.offsets.clear();
+        self.revalidate(&[]);
+    }
+
     pub fn is_at_bottom(&self) -> bool {
         matches!(self.mode, Mode::Bottom)
     }
@@ -93,14 +515,828 @@ impl App {
         self.mode = Mode::Bottom;
     }
 
+    /// the cell path of the current view, i.e. the container of the element under the cursor
+    fn view_path(&self) -> &[PathMember] {
+        let n = self.position.members.len();
+        &self.position.members[..n.saturating_sub(1)]
+    }
+
+    /// mark the element under the cursor, or unmark it if it was already marked
+    ///
+    /// > :bulb: **Note**  
+    /// > nothing happens in an empty list or record
+    pub(super) fn toggle_mark(&mut self) {
+        match self.position.members.last() {
+            None
+            | Some(PathMember::Int { optional: true, .. })
+            | Some(PathMember::String { optional: true, .. }) => return,
+            _ => {}
+        }
+
+        match self.marks.iter().position(|m| m == &self.position.members) {
+            Some(i) => {
+                self.marks.remove(i);
+            }
+            None => self.marks.push(self.position.members.clone()),
+        }
+    }
+
+    /// unmark all the elements of the current view, leaving the marks of other views untouched
+    pub(super) fn clear_marks(&mut self) {
+        let view_path = self.view_path().to_vec();
+        self.marks
+            .retain(|m| m[..m.len().saturating_sub(1)] != view_path[..]);
+    }
+
+    /// the last cell path members of all the marked elements in the current view
+    pub(super) fn marks_in_view(&self) -> Vec<PathMember> {
+        let view_path = self.view_path();
+        self.marks
+            .iter()
+            .filter(|m| !m.is_empty() && m[..m.len() - 1] == *view_path)
+            .map(|m| m[m.len() - 1].clone())
+            .collect()
+    }
+
+    /// start selecting a range of the current list at the cursor, or cancel the range if one
+    /// is already selected
+    ///
+    /// > :bulb: **Note**  
+    /// > the range is forgotten when leaving the list, and only lists have ranges
+    pub(super) fn toggle_range(&mut self) -> Result<(), String> {
+        if self.range.take().is_some() {
+            self.status_message = Some(StatusMessage::Info("range cancelled".into()));
+            return Ok(());
+        }
+        if self.is_at_bottom() {
+            return Err("can only select a range in a list, not at the bottom".into());
+        }
+
+        let view = follow(&self.value, self.view_path()).map(explorable);
+        match (view.as_deref(), self.position.members.last()) {
+            (
+                Some(Value::List { .. }),
+                Some(PathMember::Int {
+                    val,
+                    optional: false,
+                    ..
+                }),
+            ) => {
+                self.range = Some((self.view_path().to_vec(), *val));
+                Ok(())
+            }
+            (Some(Value::List { .. }), _) => Err("can not select a range of an empty list".into()),
+            (Some(Value::Record { .. }), _) => {
+                Err("can only select a range of a list, found record".into())
+            }
+            (Some(x), _) => Err(format!(
+                "can only select a range of a list, found {}",
+                x.get_type()
+            )),
+            (None, _) => Err("can only select a range of a list".into()),
+        }
+    }
+
+    /// the first and last indices of the selected range, if it is in the current view
+    pub(super) fn range_in_view(&self) -> Option<(usize, usize)> {
+        let (path, anchor) = self.range.as_ref()?;
+        if path != self.view_path() || self.is_at_bottom() {
+            return None;
+        }
+        match self.position.members.last() {
+            Some(PathMember::Int { val, .. }) => Some((*anchor.min(val), *anchor.max(val))),
+            _ => None,
+        }
+    }
+
+    /// remember the scroll offset and the focused column of the current view, before leaving
+    /// it, to restore them when coming back, see [`App::restore_offset`]
+    ///
+    /// only the [`MAX_REMEMBERED_OFFSETS`] views left the most recently are remembered.
+    pub(super) fn remember_offset(&mut self) {
+        let view_path = self.view_path().to_vec();
+        self.offsets.retain(|(p, ..)| p != &view_path);
+        self.offsets.push((view_path, self.offset, self.column));
+        if self.offsets.len() > MAX_REMEMBERED_OFFSETS {
+            self.offsets.remove(0);
+        }
+    }
+
+    /// restore the scroll offset and the focused column of the current view, as they were when
+    /// it was left, or scroll to the top of a view that has never been left
+    pub(super) fn restore_offset(&mut self) {
+        let view_path = self.view_path();
+        match self.offsets.iter().position(|(p, ..)| p == view_path) {
+            Some(i) => {
+                let (_, offset, column) = self.offsets.remove(i);
+                self.offset = offset;
+                self.column = column;
+            }
+            None => self.offset = 0,
+        }
+    }
+
+    /// forget the selected range once the cursor has left its list
+    pub(super) fn forget_range_outside_view(&mut self) {
+        if self.range_in_view().is_none() {
+            self.range = None;
+        }
+    }
+
+    /// the elements of the selected range, as a list
+    pub(super) fn peek_range(&self) -> Option<Value> {
+        let (start, end) = self.range_in_view()?;
+        match follow(&self.value, self.view_path())
+            .map(explorable)
+            .as_deref()
+        {
+            Some(Value::List { vals, .. }) => Some(Value::list(
+                vals[start..=end.min(vals.len() - 1)].to_vec(),
+                Span::unknown(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// remove all the elements of the selected range at once, applying the new list like any
+    /// other edit, see [`App::apply_mutation`]
+    ///
+    /// the cursor goes to the element after the range. the marks inside the range are dropped
+    /// and the ones after it are shifted, to keep pointing at the same elements, instead of
+    /// being all dropped by the edit.
+    pub(super) fn delete_range(&mut self) -> Result<(), String> {
+        if !self.parsed.is_empty() {
+            return Err("the data parsed from a string can not be edited".into());
+        }
+        let (start, end) = self
+            .range_in_view()
+            .ok_or_else(|| "no range selected in the current list".to_string())?;
+
+        let mut vals = match follow(&self.value, self.view_path())
+            .map(explorable)
+            .as_deref()
+        {
+            Some(Value::List { vals, .. }) => vals.clone(),
+            _ => return Err("can only delete a range of a list".into()),
+        };
+        let end = end.min(vals.len() - 1);
+        vals.drain(start..=end);
+
+        // NOTE: `None` for the paths inside the deleted range
+        let view_len = self.view_path().len();
+        let shift = |path: &[PathMember]| -> Option<Vec<PathMember>> {
+            let mut path = path.to_vec();
+            if path.len() > view_len && path[..view_len] == *self.view_path() {
+                if let PathMember::Int { val, .. } = &mut path[view_len] {
+                    if *val > end {
+                        *val -= end - start + 1;
+                    } else if *val >= start {
+                        return None;
+                    }
+                }
+            }
+            Some(path)
+        };
+        let marks: Vec<_> = self.marks.iter().filter_map(|m| shift(m)).collect();
+        self.range = None;
+        *self.position.members.last_mut().unwrap() = PathMember::Int {
+            val: start.min(vals.len().saturating_sub(1)),
+            span: Span::unknown(),
+            optional: vals.is_empty(),
+        };
+        self.status_message = Some(StatusMessage::Info(format!(
+            "deleted {} items",
+            end - start + 1
+        )));
+
+        let path = CellPath {
+            members: self.view_path().to_vec(),
+        };
+        self.apply_mutation(&Value::list(vals, Span::unknown()), &path);
+        self.marks = marks;
+        Ok(())
+    }
+
+    /// the marked elements of the current view, as a list, in the order they appear in the data
+    ///
+    /// this gives `None` when there is no marked element in the current view.
+    pub(super) fn peek_marks(&self) -> Option<Value> {
+        let marks = self.marks_in_view();
+        if marks.is_empty() {
+            return None;
+        }
+
+        let is_marked = |member: PathMember| marks.iter().any(|m| m == &member);
+
+        let vals = match follow(&self.value, self.view_path()).as_deref() {
+            Some(Value::List { vals, .. }) => vals
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| {
+                    is_marked(PathMember::Int {
+                        val: *i,
+                        span: Span::unknown(),
+                        optional: false,
+                    })
+                })
+                .map(|(_, v)| v.clone())
+                .collect(),
+            Some(Value::Record { val: rec, .. }) => (0..rec.cols.len())
+                .filter(|&i| is_marked(column_member(rec, i)))
+                .map(|i| rec.values().nth(i).unwrap().clone())
+                .collect(),
+            _ => return None,
+        };
+
+        Some(Value::list(vals, Span::unknown()))
+    }
+
+    /// a copy of the cell under the cursor, i.e. at the current position
+    pub(super) fn cell_under(&self) -> Value {
+        follow(&self.value, &self.position.members)
+            .unwrap_or_else(|| {
+                panic!(
+                    "unexpected error when following {:?}",
+                    self.position.members
+                )
+            })
+            .into_owned()
+    }
+
+    pub(super) fn enter_goto(&mut self) {
+        self.mode = Mode::Goto;
+        self.editor = Editor::prompt("Goto");
+    }
+
+    /// set the element under the cursor as the base of a diff, or compare it to the base
+    ///
+    /// the element and the base have to be siblings, i.e. in the same view. setting the same
+    /// element as the base twice clears the base.
+    pub(super) fn diff(&mut self, config: &DiffConfig) -> Result<(), String> {
+        let base = match self.diff_base.take() {
+            Some(base) => base,
+            None => {
+                self.diff_base = Some(self.position.members.clone());
+                self.status_message = Some(StatusMessage::Info("diff base set".into()));
+                return Ok(());
+            }
+        };
+
+        if base == self.position.members {
+            self.status_message = Some(StatusMessage::Info("diff base cleared".into()));
+            return Ok(());
+        }
+
+        if base.len() != self.position.members.len() || base[..base.len() - 1] != *self.view_path()
+        {
+            self.diff_base = Some(base);
+            return Err("can only diff two elements of the same view".into());
+        }
+
+        let base = match follow(&self.value, &base) {
+            Some(base) => base,
+            None => return Err("the diff base does not exist anymore".into()),
+        };
+        let other = match follow(&self.value, &self.position.members) {
+            Some(other) => other,
+            None => return Err("nothing under the cursor".into()),
+        };
+        self.differences = diff(&base, &other, config);
+        self.selected_difference = 0;
+        if self.differences.is_empty() {
+            self.status_message = Some(StatusMessage::Info("no difference".into()));
+        }
+
+        Ok(())
+    }
+
+    /// show where the value under the cursor comes from, see [`source_info`]
+    pub(super) fn show_info(&mut self) -> Result<(), String> {
+        let value = match follow(&self.value, &self.position.members) {
+            Some(value) => value,
+            None => return Err("nothing under the cursor".into()),
+        };
+        self.info = Some(source_info(&value, self.source.as_deref()));
+
+        Ok(())
+    }
+
+    /// compute the statistics of the column under the cursor, see [`column_stats`]
+    pub(super) fn column_stats(&mut self) -> Result<(), String> {
+        let column = match self
+            .column
+            .and_then(|c| navigation::view_columns(self).get(c).cloned())
+        {
+            Some(column) => column,
+            None => return Err("no column under the cursor".into()),
+        };
+
+        // NOTE: there is a column under the cursor only in the rows of a table
+        let view = follow(&self.value, self.view_path()).unwrap();
+        let rows = match view.as_ref() {
+            Value::List { vals, .. } => vals,
+            _ => return Err("no column under the cursor".into()),
+        };
+        self.stats = Some(column_stats(rows, &column)?);
+
+        Ok(())
+    }
+
+    /// the settings of the table at *path*, the default ones if it has none yet
+    pub(crate) fn table_settings(&self, path: &[PathMember]) -> TableSettings {
+        self.table_settings
+            .iter()
+            .find(|(p, _)| p == path)
+            .map(|(_, settings)| settings.clone())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn table_settings_mut(&mut self, path: &[PathMember]) -> &mut TableSettings {
+        let i = match self.table_settings.iter().position(|(p, _)| p == path) {
+            Some(i) => i,
+            None => {
+                self.table_settings
+                    .push((path.to_vec(), TableSettings::default()));
+                self.table_settings.len() - 1
+            }
+        };
+        &mut self.table_settings[i].1
+    }
+
+    /// pin the column under the cursor to the left edge of the current table, or unpin it if it
+    /// was already pinned
+    ///
+    /// > :bulb: **Note**  
+    /// > only one column can be pinned at a time, pinning another column unpins the previous one
+    pub(super) fn toggle_pin(&mut self) -> Result<(), String> {
+        let column = match self
+            .column
+            .and_then(|c| navigation::view_columns(self).get(c).cloned())
+        {
+            Some(column) => column,
+            None => return Err("no column under the cursor".into()),
+        };
+
+        let view_path = self.view_path().to_vec();
+        let settings = self.table_settings_mut(&view_path);
+        if settings.pinned.as_ref() == Some(&column) {
+            settings.pinned = None;
+        } else {
+            settings.pinned = Some(column);
+        }
+
+        Ok(())
+    }
+
+    /// compute the number of rows to move by with a *key* pressed at instant *now*
+    ///
+    /// the step doubles every [`REPEATS_PER_STEP`] repetitions of the same key, up to
+    /// [`AccelerationConfig::max_step`], and goes back to a single row as soon as another key is
+    /// pressed or after a pause longer than [`AccelerationConfig::interval`].
+    ///
+    /// > see the tests for detailed examples
+    pub(super) fn accelerate(
+        &mut self,
+        key: KeyCode,
+        now: Instant,
+        config: &AccelerationConfig,
+    ) -> usize {
+        if !config.enabled {
+            return 1;
+        }
+
+        let repeats = match self.repeat {
+            Some((k, last, repeats))
+                if k == key && now.duration_since(last).as_millis() <= config.interval as u128 =>
+            {
+                repeats + 1
+            }
+            _ => 0,
+        };
+        self.repeat = Some((key, now, repeats));
+
+        let exponent = (repeats / REPEATS_PER_STEP).min(usize::BITS as usize - 1);
+        self.step = (1usize << exponent).clamp(1, config.max_step.max(1));
+        self.step
+    }
+
+    /// stop accelerating, e.g. when a key other than up or down is pressed
+    pub(super) fn stop_accelerating(&mut self) {
+        self.repeat = None;
+        self.step = 1;
+    }
+
+    /// go into any *mode*, as the keys of NORMAL mode would, e.g. to start in that mode
+    pub(super) fn enter_mode(&mut self, mode: Mode) -> Result<(), String> {
+        match mode {
+            Mode::Insert => return self.enter_editor(),
+            Mode::Goto => self.enter_goto(),
+            Mode::Search => self.enter_search(),
+            Mode::Command => self.enter_command(""),
+            Mode::Peeking => self.mode = Mode::Peeking,
+            Mode::Normal | Mode::Bottom => {}
+        }
+
+        Ok(())
+    }
+
+    /// go into COMMAND mode, with some *text* already typed in
+    pub(super) fn enter_command(&mut self, text: &str) {
+        self.mode = Mode::Command;
+        self.editor = Editor::prompt_with("Command", text);
+    }
+
+    pub(super) fn enter_search(&mut self) {
+        self.mode = Mode::Search;
+        self.editor = Editor::prompt("Search");
+        self.matches = vec![];
+        self.selected_match = 0;
+    }
+
+    /// jump to the match under the cursor and go back to NORMAL mode
+    ///
+    /// > :bulb: **Note**  
+    /// > nothing happens if there is no match
+    pub(super) fn jump_to_match(&mut self) {
+        if let Some(m) = self.matches.get(self.selected_match) {
+            self.position.members = m.path.clone();
+            self.recenter = true;
+            self.mode = Mode::Normal;
+            self.column = None;
+            self.column_depths.clear();
+        }
+    }
+
+    /// jump to the *next* or the previous match of the last search, wrapping around
+    ///
+    /// > :bulb: **Note**  
+    /// > this gives an error when there is no match to jump to
+    pub(super) fn jump_to_next_match(&mut self, next: bool) -> Result<(), String> {
+        if self.matches.is_empty() {
+            return Err("no previous search".into());
+        }
+
+        let n = self.matches.len();
+        self.selected_match = if next {
+            (self.selected_match + 1) % n
+        } else {
+            (self.selected_match + n - 1) % n
+        };
+        self.jump_to_match();
+        Ok(())
+    }
+
+    /// check the rows of the table at *path* against the schema of [`App::validation`]
+    fn compute_failures(&self, path: &[PathMember]) -> Result<Failures, String> {
+        let schema = match &self.validation {
+            Some(schema) => schema,
+            None => return Err("no schema to validate against, see `--expect`".into()),
+        };
+        match follow(&self.value, path).as_deref() {
+            Some(Value::List { vals, .. }) => Ok(failures(vals, schema)),
+            _ => Err("can only validate the rows of a list".into()),
+        }
+    }
+
+    /// check the rows of the table at *path* against the schema of [`App::validation`] and
+    /// remember the failures until the table is edited, see [`App::revalidate`]
+    pub(crate) fn validate_at(&mut self, path: &[PathMember]) -> Result<usize, String> {
+        if !self.parsed.is_empty() {
+            return Err("can not validate a parsed view".into());
+        }
+        let failures = self.compute_failures(path)?;
+        let n = failures.len();
+        self.failures.retain(|(p, _)| p != path);
+        self.failures.push((path.to_vec(), failures));
+        Ok(n)
+    }
+
+    /// check the rows of the current view, see [`App::validate_at`], and show the number of
+    /// failures in the status bar
+    pub(super) fn validate_view(&mut self) -> Result<(), String> {
+        let path = self.view_path().to_vec();
+        let n = self.validate_at(&path)?;
+        self.status_message = Some(StatusMessage::Info(format!("{} failing rows", n)));
+        Ok(())
+    }
+
+    /// check again the tables validated so far that are inside *path*, or that contain it,
+    /// e.g. because the element at *path* has been edited
+    ///
+    /// > :bulb: **Note**  
+    /// > the tables that are not lists anymore are forgotten
+    pub(super) fn revalidate(&mut self, path: &[PathMember]) {
+        let (stale, fresh) = self
+            .failures
+            .drain(..)
+            .partition(|(p, _)| p.starts_with(path) || path.starts_with(p));
+        self.failures = fresh;
+        for (p, _) in stale {
+            if let Ok(failures) = self.compute_failures(&p) {
+                self.failures.push((p, failures));
+            }
+        }
+    }
+
+    /// the failing rows of the current view, if it has been validated
+    pub(super) fn failures_in_view(&self) -> Option<&[(usize, String)]> {
+        if !self.parsed.is_empty() {
+            return None;
+        }
+        let view_path = self.view_path();
+        self.failures
+            .iter()
+            .find(|(p, _)| p == view_path)
+            .map(|(_, failures)| failures.as_slice())
+    }
+
+    /// jump to the *next* or the previous failing row of the current view, wrapping around
+    ///
+    /// > :bulb: **Note**  
+    /// > the view is validated first if it has not been yet, see [`App::validate_view`]
+    pub(super) fn jump_to_failure(&mut self, next: bool) -> Result<(), String> {
+        if self.failures_in_view().is_none() {
+            let path = self.view_path().to_vec();
+            self.validate_at(&path)?;
+        }
+        let failures = self.failures_in_view().unwrap_or_default();
+        if failures.is_empty() {
+            return Err("no failing row in the view".into());
+        }
+
+        let current = match self.position.members.last() {
+            Some(PathMember::Int { val, .. }) => *val,
+            _ => 0,
+        };
+        let (index, reason) = if next {
+            failures.iter().find(|(i, _)| *i > current)
+        } else {
+            failures.iter().rev().find(|(i, _)| *i < current)
+        }
+        .or(if next {
+            failures.first()
+        } else {
+            failures.last()
+        })
+        .cloned()
+        .unwrap();
+
+        if let Some(last) = self.position.members.last_mut() {
+            *last = PathMember::Int {
+                val: index,
+                span: Span::unknown(),
+                optional: false,
+            };
+        }
+        self.recenter = true;
+        self.status_message = Some(StatusMessage::Info(format!("row {}: {}", index, reason)));
+        Ok(())
+    }
+
+    /// give the editor the width of the screen, see [`App::screen_size`], before typing in it
+    pub(crate) fn fit_editor(&mut self) {
+        if matches!(
+            self.mode,
+            Mode::Insert | Mode::Goto | Mode::Search | Mode::Command
+        ) {
+            self.editor.set_width(self.screen_size.0 as usize)
+        }
+    }
+
+    /// the whole data, i.e. with the items of the top-level list that are hidden past the
+    /// `--limit` put back after the explored ones
+    ///
+    /// > :bulb: **Note**  
+    /// > the data parsed from a string is given as is, it has nothing hidden
+    pub(crate) fn whole_value(&self) -> Rc<Value> {
+        if self.hidden.is_empty() || !self.parsed.is_empty() {
+            return self.value.clone();
+        }
+
+        match &*self.value {
+            Value::List {
+                vals,
+                internal_span,
+            } => {
+                let vals = vals.iter().chain(&self.hidden).cloned().collect();
+                Rc::new(Value::list(vals, *internal_span))
+            }
+            _ => self.value.clone(),
+        }
+    }
+
+    /// replace the element at *path* in the data with *cell*, e.g. after an edit
+    pub(crate) fn apply_mutation(&mut self, cell: &Value, path: &CellPath) {
+        self.value = Rc::new(crate::nu::value::mutate_value_cell(&self.value, path, cell));
+        // NOTE: the edited element and the lists and records inside it might not have the same
+        // length anymore
+        self.offsets.retain(|(p, ..)| !p.starts_with(&path.members));
+        // NOTE: the marks inside the edited element might point at other elements now, e.g. after
+        // a transposition or an insertion
+        self.marks
+            .retain(|m| m.len() <= path.members.len() || !m.starts_with(&path.members));
+        if self
+            .diff_base
+            .as_ref()
+            .is_some_and(|b| b.starts_with(&path.members))
+        {
+            self.diff_base = None;
+        }
+        self.invalidate_matches(&path.members);
+        self.revalidate(&path.members);
+        self.dirty = true;
+    }
+
+    /// forget the matches that are inside the element at *path*, e.g. because it has been edited
+    pub(super) fn invalidate_matches(&mut self, path: &[PathMember]) {
+        let selected = self.matches.get(self.selected_match).cloned();
+        self.matches.retain(|m| !m.path.starts_with(path));
+        self.selected_match = selected
+            .and_then(|s| self.matches.iter().position(|m| m == &s))
+            .unwrap_or(0);
+    }
+
+    /// the last cell path members of all the matches in the current view
+    pub(super) fn matches_in_view(&self) -> Vec<PathMember> {
+        let view_path = self.view_path();
+        self.matches
+            .iter()
+            .filter(|m| !m.path.is_empty() && m.path[..m.path.len() - 1] == *view_path)
+            .map(|m| m.path[m.path.len() - 1].clone())
+            .collect()
+    }
+
+    /// explore the string under the cursor as the structured data it contains, see
+    /// [`crate::file::parse_string`]
+    ///
+    /// the parsed data replaces the explored value until [`App::leave_parsed_view`], e.g. to
+    /// peek parts of it, and can not be edited.
+    pub(super) fn parse_string(&mut self) -> Result<(), String> {
+        if self.split.is_some() {
+            return Err("can not parse a string with a split screen".into());
+        }
+        let value = match follow(&self.value, &self.position.members).as_deref() {
+            Some(Value::String { val, .. }) => parse_string(val)?,
+            Some(x) => return Err(format!("can only parse strings, found {}", x.get_type())),
+            None => return Err("nothing to parse".into()),
+        };
+
+        self.enter_derived_view(value, None);
+        Ok(())
+    }
+
+    /// explore a *value* computed from the current data, e.g. parsed from one of its strings,
+    /// until [`App::leave_parsed_view`]
+    fn enter_derived_view(&mut self, value: Value, grouped_by: Option<String>) {
+        let derived = Self::from_value(value);
+        self.parsed.push(ParsedView {
+            value: std::mem::replace(&mut self.value, derived.value),
+            position: std::mem::replace(&mut self.position, derived.position),
+            mode: std::mem::replace(&mut self.mode, Mode::Normal),
+            offset: std::mem::take(&mut self.offset),
+            offsets: std::mem::take(&mut self.offsets),
+            marks: std::mem::take(&mut self.marks),
+            column_depths: std::mem::take(&mut self.column_depths),
+            grouped_by,
+        });
+        self.column = None;
+        self.matches = vec![];
+        self.selected_match = 0;
+        self.diff_base = None;
+    }
+
+    /// explore the rows of the current table grouped by the values of a *column*, the column
+    /// under the cursor by default
+    ///
+    /// the groups are a record, from the string representation of each distinct value, in
+    /// order of first appearance, to the table of its rows. as with [`App::parse_string`], the
+    /// groups replace the explored value until [`App::ungroup`] and can not be edited.
+    ///
+    /// > :bulb: **Note**  
+    /// > computing the groups stops with an error after *max_groups* distinct values
+    ///
+    /// > see the tests for detailed examples
+    pub(super) fn group_by(
+        &mut self,
+        column: Option<&str>,
+        max_groups: usize,
+    ) -> Result<(), String> {
+        if self.split.is_some() {
+            return Err("can not group a table with a split screen".into());
+        }
+        let column = match column.map(String::from).or_else(|| self.focused_column()) {
+            Some(column) => column,
+            None => return Err("no column to group by".into()),
+        };
+
+        let rows = match follow(&self.value, self.view_path()).as_deref() {
+            Some(Value::List { vals, .. }) if vals.iter().all(|v| v.as_record().is_ok()) => {
+                vals.clone()
+            }
+            _ => return Err("can only group the rows of a table".into()),
+        };
+
+        let mut groups: Vec<(String, Vec<Value>)> = vec![];
+        // NOTE: the index of each group, to not look for it among all the groups at each row
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for (i, row) in rows.into_iter().enumerate() {
+            let key = match row.as_record().ok().and_then(|rec| rec.get(&column)) {
+                Some(val) => val.into_string(" ", &nu_protocol::Config::default()),
+                None => return Err(format!("row {} has no column `{}`", i, column)),
+            };
+            match index.get(&key) {
+                Some(&j) => groups[j].1.push(row),
+                None if groups.len() >= max_groups => {
+                    return Err(format!(
+                        "more than {} distinct values in column `{}`",
+                        max_groups, column
+                    ))
+                }
+                None => {
+                    index.insert(key.clone(), groups.len());
+                    groups.push((key, vec![row]));
+                }
+            }
+        }
+
+        let mut record = Record::new();
+        for (key, group) in groups {
+            record.push(key, Value::list(group, Span::unknown()));
+        }
+        self.enter_derived_view(Value::record(record, Span::unknown()), Some(column));
+
+        Ok(())
+    }
+
+    /// go back to the table the current view has been grouped from, see [`App::group_by`]
+    pub(super) fn ungroup(&mut self) -> Result<(), String> {
+        match self.parsed.last() {
+            Some(ParsedView {
+                grouped_by: Some(_),
+                ..
+            }) => {
+                self.leave_parsed_view();
+                Ok(())
+            }
+            _ => Err("the view is not grouped".into()),
+        }
+    }
+
+    /// go back to the string the current data has been parsed from, or to the table it has been
+    /// grouped from, if any, see [`App::parse_string`] and [`App::group_by`]
+    ///
+    /// `true` is returned when a parsed view has been left.
+    pub(super) fn leave_parsed_view(&mut self) -> bool {
+        let view = match self.parsed.pop() {
+            Some(view) => view,
+            None => return false,
+        };
+
+        self.value = view.value;
+        self.position = view.position;
+        self.mode = view.mode;
+        self.offset = view.offset;
+        self.offsets = view.offsets;
+        self.marks = view.marks;
+        self.column_depths = view.column_depths;
+        self.column = None;
+        self.matches = vec![];
+        self.selected_match = 0;
+        self.diff_base = None;
+        self.recenter = true;
+
+        true
+    }
+
+    /// the *cell path* in the data given as input, i.e. outside of the strings parsed as data
+    pub(crate) fn root_position(&self) -> &CellPath {
+        self.parsed
+            .first()
+            .map_or(&self.position, |view| &view.position)
+    }
+
+    /// go into INSERT mode to edit the cell under the cursor
+    ///
+    /// > :bulb: **Note**  
+    /// > in an empty record or list, a new element is inserted instead, see
+    /// > [`App::enter_insertion`]
     pub(super) fn enter_editor(&mut self) -> Result<(), String> {
-        let value = self
-            .value
-            .clone()
-            .follow_cell_path(&self.position.members, false)
-            .unwrap();
+        if !self.parsed.is_empty() {
+            return Err("the data parsed from a string can not be edited".into());
+        }
+        if matches!(
+            self.position.members.last(),
+            Some(
+                PathMember::Int { optional: true, .. } | PathMember::String { optional: true, .. }
+            )
+        ) && !self.is_at_bottom()
+        {
+            return self.enter_insertion();
+        }
+
+        let value = follow(&self.value, &self.position.members).unwrap();
 
-        if matches!(value, Value::String { .. }) {
+        if matches!(*value, Value::String { .. }) {
             self.mode = Mode::Insert;
             self.editor = Editor::from_value(&value);
 
@@ -113,4 +1349,848 @@ impl App {
             ))
         }
     }
+
+    /// go into INSERT mode to type a new key of the current record or a new item of the current
+    /// list, see [`App::insert`]
+    pub(super) fn enter_insertion(&mut self) -> Result<(), String> {
+        if !self.parsed.is_empty() {
+            return Err("the data parsed from a string can not be edited".into());
+        }
+        let title = match follow(&self.value, self.view_path()).as_deref() {
+            Some(Value::Record { .. }) if !self.is_at_bottom() => "New key",
+            Some(Value::List { .. }) if !self.is_at_bottom() => "New item",
+            _ => return Err("can only insert into a record or a list".into()),
+        };
+
+        self.mode = Mode::Insert;
+        self.editor = Editor::prompt(title);
+        self.insertion = true;
+
+        Ok(())
+    }
+
+    /// add the *text* typed after [`App::enter_insertion`] to the current view and put the
+    /// cursor on it
+    ///
+    /// - in a record, the text is either `key` or `key: value`, the value being an empty string
+    ///   by default
+    /// - in a list, the text is the new item, appended at the end
+    /// - a value of `{}` or `[]` is an empty record or list, to be filled in later, any other
+    ///   value is a string
+    ///
+    /// the new view is given back along with its *cell path*, to replace the current view in
+    /// the data.
+    ///
+    /// > see the tests for detailed examples
+    pub(super) fn insert(&mut self, text: &str) -> Result<(Value, CellPath), String> {
+        self.insertion = false;
+
+        let new_value = |text: &str| match text.trim() {
+            "{}" => Value::record(Record::new(), Span::unknown()),
+            "[]" => Value::list(vec![], Span::unknown()),
+            _ => Value::string(text.trim(), Span::unknown()),
+        };
+
+        let view_path = self.view_path().to_vec();
+        let (view, member) = match follow(&self.value, &view_path).as_deref() {
+            Some(Value::Record { val: rec, .. }) => {
+                let (key, value) = match text.split_once(':') {
+                    Some((key, value)) => (key.trim(), new_value(value)),
+                    None => (text.trim(), Value::string("", Span::unknown())),
+                };
+                if key.is_empty() {
+                    return Err("the new key is empty".into());
+                }
+                if rec.contains(key) {
+                    return Err(format!("key `{}` already exists", key));
+                }
+
+                let mut rec = rec.clone();
+                rec.push(key, value);
+                let member = PathMember::String {
+                    val: key.into(),
+                    span: Span::unknown(),
+                    optional: false,
+                };
+                (Value::record(rec, Span::unknown()), member)
+            }
+            Some(Value::List { vals, .. }) => {
+                let mut vals = vals.clone();
+                vals.push(new_value(text));
+                let member = PathMember::Int {
+                    val: vals.len() - 1,
+                    span: Span::unknown(),
+                    optional: false,
+                };
+                (Value::list(vals, Span::unknown()), member)
+            }
+            _ => return Err("can only insert into a record or a list".into()),
+        };
+
+        self.position.members = view_path.clone();
+        self.position.members.push(member);
+
+        Ok((view, CellPath { members: view_path }))
+    }
+
+    /// split the screen vertically, the new pane on the right starting where the current one
+    /// is, or close the split, keeping the focused pane
+    pub(super) fn toggle_split(&mut self) -> Result<(), String> {
+        if self.split.take().is_some() {
+            return Ok(());
+        }
+        if !self.parsed.is_empty() {
+            return Err("can not split the screen in the data parsed from a string".into());
+        }
+
+        self.split = Some(Split {
+            pane: Pane {
+                position: self.position.clone(),
+                mode: self.mode.clone(),
+                offset: self.offset,
+                column: self.column,
+                column_depths: self.column_depths.clone(),
+                table_cache: None,
+                rows_cache: None,
+            },
+            right: false,
+            swapped: false,
+        });
+
+        Ok(())
+    }
+
+    /// move the focus to the other pane of the split screen
+    pub(super) fn switch_pane(&mut self) -> Result<(), String> {
+        self.swap_panes();
+        match &mut self.split {
+            Some(split) => {
+                split.swapped = false;
+                split.right = !split.right;
+                Ok(())
+            }
+            None => Err("the screen is not split".into()),
+        }
+    }
+
+    /// exchange the state of the [`App`] with the one of the pane that is not focused, e.g. to
+    /// render it, and back
+    ///
+    /// > :bulb: **Note**  
+    /// > a pane whose *cell path* does not exist anymore, e.g. after an edit in the other pane,
+    /// > starts again from where the other pane is
+    pub(crate) fn swap_panes(&mut self) {
+        let split = match &mut self.split {
+            Some(split) => split,
+            None => return,
+        };
+
+        let pane = &mut split.pane;
+        std::mem::swap(&mut self.position, &mut pane.position);
+        std::mem::swap(&mut self.mode, &mut pane.mode);
+        std::mem::swap(&mut self.offset, &mut pane.offset);
+        std::mem::swap(&mut self.column, &mut pane.column);
+        std::mem::swap(&mut self.column_depths, &mut pane.column_depths);
+        std::mem::swap(&mut self.table_cache, &mut pane.table_cache);
+        std::mem::swap(&mut self.rows_cache, &mut pane.rows_cache);
+        split.swapped = !split.swapped;
+
+        if follow(&self.value, &self.position.members).is_none() {
+            self.position = pane.position.clone();
+            self.mode = pane.mode.clone();
+            self.offset = pane.offset;
+            self.column = pane.column;
+            self.column_depths = pane.column_depths.clone();
+        }
+    }
+
+    /// the name of the column under the cursor in a table, if any
+    pub(super) fn focused_column(&self) -> Option<String> {
+        self.column
+            .and_then(|c| navigation::view_columns(self).get(c).cloned())
+    }
+
+    /// the *cell path* of the cell under the cursor, i.e. of the cell in the column under the
+    /// cursor when in a table
+    fn members_under(&self) -> Vec<PathMember> {
+        let mut members = self.position.members.clone();
+        if let Some(col) = self.focused_column() {
+            members.push(PathMember::String {
+                val: col,
+                span: Span::unknown(),
+                optional: false,
+            });
+        }
+        members
+    }
+
+    /// open the URL or the path under the cursor with the opener of the system, see
+    /// [`crate::open`]
+    ///
+    /// > :bulb: **Note**  
+    /// > nothing is ever opened unless *allowed*, see [`crate::config::Config::allow_open`]
+    pub(super) fn open_under(&mut self, allowed: bool) -> Result<(), String> {
+        if !allowed {
+            return Err("opening is disabled, see `allow_open` in the config".into());
+        }
+
+        let target = match follow(&self.value, &self.members_under()).as_deref() {
+            Some(Value::String { val, .. }) => match openable(val) {
+                Some(target) => target,
+                None => return Err(format!("`{}` is neither a URL nor an existing path", val)),
+            },
+            Some(x) => return Err(format!("can only open strings, found {}", x.get_type())),
+            None => return Err("nothing to open".into()),
+        };
+
+        open(&target)?;
+        self.status_message = Some(StatusMessage::Info(format!("opened `{}`", target)));
+        Ok(())
+    }
+
+    /// apply a *transform* to the string under the cursor, i.e. in the column under the cursor
+    /// when in a table, or to all the strings of that *column*
+    ///
+    /// the new string, or the new table, is given back along with its *cell path*, to replace it
+    /// in the data. the number of transformed and skipped cells of a column is given in the
+    /// status bar.
+    ///
+    /// > see the tests for detailed examples
+    pub(super) fn transform_strings(
+        &mut self,
+        transform: Transform,
+        column: bool,
+    ) -> Result<(Value, CellPath), String> {
+        if !self.parsed.is_empty() {
+            return Err("the data parsed from a string can not be edited".into());
+        }
+        if column {
+            let col = match self.focused_column() {
+                Some(col) => col,
+                None => return Err("no column under the cursor".into()),
+            };
+
+            let view_path = self.view_path().to_vec();
+            let rows = match follow(&self.value, &view_path).as_deref() {
+                Some(Value::List { vals, .. }) => vals.clone(),
+                _ => return Err("no column under the cursor".into()),
+            };
+
+            let (mut transformed, mut skipped) = (0, 0);
+            let rows = rows
+                .into_iter()
+                .map(|row| match row {
+                    Value::Record { val: mut rec, .. } => {
+                        match rec.get_mut(&col) {
+                            Some(Value::String { val, .. }) => {
+                                *val = transform.apply(val);
+                                transformed += 1;
+                            }
+                            _ => skipped += 1,
+                        }
+                        Value::record(rec, Span::unknown())
+                    }
+                    row => row,
+                })
+                .collect();
+
+            self.status_message = Some(StatusMessage::Info(format!(
+                "{}: {} cells transformed, {} skipped",
+                transform, transformed, skipped
+            )));
+            return Ok((
+                Value::list(rows, Span::unknown()),
+                CellPath { members: view_path },
+            ));
+        }
+
+        let members = self.members_under();
+        match follow(&self.value, &members).as_deref() {
+            Some(Value::String { val, .. }) => Ok((
+                Value::string(transform.apply(val), Span::unknown()),
+                CellPath { members },
+            )),
+            Some(x) => Err(format!(
+                "can only transform strings, found {}",
+                x.get_type()
+            )),
+            None => Err("can only transform strings, found nothing".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        rc::Rc,
+        time::{Duration, Instant},
+    };
+
+    use crossterm::event::KeyCode;
+    use nu_protocol::{ast::CellPath, record, Record, Value};
+
+    use super::{App, Mode, StatusMessage};
+    use crate::{
+        config::AccelerationConfig,
+        navigation::{go_back_in_data, go_deeper_in_data, go_up_or_down_in_data, Direction},
+        nu::{
+            cell_path::{to_path_member_vec, PM},
+            strings::Transform,
+        },
+    };
+
+    #[test]
+    fn mark_and_peek_elements() {
+        let value = Value::test_record(record! {
+            "l" => Value::test_list(vec![
+                Value::test_int(0),
+                Value::test_int(1),
+                Value::test_int(2),
+            ]),
+            "s" => Value::test_string("foo"),
+        });
+        let mut app = App::from_value(value);
+
+        assert_eq!(app.peek_marks(), None);
+
+        // mark `$.l` and then go into it
+        app.toggle_mark();
+        go_deeper_in_data(&mut app);
+
+        // mark `$.l.2` and then `$.l.0`
+        go_up_or_down_in_data(&mut app, Direction::Up);
+        app.toggle_mark();
+        go_up_or_down_in_data(&mut app, Direction::Down);
+        app.toggle_mark();
+
+        assert_eq!(
+            app.marks_in_view(),
+            to_path_member_vec(&[PM::I(2), PM::I(0)])
+        );
+        assert_eq!(
+            app.peek_marks(),
+            Some(Value::test_list(vec![
+                Value::test_int(0),
+                Value::test_int(2)
+            ]))
+        );
+
+        // unmark `$.l.0`
+        app.toggle_mark();
+        assert_eq!(
+            app.peek_marks(),
+            Some(Value::test_list(vec![Value::test_int(2)]))
+        );
+
+        // the marks of another view are not touched
+        app.clear_marks();
+        assert_eq!(app.peek_marks(), None);
+        assert_eq!(app.marks, vec![to_path_member_vec(&[PM::S("l")])]);
+    }
+
+    #[test]
+    fn reload_value() {
+        let value = Value::test_record(record! {
+            "a" => Value::test_list(vec![
+                Value::test_int(0),
+                Value::test_record(record! { "b" => Value::test_int(1) }),
+            ]),
+            "c" => Value::test_int(2),
+        });
+
+        #[rustfmt::skip]
+        let cases = vec![
+            // the position still exists
+            (
+                Value::test_record(record! {
+                    "a" => Value::test_list(vec![
+                        Value::test_int(0),
+                        Value::test_record(record! { "b" => Value::test_int(10) }),
+                    ]),
+                }),
+                vec![PM::S("a"), PM::I(1), PM::S("b")],
+            ),
+            // only a prefix of the position exists
+            (
+                Value::test_record(record! {
+                    "a" => Value::test_list(vec![Value::test_int(0), Value::test_int(1)]),
+                }),
+                vec![PM::S("a"), PM::I(1)],
+            ),
+            (
+                Value::test_record(record! {
+                    "a" => Value::test_list(vec![Value::test_int(0)]),
+                }),
+                vec![PM::S("a")],
+            ),
+            // nothing exists anymore
+            (
+                Value::test_list(vec![Value::test_int(0)]),
+                vec![PM::I(0)],
+            ),
+        ];
+
+        for (new, expected) in cases {
+            let mut app = App::from_value(value.clone());
+            app.position.members = to_path_member_vec(&[PM::S("a"), PM::I(1), PM::S("b")]);
+            app.hit_bottom();
+
+            app.reload(new.clone());
+
+            assert_eq!(*app.value, new);
+            assert_eq!(
+                app.position.members,
+                to_path_member_vec(&expected),
+                "unexpected position after reloading {:?}",
+                new
+            );
+            let expected_mode = if expected.len() == 3 {
+                Mode::Bottom
+            } else {
+                Mode::Normal
+            };
+            assert_eq!(app.mode, expected_mode);
+        }
+    }
+
+    #[test]
+    fn reload_removes_stale_marks() {
+        let value = Value::test_list(vec![
+            Value::test_int(0),
+            Value::test_int(1),
+            Value::test_int(2),
+        ]);
+        let mut app = App::from_value(value);
+        app.marks = vec![
+            to_path_member_vec(&[PM::I(0)]),
+            to_path_member_vec(&[PM::I(2)]),
+        ];
+
+        app.reload(Value::test_list(vec![
+            Value::test_int(0),
+            Value::test_int(1),
+        ]));
+        assert_eq!(app.marks, vec![to_path_member_vec(&[PM::I(0)])]);
+    }
+
+    #[test]
+    fn accelerate() {
+        let config = AccelerationConfig {
+            enabled: true,
+            interval: 100,
+            max_step: 4,
+        };
+        let mut app = App::default();
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // NOTE: the step doubles every 4 repetitions, up to the maximum step
+        let steps: Vec<usize> = (0..14)
+            .map(|i| app.accelerate(KeyCode::Char('j'), at(i * 50), &config))
+            .collect();
+        assert_eq!(steps, vec![1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 4]);
+        assert_eq!(app.step, 4);
+
+        // NOTE: a pause or another key starts over
+        assert_eq!(app.accelerate(KeyCode::Char('j'), at(1000), &config), 1);
+        for i in 0..8 {
+            app.accelerate(KeyCode::Char('j'), at(1000 + i * 10), &config);
+        }
+        assert_eq!(app.accelerate(KeyCode::Char('k'), at(1090), &config), 1);
+        app.accelerate(KeyCode::Char('k'), at(1100), &config);
+        app.stop_accelerating();
+        assert_eq!(app.step, 1);
+
+        let config = AccelerationConfig {
+            enabled: false,
+            ..config
+        };
+        for i in 0..8 {
+            assert_eq!(app.accelerate(KeyCode::Char('j'), at(i), &config), 1);
+        }
+    }
+
+    #[test]
+    fn parse_modes() {
+        #[rustfmt::skip]
+        let cases = vec![
+            ("normal", Ok(Mode::Normal)),
+            ("INSERT", Ok(Mode::Insert)),
+            ("Peeking", Ok(Mode::Peeking)),
+            ("goto", Ok(Mode::Goto)),
+            ("search", Ok(Mode::Search)),
+            ("command", Ok(Mode::Command)),
+            ("bottom", Err("expected one of normal, insert, peeking, goto, search or command, found `bottom`".into())),
+            ("", Err("expected one of normal, insert, peeking, goto, search or command, found ``".into())),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(input.parse::<Mode>(), expected, "parsing {:?}", input);
+        }
+    }
+
+    #[test]
+    fn insert_new_elements() {
+        let mut app = App::from_value(Value::test_record(record! {}));
+        app.enter_editor().unwrap();
+        assert!(app.mode == Mode::Insert);
+        assert!(app.insertion);
+
+        let (view, path) = app.insert("name: foo").unwrap();
+        assert!(!app.insertion);
+        assert_eq!(
+            view,
+            Value::test_record(record! { "name" => Value::test_string("foo") })
+        );
+        assert!(path.members.is_empty());
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::S("name")]));
+        app.value = Rc::new(view);
+
+        #[rustfmt::skip]
+        let cases = vec![
+            ("tags: []", "tags", Value::test_list(vec![])),
+            ("  empty  ", "empty", Value::test_string("")),
+            ("sub:{}", "sub", Value::test_record(record! {})),
+        ];
+        for (text, key, expected) in cases {
+            app.enter_insertion().unwrap();
+            let (view, _) = app.insert(text).unwrap();
+            assert_eq!(
+                view.get_data_by_key(key),
+                Some(expected),
+                "inserting {:?}",
+                text
+            );
+            assert_eq!(app.position.members, to_path_member_vec(&[PM::S(key)]));
+            app.value = Rc::new(view);
+        }
+
+        assert_eq!(
+            app.insert("name: bar"),
+            Err("key `name` already exists".into())
+        );
+        assert_eq!(app.insert(": bar"), Err("the new key is empty".into()));
+
+        // NOTE: in the empty list of `$.tags`
+        go_up_or_down_in_data(&mut app, Direction::Up);
+        go_up_or_down_in_data(&mut app, Direction::Up);
+        go_deeper_in_data(&mut app);
+        assert_eq!(app.enter_editor(), Ok(()));
+        let (view, path) = app.insert("a: b").unwrap();
+        assert_eq!(view, Value::test_list(vec![Value::test_string("a: b")]));
+        assert_eq!(path.members, to_path_member_vec(&[PM::S("tags")]));
+        assert_eq!(
+            app.position.members,
+            to_path_member_vec(&[PM::S("tags"), PM::I(0)])
+        );
+
+        let mut app = App::from_value(Value::test_string("foo"));
+        assert_eq!(
+            app.enter_insertion(),
+            Err("can only insert into a record or a list".into())
+        );
+    }
+
+    #[test]
+    fn pin_columns() {
+        let row = Value::test_record(record! {
+            "a" => Value::test_int(1),
+            "b" => Value::test_int(2),
+        });
+        let mut app = App::from_value(Value::test_record(record! {
+            "t" => Value::test_list(vec![row.clone(), row]),
+        }));
+        go_deeper_in_data(&mut app);
+        let table = to_path_member_vec(&[PM::S("t")]);
+
+        assert_eq!(app.toggle_pin(), Err("no column under the cursor".into()));
+
+        app.column = Some(1);
+        app.toggle_pin().unwrap();
+        assert_eq!(app.table_settings(&table).pinned, Some("b".into()));
+        app.column = Some(0);
+        app.toggle_pin().unwrap();
+        assert_eq!(app.table_settings(&table).pinned, Some("a".into()));
+        app.toggle_pin().unwrap();
+        assert_eq!(app.table_settings(&table).pinned, None);
+
+        // NOTE: the settings are kept per table
+        app.toggle_pin().unwrap();
+        assert_eq!(app.table_settings(&[]).pinned, None);
+        assert_eq!(app.table_settings.len(), 1);
+    }
+
+    #[test]
+    fn mark_and_peek_duplicate_columns() {
+        let mut rec = Record::new();
+        for (col, val) in [("x", 1), ("x", 2), ("x", 3)] {
+            rec.push(col, Value::test_int(val));
+        }
+        let mut app = App::from_value(Value::test_record(rec));
+
+        app.toggle_mark();
+        go_up_or_down_in_data(&mut app, Direction::Up);
+        app.toggle_mark();
+        assert_eq!(
+            app.marks_in_view(),
+            to_path_member_vec(&[PM::I(0), PM::I(2)])
+        );
+        assert_eq!(
+            app.peek_marks(),
+            Some(Value::test_list(vec![
+                Value::test_int(1),
+                Value::test_int(3)
+            ]))
+        );
+    }
+
+    #[test]
+    fn transform_strings() {
+        let row = |name: &str| {
+            Value::test_record(record! {
+                "name" => Value::test_string(name),
+                "id" => Value::test_int(1),
+            })
+        };
+        let mut app = App::from_value(Value::test_record(record! {
+            "t" => Value::test_list(vec![
+                row("  foo "),
+                Value::test_record(record! { "name" => Value::test_nothing() }),
+                row("bar baz"),
+            ]),
+        }));
+
+        assert_eq!(
+            app.transform_strings(Transform::Upper, false),
+            Err("can only transform strings, found list<any>".into())
+        );
+
+        go_deeper_in_data(&mut app);
+        assert_eq!(
+            app.transform_strings(Transform::Upper, true),
+            Err("no column under the cursor".into())
+        );
+
+        app.column = Some(0);
+        assert_eq!(
+            app.transform_strings(Transform::Trim, false),
+            Ok((
+                Value::test_string("foo"),
+                nu_protocol::ast::CellPath {
+                    members: to_path_member_vec(&[PM::S("t"), PM::I(0), PM::S("name")])
+                }
+            ))
+        );
+
+        let (table, path) = app.transform_strings(Transform::Titlecase, true).unwrap();
+        assert_eq!(path.members, to_path_member_vec(&[PM::S("t")]));
+        assert_eq!(
+            table,
+            Value::test_list(vec![
+                row("  Foo "),
+                Value::test_record(record! { "name" => Value::test_nothing() }),
+                row("Bar Baz"),
+            ])
+        );
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info(
+                "titlecase: 2 cells transformed, 1 skipped".into()
+            ))
+        );
+
+        app.column = Some(1);
+        assert_eq!(
+            app.transform_strings(Transform::Lower, false),
+            Err("can only transform strings, found int".into())
+        );
+    }
+
+    #[test]
+    fn split_panes() {
+        let mut app = App::from_value(Value::test_record(record! {
+            "a" => Value::test_record(record! { "x" => Value::test_int(1) }),
+            "b" => Value::test_string(r#"{"y": 2}"#),
+        }));
+        let a = to_path_member_vec(&[PM::S("a")]);
+        let ax = to_path_member_vec(&[PM::S("a"), PM::S("x")]);
+        let b = to_path_member_vec(&[PM::S("b")]);
+
+        assert_eq!(app.switch_pane(), Err("the screen is not split".into()));
+
+        app.toggle_split().unwrap();
+        go_deeper_in_data(&mut app);
+        assert_eq!(app.position.members, ax);
+
+        // NOTE: each pane has its own position
+        app.switch_pane().unwrap();
+        assert_eq!(app.position.members, a);
+        go_up_or_down_in_data(&mut app, Direction::Down);
+        assert_eq!(app.position.members, b);
+        assert!(app.split.as_ref().unwrap().right);
+        assert_eq!(app.split.as_ref().unwrap().other_position().members, ax);
+        assert_eq!(
+            app.parse_string(),
+            Err("can not parse a string with a split screen".into())
+        );
+
+        app.switch_pane().unwrap();
+        assert_eq!(app.position.members, ax);
+        assert!(!app.split.as_ref().unwrap().right);
+
+        // NOTE: a pane that does not exist anymore starts again from the other one
+        app.value = Rc::new(Value::test_record(record! {
+            "a" => Value::test_record(record! { "x" => Value::test_int(1) }),
+        }));
+        app.switch_pane().unwrap();
+        assert_eq!(app.position.members, ax);
+
+        // NOTE: closing the split keeps the focused pane
+        go_back_in_data(&mut app);
+        app.toggle_split().unwrap();
+        assert!(app.split.is_none());
+        assert_eq!(app.position.members, a);
+    }
+
+    #[test]
+    fn open_only_when_allowed() {
+        let mut app = App::from_value(Value::test_record(record! {
+            "s" => Value::test_string("not a url"),
+            "i" => Value::test_int(1),
+        }));
+
+        assert_eq!(
+            app.open_under(false),
+            Err("opening is disabled, see `allow_open` in the config".into())
+        );
+        assert_eq!(
+            app.open_under(true),
+            Err("`not a url` is neither a URL nor an existing path".into())
+        );
+        go_up_or_down_in_data(&mut app, Direction::Down);
+        assert_eq!(
+            app.open_under(true),
+            Err("can only open strings, found int".into())
+        );
+        assert_eq!(app.status_message, None);
+    }
+
+    #[test]
+    fn group_rows_by_column() {
+        let row = |name: &str, kind: &str| {
+            Value::test_record(record! {
+                "name" => Value::test_string(name),
+                "kind" => Value::test_string(kind),
+            })
+        };
+        let table = vec![row("a", "x"), row("b", "y"), row("c", "x")];
+        let value = Value::test_record(record! {
+            "t" => Value::test_list(table.clone()),
+            "n" => Value::test_int(1),
+        });
+        let mut app = App::from_value(value.clone());
+
+        assert_eq!(
+            app.group_by(Some("kind"), 10),
+            Err("can only group the rows of a table".into())
+        );
+
+        go_deeper_in_data(&mut app);
+        go_up_or_down_in_data(&mut app, Direction::Down);
+        let position = app.position.clone();
+        assert_eq!(app.group_by(None, 10), Err("no column to group by".into()));
+        assert_eq!(
+            app.group_by(Some("kind"), 1),
+            Err("more than 1 distinct values in column `kind`".into())
+        );
+        assert_eq!(
+            app.group_by(Some("size"), 10),
+            Err("row 0 has no column `size`".into())
+        );
+        assert_eq!(app.ungroup(), Err("the view is not grouped".into()));
+
+        app.column = Some(1);
+        app.group_by(None, 10).unwrap();
+        assert_eq!(
+            *app.value,
+            Value::test_record(record! {
+                "x" => Value::test_list(vec![row("a", "x"), row("c", "x")]),
+                "y" => Value::test_list(vec![row("b", "y")]),
+            })
+        );
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::S("x")]));
+        assert_eq!(app.parsed[0].grouped_by, Some("kind".into()));
+
+        // NOTE: the original data is untouched and explored again once ungrouped
+        app.ungroup().unwrap();
+        assert_eq!(*app.value, value);
+        assert_eq!(app.position, position);
+        assert!(app.parsed.is_empty());
+    }
+
+    #[test]
+    fn validate_rows() {
+        let row = |age: Value| Value::test_record(record! { "age" => age });
+        let value = Value::test_list(vec![
+            row(Value::test_int(1)),
+            row(Value::test_string("x")),
+            row(Value::test_int(3)),
+            row(Value::test_nothing()),
+        ]);
+        let mut app = App::from_value(value);
+
+        assert_eq!(
+            app.validate_view(),
+            Err("no schema to validate against, see `--expect`".into())
+        );
+
+        app.validation = Some(record! { "age" => Value::test_string("int") });
+        assert_eq!(app.failures_in_view(), None);
+
+        // NOTE: the view is validated on the first jump
+        assert_eq!(app.jump_to_failure(true), Ok(()));
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::I(1)]));
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info(
+                "row 1: `age` is string, expected int".into()
+            ))
+        );
+        assert_eq!(
+            app.failures_in_view()
+                .map(|f| f.iter().map(|(i, _)| *i).collect()),
+            Some(vec![1, 3])
+        );
+
+        #[rustfmt::skip]
+        let jumps = vec![(true, 3), (true, 1), (false, 3), (false, 1)];
+        for (next, expected) in jumps {
+            app.jump_to_failure(next).unwrap();
+            assert_eq!(app.position.members, to_path_member_vec(&[PM::I(expected)]));
+        }
+
+        // NOTE: the edits are validated again
+        let path = CellPath {
+            members: to_path_member_vec(&[PM::I(1), PM::S("age")]),
+        };
+        app.apply_mutation(&Value::test_int(2), &path);
+        assert_eq!(
+            app.failures_in_view()
+                .map(|f| f.iter().map(|(i, _)| *i).collect()),
+            Some(vec![3])
+        );
+
+        app.validate_view().unwrap();
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info("1 failing rows".into()))
+        );
+
+        app.validation = Some(record! { "age" => Value::test_string("int?") });
+        app.validate_view().unwrap();
+        assert_eq!(
+            app.jump_to_failure(true),
+            Err("no failing row in the view".into())
+        );
+    }
 }
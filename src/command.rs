@@ -0,0 +1,139 @@
+//! the commands that can be typed in COMMAND mode (see [`crate::app::Mode::Command`])
+use crate::nu::strings::Transform;
+
+/// a command typed in COMMAND mode, e.g. `write file.json`
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Command {
+    /// write the value under the cursor, or the whole data, to a file
+    ///
+    /// the `write!` form writes the whole data and overwrites the file if it exists, otherwise
+    /// the user is asked for a confirmation before overwriting anything.
+    Write { path: String, all: bool },
+    /// forget the matches of the last search, as `:nohlsearch` in Vim
+    ClearSearch,
+    /// quit the application
+    ///
+    /// the `quit!` form discards the edits without asking, otherwise the user is asked for a
+    /// confirmation before discarding anything.
+    Quit { force: bool },
+    /// peek what's under the cursor, or the range being selected, and quit at once
+    ///
+    /// the `peek!` form discards the edits without asking, otherwise the user is asked for a
+    /// confirmation before discarding anything.
+    Peek { force: bool },
+    /// delete the elements of the range being selected in the current list
+    ///
+    /// the `delete-range!` form deletes them without asking, otherwise the user is asked for a
+    /// confirmation before deleting anything.
+    DeleteRange { force: bool },
+    /// transform the string under the cursor, e.g. `upper` or `trim`
+    ///
+    /// the `upper!` form transforms all the strings of the column under the cursor in a table,
+    /// the other cells of the column being skipped, after a confirmation of the user.
+    Transform { transform: Transform, column: bool },
+    /// group the rows of the current table by the values of a column, the column under the
+    /// cursor by default
+    GroupBy { column: Option<String> },
+    /// go back to the table a grouped view has been computed from
+    Ungroup,
+}
+
+impl Command {
+    /// parse a command, with or without the leading `:`
+    ///
+    /// > see the tests for detailed examples
+    pub(crate) fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        let input = input.strip_prefix(':').unwrap_or(input);
+
+        let (name, args) = match input.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (input, ""),
+        };
+
+        match name {
+            "write" | "w" | "write!" | "w!" => {
+                if args.is_empty() {
+                    return Err(format!("`{}` requires a path", name));
+                }
+
+                Ok(Self::Write {
+                    path: args.to_string(),
+                    all: name.ends_with('!'),
+                })
+            }
+            "nohlsearch" | "nohl" | "noh" => Ok(Self::ClearSearch),
+            "group-by" => Ok(Self::GroupBy {
+                column: (!args.is_empty()).then(|| args.to_string()),
+            }),
+            "ungroup" => Ok(Self::Ungroup),
+            "quit" | "q" | "quit!" | "q!" => Ok(Self::Quit {
+                force: name.ends_with('!'),
+            }),
+            "delete-range" | "delete-range!" => Ok(Self::DeleteRange {
+                force: name.ends_with('!'),
+            }),
+            "peek" | "peek!" => Ok(Self::Peek {
+                force: name.ends_with('!'),
+            }),
+            "" => Err("no command given".into()),
+            x => match Transform::parse(x.strip_suffix('!').unwrap_or(x)) {
+                Some(transform) => Ok(Self::Transform {
+                    transform,
+                    column: x.ends_with('!'),
+                }),
+                None => Err(format!("unknown command `{}`", x)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Command;
+    use crate::nu::strings::Transform;
+
+    #[test]
+    fn parse_commands() {
+        let write = |path: &str, all: bool| {
+            Ok(Command::Write {
+                path: path.into(),
+                all,
+            })
+        };
+
+        #[rustfmt::skip]
+        let cases = vec![
+            ("write foo.json", write("foo.json", false)),
+            (":write foo.json", write("foo.json", false)),
+            ("  w   foo bar.json  ", write("foo bar.json", false)),
+            ("write! foo.json", write("foo.json", true)),
+            (":w! foo.json", write("foo.json", true)),
+            ("write", Err("`write` requires a path".into())),
+            (":nohl", Ok(Command::ClearSearch)),
+            ("nohlsearch", Ok(Command::ClearSearch)),
+            (":q", Ok(Command::Quit { force: false })),
+            ("quit!", Ok(Command::Quit { force: true })),
+            (":q! foo", Ok(Command::Quit { force: true })),
+            (":peek", Ok(Command::Peek { force: false })),
+            ("peek!", Ok(Command::Peek { force: true })),
+            (":delete-range", Ok(Command::DeleteRange { force: false })),
+            ("delete-range!", Ok(Command::DeleteRange { force: true })),
+            (":upper", Ok(Command::Transform { transform: Transform::Upper, column: false })),
+            ("titlecase!", Ok(Command::Transform { transform: Transform::Titlecase, column: true })),
+            (":trim", Ok(Command::Transform { transform: Transform::Trim, column: false })),
+            (":lower!", Ok(Command::Transform { transform: Transform::Lower, column: true })),
+            (":upper!!", Err("unknown command `upper!!`".into())),
+            (":group-by name", Ok(Command::GroupBy { column: Some("name".into()) })),
+            ("group-by", Ok(Command::GroupBy { column: None })),
+            (":ungroup", Ok(Command::Ungroup)),
+            ("", Err("no command given".into())),
+            (":", Err("no command given".into())),
+            ("foo bar", Err("unknown command `foo`".into())),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(Command::parse(input), expected, "parsing {:?}", input);
+        }
+    }
+}
@@ -4,6 +4,8 @@
 //! 1. holds the data structure of the [`Config`]
 //! 1. gives default values to a [`Config`] with [`Config::default`]
 //! 1. parses a Nushell [`Value`](https://docs.rs/nu-protocol/0.83.1/nu_protocol/enum.Value.html) into a valid [`Config`]
+use std::collections::HashMap;
+
 use crossterm::event::KeyCode;
 use ratatui::style::{Color, Modifier};
 
@@ -11,9 +13,11 @@ use nu_plugin::LabeledError;
 use nu_protocol::Value;
 
 mod parsing;
+pub use parsing::parse_keys;
 use parsing::{
-    follow_cell_path, invalid_field, invalid_type, try_bool, try_fg_bg_colors, try_key, try_layout,
-    try_modifier, try_string,
+    follow_cell_path, invalid_field, invalid_type, try_bool, try_color, try_column_width,
+    try_fg_bg_colors, try_key, try_layout, try_log_level, try_modifier, try_string,
+    try_substitutions, try_usize,
 };
 
 /// the configuration for the status bar colors in all [`crate::app::Mode`]s
@@ -23,6 +27,9 @@ pub struct StatusBarColorConfig {
     pub insert: BgFgColorConfig,
     pub peek: BgFgColorConfig,
     pub bottom: BgFgColorConfig,
+    pub goto: BgFgColorConfig,
+    pub search: BgFgColorConfig,
+    pub command: BgFgColorConfig,
 }
 
 /// the configuration for a row of the data rendering table
@@ -48,12 +55,22 @@ pub struct EditorColorConfig {
 pub struct ColorConfig {
     /// the color when a row is NOT selected
     pub normal: TableRowColorConfig,
+    /// the background of the even rows, counting from the first element of the data
+    pub row: Color,
+    /// the background of the odd rows, to stripe the data
+    pub row_alt: Color,
     /// the color when a row is selected
     pub selected: BgFgColorConfig,
     /// the modifier to apply to the row under the cursor
     pub selected_modifier: Modifier,
+    /// the modifier to apply to the header of the tables, to tell it apart from the rows
+    pub header_modifier: Modifier,
     /// the symbol to show to the left of the selected row under the cursor
     pub selected_symbol: String,
+    /// the color of the marked rows
+    pub marked: BgFgColorConfig,
+    /// the symbol to show to the left of the marked rows
+    pub marked_symbol: String,
     pub status_bar: StatusBarColorConfig,
     /// the color when editing a cell
     pub editor: EditorColorConfig,
@@ -77,6 +94,10 @@ pub struct NavigationBindingsMap {
     pub left: KeyCode,
     /// go one level deeper in the data
     pub right: KeyCode,
+    /// move the column cursor of a table one column to the right
+    pub next_column: KeyCode,
+    /// move the column cursor of a table one column to the left
+    pub previous_column: KeyCode,
 }
 
 /// the bindings in PEEKING mode (see [crate::app::Mode::Peeking])
@@ -84,12 +105,19 @@ pub struct NavigationBindingsMap {
 pub struct PeekingBindingsMap {
     /// peek the whole data structure
     pub all: KeyCode,
-    /// peek the current cell path
+    /// peek the current cell path, unless it goes through a duplicate column
     pub cell_path: KeyCode,
     /// peek the current level, but only the row under the cursor
     pub under: KeyCode,
     /// peek the current view
     pub view: KeyCode,
+    /// peek the marked elements of the current view
+    pub marks: KeyCode,
+    /// peek the text on the screen, as it is rendered
+    pub screen: KeyCode,
+    /// peek the table under the cursor as a record of its columns (see
+    /// [crate::nu::value::to_columns])
+    pub columns: KeyCode,
 }
 
 /// the keybindings mapping
@@ -103,8 +131,297 @@ pub struct KeyBindingsMap {
     pub navigation: NavigationBindingsMap,
     /// go into PEEKING mode (see [crate::app::Mode::Peeking])
     pub peek: KeyCode,
+    /// peek what's under the cursor and quit at once, without going into PEEKING mode, like
+    /// [`PeekingBindingsMap::under`] but asking before discarding the edits as `q` does
+    pub peek_under_quick: KeyCode,
     pub peeking: PeekingBindingsMap,
     pub transpose: KeyCode,
+    /// go into GOTO mode (see [crate::app::Mode::Goto])
+    pub goto: KeyCode,
+    /// mark or unmark the element under the cursor
+    pub mark: KeyCode,
+    /// unmark all the elements of the current view
+    pub clear_marks: KeyCode,
+    /// go into SEARCH mode (see [crate::app::Mode::Search])
+    pub search: KeyCode,
+    /// toggle between the formatted and the raw representation of the simple values
+    pub raw: KeyCode,
+    /// go into COMMAND mode (see [crate::app::Mode::Command])
+    pub command: KeyCode,
+    /// go into COMMAND mode to write the value under the cursor to a file
+    pub write: KeyCode,
+    /// set the element under the cursor as the base of a diff, or diff it against the base
+    pub diff: KeyCode,
+    /// jump to the next match of the last search
+    pub next_match: KeyCode,
+    /// jump to the previous match of the last search
+    pub previous_match: KeyCode,
+    /// show the statistics of the numeric column under the cursor in a table
+    pub stats: KeyCode,
+    /// go into INSERT mode to add a new key to the current record or a new item to the current
+    /// list (see [crate::app::App::enter_insertion])
+    pub append: KeyCode,
+    /// pin the column under the cursor to the left edge of the table, or unpin it
+    pub pin: KeyCode,
+    /// explore the string under the cursor as structured data, e.g. JSON (see
+    /// [crate::app::App::parse_string])
+    pub parse: KeyCode,
+    /// show one more level of the lists and records in the previews
+    pub deeper_preview: KeyCode,
+    /// show one less level of the lists and records in the previews
+    pub shallower_preview: KeyCode,
+    /// split the screen vertically, or close the split (see [crate::app::App::toggle_split])
+    pub split: KeyCode,
+    /// move the focus to the other pane of the split screen
+    pub other_pane: KeyCode,
+    /// open the URL or the path under the cursor with the opener of the system, see
+    /// [`Config::allow_open`]
+    pub open: KeyCode,
+    /// start recording the keys into the register typed next, or stop recording, only in NORMAL
+    /// mode
+    pub record_macro: KeyCode,
+    /// replay the keys of the register typed next, the last replayed one again when typed twice,
+    /// as many times as the number typed before, only in NORMAL mode
+    pub replay_macro: KeyCode,
+    /// check the rows of the current table against the schema given with `--expect` (see
+    /// [crate::app::App::validate_view])
+    pub validate: KeyCode,
+    /// jump to the next row of the current table that fails the validation
+    pub next_failure: KeyCode,
+    /// jump to the previous row of the current table that fails the validation
+    pub previous_failure: KeyCode,
+    /// jump to the same place in the next item of the nearest list above the cursor (see
+    /// [crate::navigation::go_to_ancestor_sibling])
+    pub next_sibling: KeyCode,
+    /// jump to the same place in the previous item of the nearest list above the cursor
+    pub previous_sibling: KeyCode,
+    /// show the type and the span of the value under the cursor, and the text of the source it
+    /// comes from (see [crate::nu::source::source_info])
+    pub info: KeyCode,
+    /// go deeper and keep going through the containers with a single child, whatever
+    /// [`NavigationConfig::auto_descend`]
+    pub descend: KeyCode,
+    /// cycle between the strategies of the widths of the columns of the tables (see
+    /// [`ColumnWidth::next`])
+    pub column_width: KeyCode,
+    /// start selecting a range of a list at the cursor, or cancel it (see
+    /// [crate::app::App::toggle_range])
+    pub range: KeyCode,
+    /// remove all the elements of the selected range of a list, after a confirmation
+    pub delete_range: KeyCode,
+}
+
+/// the limits of the deep search (see [crate::app::Mode::Search])
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchConfig {
+    /// the maximum number of levels to walk down into the data
+    pub max_depth: usize,
+    /// the number of matches after which the search stops
+    pub max_matches: usize,
+    /// binary values larger than this number of bytes are skipped
+    pub max_binary_size: usize,
+}
+
+/// the limits of the diff between two elements
+#[derive(Clone, PartialEq, Debug)]
+pub struct DiffConfig {
+    /// the maximum number of levels to compare, deeper elements are compared as a whole
+    pub max_depth: usize,
+    /// the number of differences after which the comparison stops
+    pub max_differences: usize,
+}
+
+/// the limits of the schema of the data, see `--schema`
+#[derive(Clone, PartialEq, Debug)]
+pub struct SchemaConfig {
+    /// the maximum number of items of each list used to compute the type of its items
+    pub max_samples: usize,
+}
+
+/// the limits of the grouping of a table, see `:group-by`
+#[derive(Clone, PartialEq, Debug)]
+pub struct GroupConfig {
+    /// the maximum number of distinct values of the column, i.e. of groups
+    pub max_groups: usize,
+}
+
+/// the configuration of the navigation in the data
+#[derive(Clone, PartialEq, Debug)]
+pub struct NavigationConfig {
+    /// keep going deeper when entering a container with a single child that is itself a
+    /// container, e.g. the `data` of `{data: [...]}` (see [crate::navigation::auto_descend])
+    pub auto_descend: bool,
+}
+
+/// the acceleration of the vertical navigation when a key is held down
+#[derive(Clone, PartialEq, Debug)]
+pub struct AccelerationConfig {
+    /// move by more than one row at a time when the same key is repeated quickly
+    pub enabled: bool,
+    /// the maximum number of milliseconds between two presses of the same key for the second
+    /// one to be a repetition
+    pub interval: usize,
+    /// the maximum number of rows to move by at once
+    pub max_step: usize,
+}
+
+/// the configuration of how the data is displayed
+#[derive(Clone, PartialEq, Debug)]
+pub struct DisplayConfig {
+    /// the maximum number of graphemes in the preview of a cell, `0` to never truncate
+    pub preview_length: usize,
+    /// render the colors of the ANSI escape sequences in strings instead of removing them
+    pub render_ansi: bool,
+    /// color the booleans in green and red and the paths to existing files as in
+    /// `$env.LS_COLORS`
+    pub colorize_values: bool,
+    /// show the consecutive identical simple values of a list as a single row
+    pub collapse_runs: bool,
+    /// the number of levels of the lists and records shown in their preview, `0` to only
+    /// summarize them, e.g. `[3 items]`
+    pub preview_depth: usize,
+    /// render without any color, the states of the rows being distinguished by modifiers only,
+    /// e.g. the selection is reversed, also enabled by a non-empty `$env.NO_COLOR`
+    pub monochrome: bool,
+    /// how the widths of the columns of a table are computed
+    pub column_width: ColumnWidth,
+    /// show the other keys of the parent record or the neighboring indices of the parent list
+    /// above a simple value at the bottom, the current one being highlighted
+    pub bottom_context: bool,
+    /// the texts shown instead of some simple values of some columns or types, see
+    /// [`Substitutions`]
+    pub substitutions: Substitutions,
+}
+
+/// the texts shown instead of some simple values, from the name of a column or of a type, e.g.
+/// `status` or `bool`, to a map from the default rendering of the values to the text to show
+/// instead, e.g. `{ok: "✔", error: "✘"}`
+///
+/// the substitutions of the column of a value come before the ones of its type, and the values
+/// that are in none of them are rendered as usual.
+///
+/// > :bulb: **Note**  
+/// > only the rendering changes, the values that are peeked or edited are the original ones
+///
+/// > :bulb: **Note**  
+/// > these are static lookups, the closures of `display.renderers` need plugins that can evaluate
+/// > closures, which the ones of Nushell 0.90 can not
+pub type Substitutions = HashMap<String, HashMap<String, String>>;
+
+/// how much is written to the log file, from the least to the most verbose
+#[derive(Clone, Copy, Default, PartialEq, PartialOrd, Debug)]
+pub enum LogLevel {
+    /// only the errors, e.g. an invalid command or a panic of the renderer
+    Error,
+    /// the errors, the actions other than `continue` and the changes of mode or *cell path*
+    #[default]
+    Info,
+    /// everything, including each key event
+    Debug,
+}
+
+/// the configuration of the log file, to help reproduce the bugs from user reports
+#[derive(Clone, PartialEq, Debug)]
+pub struct LogConfig {
+    /// the file to append the log lines to, nothing is logged when there is none
+    pub file: Option<String>,
+    pub level: LogLevel,
+}
+
+/// the narrowest a column of a table can be, see [`ColumnWidth::clamp`]
+pub const MIN_COLUMN_WIDTH: u16 = 3;
+/// the widest a column of a table can be, see [`ColumnWidth::clamp`]
+pub const MAX_COLUMN_WIDTH: u16 = 1000;
+
+/// the width of a column of a table, see [`ColumnWidth::Custom`]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WidthSpec {
+    /// a number of terminal columns
+    Fixed(u16),
+    /// a percentage of the width of the table
+    Percent(u16),
+}
+
+/// how the widths of the columns of a table are computed
+#[derive(Clone, Default, PartialEq, Debug)]
+pub enum ColumnWidth {
+    /// as wide as their content, up to the width of the table
+    #[default]
+    Auto,
+    /// all the same width, to fit all the columns in the table if possible
+    Equal,
+    /// the given widths, in column order, the other columns sharing what is left
+    Custom(Vec<WidthSpec>),
+}
+
+impl std::fmt::Display for ColumnWidth {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Equal => write!(f, "equal"),
+            Self::Custom(specs) => {
+                let specs: Vec<String> = specs
+                    .iter()
+                    .map(|spec| match spec {
+                        WidthSpec::Fixed(w) => w.to_string(),
+                        WidthSpec::Percent(p) => format!("{}%", p),
+                    })
+                    .collect();
+                write!(f, "[{}]", specs.join(", "))
+            }
+        }
+    }
+}
+
+impl ColumnWidth {
+    /// bring the custom widths back within sane bounds, with a warning for each one that was not
+    ///
+    /// - a fixed width is between [`MIN_COLUMN_WIDTH`] and [`MAX_COLUMN_WIDTH`]
+    /// - a percentage is between 1 and 100
+    ///
+    /// > see the tests for detailed examples
+    pub fn clamp(self) -> (Self, Vec<String>) {
+        let specs = match self {
+            Self::Custom(specs) => specs,
+            x => return (x, vec![]),
+        };
+
+        let mut warnings = vec![];
+        let specs = specs
+            .into_iter()
+            .enumerate()
+            .map(|(j, spec)| {
+                let clamped = match spec {
+                    WidthSpec::Fixed(w) => {
+                        WidthSpec::Fixed(w.clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH))
+                    }
+                    WidthSpec::Percent(p) => WidthSpec::Percent(p.clamp(1, 100)),
+                };
+                if clamped != spec {
+                    warnings.push(format!(
+                        "the width of column {} has been clamped to {}",
+                        j,
+                        Self::Custom(vec![clamped])
+                            .to_string()
+                            .trim_matches(|c| c == '[' || c == ']')
+                    ));
+                }
+                clamped
+            })
+            .collect();
+
+        (Self::Custom(specs), warnings)
+    }
+
+    /// the next strategy to try at runtime, from `auto` to `equal` to the *custom* widths of the
+    /// config, if any, and back to `auto`
+    pub fn next(&self, custom: &ColumnWidth) -> Self {
+        match (self, custom) {
+            (Self::Auto, _) => Self::Equal,
+            (Self::Equal, Self::Custom(_)) => custom.clone(),
+            _ => Self::Auto,
+        }
+    }
 }
 
 /// the layout of the application
@@ -113,6 +430,9 @@ pub enum Layout {
     /// show each row in a `[name, data, type]` column
     Table,
     /// show each row in compact form, to the left, `"{name}: ({type}) {data}"`
+    ///
+    /// the names of a record are aligned on the longest visible one, truncated in the middle
+    /// when too long
     Compact,
 }
 
@@ -124,6 +444,30 @@ pub struct Config {
     pub show_cell_path: bool,
     pub layout: Layout,
     pub show_table_header: bool,
+    /// show the key or the index of the current view in its parent, on top of the data
+    pub show_context: bool,
+    /// peek under the cursor when peeking the marks without any marked element, instead of
+    /// giving an error
+    pub peek_under_without_marks: bool,
+    /// keep the status bar at the bottom when peeking the screen
+    pub peek_status_bar: bool,
+    /// start from the last *cell path* of the previous exploration of the same data, see
+    /// [`crate::session`]
+    pub restore_session: bool,
+    /// disable all the edition of the data, e.g. INSERT mode, so that the data can only be
+    /// peeked, see `--read-only`
+    pub read_only: bool,
+    /// let the URLs and the paths of the data be opened with the opener of the system, e.g.
+    /// `xdg-open`, which launches external programs
+    pub allow_open: bool,
+    pub search: SearchConfig,
+    pub diff: DiffConfig,
+    pub schema: SchemaConfig,
+    pub group: GroupConfig,
+    pub navigation: NavigationConfig,
+    pub acceleration: AccelerationConfig,
+    pub display: DisplayConfig,
+    pub log: LogConfig,
 }
 
 impl Default for Config {
@@ -133,7 +477,47 @@ impl Default for Config {
         Self {
             show_cell_path: true,
             show_table_header: true,
+            show_context: false,
+            peek_under_without_marks: true,
+            peek_status_bar: false,
+            restore_session: true,
+            read_only: false,
+            allow_open: false,
             layout: Layout::Table,
+            display: DisplayConfig {
+                preview_length: 80,
+                render_ansi: false,
+                colorize_values: false,
+                collapse_runs: false,
+                preview_depth: 2,
+                monochrome: false,
+                column_width: ColumnWidth::Auto,
+                bottom_context: true,
+                substitutions: HashMap::new(),
+            },
+            search: SearchConfig {
+                max_depth: 16,
+                max_matches: 100,
+                max_binary_size: 1024 * 1024,
+            },
+            diff: DiffConfig {
+                max_depth: 16,
+                max_differences: 100,
+            },
+            schema: SchemaConfig { max_samples: 100 },
+            group: GroupConfig { max_groups: 1000 },
+            navigation: NavigationConfig {
+                auto_descend: false,
+            },
+            log: LogConfig {
+                file: None,
+                level: LogLevel::Info,
+            },
+            acceleration: AccelerationConfig {
+                enabled: false,
+                interval: 100,
+                max_step: 8,
+            },
             colors: ColorConfig {
                 normal: TableRowColorConfig {
                     name: BgFgColorConfig {
@@ -149,28 +533,48 @@ impl Default for Config {
                         foreground: Color::Blue,
                     },
                 },
+                row: Color::Reset,
+                row_alt: Color::Reset,
                 selected: BgFgColorConfig {
                     background: Color::White,
                     foreground: Color::Black,
                 },
                 selected_modifier: Modifier::BOLD,
+                header_modifier: Modifier::REVERSED,
                 selected_symbol: "".into(),
+                marked: BgFgColorConfig {
+                    background: Color::Reset,
+                    foreground: Color::LightYellow,
+                },
+                marked_symbol: "● ".into(),
                 status_bar: StatusBarColorConfig {
                     normal: BgFgColorConfig {
-                        background: Color::Black,
-                        foreground: Color::White,
+                        background: Color::LightBlue,
+                        foreground: Color::Black,
                     },
                     insert: BgFgColorConfig {
-                        background: Color::Black,
-                        foreground: Color::LightYellow,
+                        background: Color::LightGreen,
+                        foreground: Color::Black,
                     },
                     peek: BgFgColorConfig {
-                        background: Color::Black,
-                        foreground: Color::LightGreen,
+                        background: Color::LightYellow,
+                        foreground: Color::Black,
                     },
                     bottom: BgFgColorConfig {
-                        background: Color::Black,
-                        foreground: Color::LightMagenta,
+                        background: Color::LightMagenta,
+                        foreground: Color::Black,
+                    },
+                    goto: BgFgColorConfig {
+                        background: Color::LightCyan,
+                        foreground: Color::Black,
+                    },
+                    search: BgFgColorConfig {
+                        background: Color::LightRed,
+                        foreground: Color::Black,
+                    },
+                    command: BgFgColorConfig {
+                        background: Color::White,
+                        foreground: Color::Black,
                     },
                 },
                 editor: EditorColorConfig {
@@ -193,15 +597,52 @@ impl Default for Config {
                     down: KeyCode::Char('j'),
                     up: KeyCode::Char('k'),
                     right: KeyCode::Char('l'),
+                    next_column: KeyCode::Tab,
+                    previous_column: KeyCode::BackTab,
                 },
                 peek: KeyCode::Char('p'),
+                peek_under_quick: KeyCode::Char('P'),
                 peeking: PeekingBindingsMap {
                     all: KeyCode::Char('a'),
                     cell_path: KeyCode::Char('c'),
                     under: KeyCode::Char('p'),
                     view: KeyCode::Char('v'),
+                    marks: KeyCode::Char('m'),
+                    screen: KeyCode::Char('s'),
+                    columns: KeyCode::Char('t'),
                 },
                 transpose: KeyCode::Char('t'),
+                goto: KeyCode::Char('g'),
+                mark: KeyCode::Char(' '),
+                clear_marks: KeyCode::Char('x'),
+                search: KeyCode::Char('?'),
+                raw: KeyCode::Char('R'),
+                command: KeyCode::Char(':'),
+                write: KeyCode::Char('w'),
+                diff: KeyCode::Char('D'),
+                next_match: KeyCode::Char('n'),
+                previous_match: KeyCode::Char('N'),
+                stats: KeyCode::Char('='),
+                append: KeyCode::Char('a'),
+                pin: KeyCode::Char('!'),
+                parse: KeyCode::Char('e'),
+                deeper_preview: KeyCode::Char('+'),
+                shallower_preview: KeyCode::Char('-'),
+                split: KeyCode::Char('v'),
+                other_pane: KeyCode::Char('o'),
+                open: KeyCode::Char('O'),
+                record_macro: KeyCode::Char('Q'),
+                replay_macro: KeyCode::Char('@'),
+                validate: KeyCode::Char('V'),
+                next_failure: KeyCode::Char(']'),
+                previous_failure: KeyCode::Char('['),
+                next_sibling: KeyCode::Char('J'),
+                previous_sibling: KeyCode::Char('K'),
+                info: KeyCode::Char('I'),
+                descend: KeyCode::Char('d'),
+                column_width: KeyCode::Char('W'),
+                range: KeyCode::Char('r'),
+                delete_range: KeyCode::Char('X'),
             },
         }
     }
@@ -223,11 +664,282 @@ impl Config {
                         config.show_table_header = val
                     }
                 }
+                "show_context" => {
+                    if let Some(val) = try_bool(&value, &["show_context"])? {
+                        config.show_context = val
+                    }
+                }
+                "peek_status_bar" => {
+                    if let Some(val) = try_bool(&value, &["peek_status_bar"])? {
+                        config.peek_status_bar = val
+                    }
+                }
+                "allow_open" => {
+                    if let Some(val) = try_bool(&value, &["allow_open"])? {
+                        config.allow_open = val
+                    }
+                }
+                "peek_under_without_marks" => {
+                    if let Some(val) = try_bool(&value, &["peek_under_without_marks"])? {
+                        config.peek_under_without_marks = val
+                    }
+                }
+                "restore_session" => {
+                    if let Some(val) = try_bool(&value, &["restore_session"])? {
+                        config.restore_session = val
+                    }
+                }
+                "read_only" => {
+                    if let Some(val) = try_bool(&value, &["read_only"])? {
+                        config.read_only = val
+                    }
+                }
                 "layout" => {
                     if let Some(val) = try_layout(&value, &["layout"])? {
                         config.layout = val
                     }
                 }
+                "display" => {
+                    let cell = follow_cell_path(&value, &["display"]).unwrap();
+                    let columns = match &cell {
+                        Value::Record { val: rec, .. } => &rec.cols,
+                        x => return Err(invalid_type(x, &["display"], "record")),
+                    };
+
+                    for column in columns {
+                        match column.as_str() {
+                            "preview_length" => {
+                                if let Some(val) =
+                                    try_usize(&value, &["display", "preview_length"])?
+                                {
+                                    config.display.preview_length = val
+                                }
+                            }
+                            "render_ansi" => {
+                                if let Some(val) = try_bool(&value, &["display", "render_ansi"])? {
+                                    config.display.render_ansi = val
+                                }
+                            }
+                            "colorize_values" => {
+                                if let Some(val) =
+                                    try_bool(&value, &["display", "colorize_values"])?
+                                {
+                                    config.display.colorize_values = val
+                                }
+                            }
+                            "collapse_runs" => {
+                                if let Some(val) = try_bool(&value, &["display", "collapse_runs"])?
+                                {
+                                    config.display.collapse_runs = val
+                                }
+                            }
+                            "monochrome" => {
+                                if let Some(val) = try_bool(&value, &["display", "monochrome"])? {
+                                    config.display.monochrome = val
+                                }
+                            }
+                            "substitutions" => {
+                                if let Some(val) = try_substitutions(&value, &["display", "substitutions"])?
+                                {
+                                    config.display.substitutions = val
+                                }
+                            }
+                            "bottom_context" => {
+                                if let Some(val) = try_bool(&value, &["display", "bottom_context"])?
+                                {
+                                    config.display.bottom_context = val
+                                }
+                            }
+                            "preview_depth" => {
+                                if let Some(val) = try_usize(&value, &["display", "preview_depth"])?
+                                {
+                                    config.display.preview_depth = val
+                                }
+                            }
+                            "column_width" => {
+                                if let Some(val) =
+                                    try_column_width(&value, &["display", "column_width"])?
+                                {
+                                    config.display.column_width = val
+                                }
+                            }
+                            // NOTE: the renderers are closures, they wait for an engine interface
+                            "renderers" => {
+                                return Err(LabeledError {
+                                    label: "invalid config".into(),
+                                    msg: "`$.display.renderers`: plugins can not evaluate closures yet, give static renderings to `$.display.substitutions` instead".into(),
+                                    span: Some(cell.span()),
+                                })
+                            }
+                            x => return Err(invalid_field(&["display", x], Some(cell.span()))),
+                        }
+                    }
+                }
+                "search" => {
+                    let cell = follow_cell_path(&value, &["search"]).unwrap();
+                    let columns = match &cell {
+                        Value::Record { val: rec, .. } => &rec.cols,
+                        x => return Err(invalid_type(x, &["search"], "record")),
+                    };
+
+                    for column in columns {
+                        match column.as_str() {
+                            "max_depth" => {
+                                if let Some(val) = try_usize(&value, &["search", "max_depth"])? {
+                                    config.search.max_depth = val
+                                }
+                            }
+                            "max_matches" => {
+                                if let Some(val) = try_usize(&value, &["search", "max_matches"])? {
+                                    config.search.max_matches = val
+                                }
+                            }
+                            "max_binary_size" => {
+                                if let Some(val) =
+                                    try_usize(&value, &["search", "max_binary_size"])?
+                                {
+                                    config.search.max_binary_size = val
+                                }
+                            }
+                            x => return Err(invalid_field(&["search", x], Some(cell.span()))),
+                        }
+                    }
+                }
+                "diff" => {
+                    let cell = follow_cell_path(&value, &["diff"]).unwrap();
+                    let columns = match &cell {
+                        Value::Record { val: rec, .. } => &rec.cols,
+                        x => return Err(invalid_type(x, &["diff"], "record")),
+                    };
+
+                    for column in columns {
+                        match column.as_str() {
+                            "max_depth" => {
+                                if let Some(val) = try_usize(&value, &["diff", "max_depth"])? {
+                                    config.diff.max_depth = val
+                                }
+                            }
+                            "max_differences" => {
+                                if let Some(val) = try_usize(&value, &["diff", "max_differences"])?
+                                {
+                                    config.diff.max_differences = val
+                                }
+                            }
+                            x => return Err(invalid_field(&["diff", x], Some(cell.span()))),
+                        }
+                    }
+                }
+                "schema" => {
+                    let cell = follow_cell_path(&value, &["schema"]).unwrap();
+                    let columns = match &cell {
+                        Value::Record { val: rec, .. } => &rec.cols,
+                        x => return Err(invalid_type(x, &["schema"], "record")),
+                    };
+
+                    for column in columns {
+                        match column.as_str() {
+                            "max_samples" => {
+                                if let Some(val) = try_usize(&value, &["schema", "max_samples"])? {
+                                    config.schema.max_samples = val
+                                }
+                            }
+                            x => return Err(invalid_field(&["schema", x], Some(cell.span()))),
+                        }
+                    }
+                }
+                "group" => {
+                    let cell = follow_cell_path(&value, &["group"]).unwrap();
+                    let columns = match &cell {
+                        Value::Record { val: rec, .. } => &rec.cols,
+                        x => return Err(invalid_type(x, &["group"], "record")),
+                    };
+
+                    for column in columns {
+                        match column.as_str() {
+                            "max_groups" => {
+                                if let Some(val) = try_usize(&value, &["group", "max_groups"])? {
+                                    config.group.max_groups = val
+                                }
+                            }
+                            x => return Err(invalid_field(&["group", x], Some(cell.span()))),
+                        }
+                    }
+                }
+                "navigation" => {
+                    let cell = follow_cell_path(&value, &["navigation"]).unwrap();
+                    let columns = match &cell {
+                        Value::Record { val: rec, .. } => &rec.cols,
+                        x => return Err(invalid_type(x, &["navigation"], "record")),
+                    };
+
+                    for column in columns {
+                        match column.as_str() {
+                            "auto_descend" => {
+                                if let Some(val) =
+                                    try_bool(&value, &["navigation", "auto_descend"])?
+                                {
+                                    config.navigation.auto_descend = val
+                                }
+                            }
+                            x => return Err(invalid_field(&["navigation", x], Some(cell.span()))),
+                        }
+                    }
+                }
+                "log" => {
+                    let cell = follow_cell_path(&value, &["log"]).unwrap();
+                    let columns = match &cell {
+                        Value::Record { val: rec, .. } => &rec.cols,
+                        x => return Err(invalid_type(x, &["log"], "record")),
+                    };
+
+                    for column in columns {
+                        match column.as_str() {
+                            "file" => {
+                                if let Some(val) = try_string(&value, &["log", "file"])? {
+                                    config.log.file = Some(val)
+                                }
+                            }
+                            "level" => {
+                                if let Some(val) = try_log_level(&value, &["log", "level"])? {
+                                    config.log.level = val
+                                }
+                            }
+                            x => return Err(invalid_field(&["log", x], Some(cell.span()))),
+                        }
+                    }
+                }
+                "acceleration" => {
+                    let cell = follow_cell_path(&value, &["acceleration"]).unwrap();
+                    let columns = match &cell {
+                        Value::Record { val: rec, .. } => &rec.cols,
+                        x => return Err(invalid_type(x, &["acceleration"], "record")),
+                    };
+
+                    for column in columns {
+                        match column.as_str() {
+                            "enabled" => {
+                                if let Some(val) = try_bool(&value, &["acceleration", "enabled"])? {
+                                    config.acceleration.enabled = val
+                                }
+                            }
+                            "interval" => {
+                                if let Some(val) = try_usize(&value, &["acceleration", "interval"])?
+                                {
+                                    config.acceleration.interval = val
+                                }
+                            }
+                            "max_step" => {
+                                if let Some(val) = try_usize(&value, &["acceleration", "max_step"])?
+                                {
+                                    config.acceleration.max_step = val
+                                }
+                            }
+                            x => {
+                                return Err(invalid_field(&["acceleration", x], Some(cell.span())))
+                            }
+                        }
+                    }
+                }
                 "colors" => {
                     let cell = follow_cell_path(&value, &["colors"]).unwrap();
                     let columns = match &cell {
@@ -288,6 +1000,16 @@ impl Config {
                                     }
                                 }
                             }
+                            "row" => {
+                                if let Some(val) = try_color(&value, &["colors", "row"])? {
+                                    config.colors.row = val
+                                }
+                            }
+                            "row_alt" => {
+                                if let Some(val) = try_color(&value, &["colors", "row_alt"])? {
+                                    config.colors.row_alt = val
+                                }
+                            }
                             "selected" => {
                                 if let Some(val) = try_fg_bg_colors(
                                     &value,
@@ -304,6 +1026,21 @@ impl Config {
                                     config.colors.selected_symbol = val
                                 }
                             }
+                            "marked" => {
+                                if let Some(val) = try_fg_bg_colors(
+                                    &value,
+                                    &["colors", "marked"],
+                                    &config.colors.marked,
+                                )? {
+                                    config.colors.marked = val
+                                }
+                            }
+                            "marked_symbol" => {
+                                if let Some(val) = try_string(&value, &["colors", "marked_symbol"])?
+                                {
+                                    config.colors.marked_symbol = val
+                                }
+                            }
                             "selected_modifier" => {
                                 if let Some(val) =
                                     try_modifier(&value, &["colors", "selected_modifier"])?
@@ -311,6 +1048,13 @@ impl Config {
                                     config.colors.selected_modifier = val
                                 }
                             }
+                            "header_modifier" => {
+                                if let Some(val) =
+                                    try_modifier(&value, &["colors", "header_modifier"])?
+                                {
+                                    config.colors.header_modifier = val
+                                }
+                            }
                             "status_bar" => {
                                 let cell =
                                     follow_cell_path(&value, &["colors", "status_bar"]).unwrap();
@@ -325,6 +1069,34 @@ impl Config {
                                     }
                                 };
 
+                                // NOTE: the *flat* form, i.e. a single pair of colors, is applied
+                                // to all the modes at once
+                                if !columns.is_empty()
+                                    && columns
+                                        .iter()
+                                        .all(|c| c == "background" || c == "foreground")
+                                {
+                                    let status_bar = &mut config.colors.status_bar;
+                                    for colors in [
+                                        &mut status_bar.normal,
+                                        &mut status_bar.insert,
+                                        &mut status_bar.peek,
+                                        &mut status_bar.bottom,
+                                        &mut status_bar.goto,
+                                        &mut status_bar.search,
+                                        &mut status_bar.command,
+                                    ] {
+                                        if let Some(val) = try_fg_bg_colors(
+                                            &value,
+                                            &["colors", "status_bar"],
+                                            colors,
+                                        )? {
+                                            *colors = val
+                                        }
+                                    }
+                                    continue;
+                                }
+
                                 for column in columns {
                                     match column.as_str() {
                                         "normal" => {
@@ -363,6 +1135,33 @@ impl Config {
                                                 config.colors.status_bar.bottom = val
                                             }
                                         }
+                                        "goto" => {
+                                            if let Some(val) = try_fg_bg_colors(
+                                                &value,
+                                                &["colors", "status_bar", "goto"],
+                                                &config.colors.status_bar.goto,
+                                            )? {
+                                                config.colors.status_bar.goto = val
+                                            }
+                                        }
+                                        "search" => {
+                                            if let Some(val) = try_fg_bg_colors(
+                                                &value,
+                                                &["colors", "status_bar", "search"],
+                                                &config.colors.status_bar.search,
+                                            )? {
+                                                config.colors.status_bar.search = val
+                                            }
+                                        }
+                                        "command" => {
+                                            if let Some(val) = try_fg_bg_colors(
+                                                &value,
+                                                &["colors", "status_bar", "command"],
+                                                &config.colors.status_bar.command,
+                                            )? {
+                                                config.colors.status_bar.command = val
+                                            }
+                                        }
                                         x => {
                                             return Err(invalid_field(
                                                 &["colors", "status_bar", x],
@@ -490,20 +1289,43 @@ impl Config {
                                                 config.keybindings.navigation.right = val
                                             }
                                         }
-                                        x => {
-                                            return Err(invalid_field(
-                                                &["keybindings", "navigation", x],
-                                                Some(cell.span()),
-                                            ));
-                                        }
-                                    }
-                                }
+                                        "next_column" => {
+                                            if let Some(val) = try_key(
+                                                &value,
+                                                &["keybindings", "navigation", "next_column"],
+                                            )? {
+                                                config.keybindings.navigation.next_column = val
+                                            }
+                                        }
+                                        "previous_column" => {
+                                            if let Some(val) = try_key(
+                                                &value,
+                                                &["keybindings", "navigation", "previous_column"],
+                                            )? {
+                                                config.keybindings.navigation.previous_column = val
+                                            }
+                                        }
+                                        x => {
+                                            return Err(invalid_field(
+                                                &["keybindings", "navigation", x],
+                                                Some(cell.span()),
+                                            ));
+                                        }
+                                    }
+                                }
                             }
                             "peek" => {
                                 if let Some(val) = try_key(&value, &["keybindings", "peek"])? {
                                     config.keybindings.peek = val
                                 }
                             }
+                            "peek_under_quick" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "peek_under_quick"])?
+                                {
+                                    config.keybindings.peek_under_quick = val
+                                }
+                            }
                             "peeking" => {
                                 let cell =
                                     follow_cell_path(&value, &["keybindings", "peeking"]).unwrap();
@@ -551,6 +1373,30 @@ impl Config {
                                                 config.keybindings.peeking.view = val
                                             }
                                         }
+                                        "marks" => {
+                                            if let Some(val) = try_key(
+                                                &value,
+                                                &["keybindings", "peeking", "marks"],
+                                            )? {
+                                                config.keybindings.peeking.marks = val
+                                            }
+                                        }
+                                        "screen" => {
+                                            if let Some(val) = try_key(
+                                                &value,
+                                                &["keybindings", "peeking", "screen"],
+                                            )? {
+                                                config.keybindings.peeking.screen = val
+                                            }
+                                        }
+                                        "columns" => {
+                                            if let Some(val) = try_key(
+                                                &value,
+                                                &["keybindings", "peeking", "columns"],
+                                            )? {
+                                                config.keybindings.peeking.columns = val
+                                            }
+                                        }
                                         x => {
                                             return Err(invalid_field(
                                                 &["keybindings", "peeking", x],
@@ -565,6 +1411,186 @@ impl Config {
                                     config.keybindings.transpose = val
                                 }
                             }
+                            "goto" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "goto"])? {
+                                    config.keybindings.goto = val
+                                }
+                            }
+                            "mark" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "mark"])? {
+                                    config.keybindings.mark = val
+                                }
+                            }
+                            "clear_marks" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "clear_marks"])?
+                                {
+                                    config.keybindings.clear_marks = val
+                                }
+                            }
+                            "search" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "search"])? {
+                                    config.keybindings.search = val
+                                }
+                            }
+                            "raw" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "raw"])? {
+                                    config.keybindings.raw = val
+                                }
+                            }
+                            "command" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "command"])? {
+                                    config.keybindings.command = val
+                                }
+                            }
+                            "write" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "write"])? {
+                                    config.keybindings.write = val
+                                }
+                            }
+                            "diff" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "diff"])? {
+                                    config.keybindings.diff = val
+                                }
+                            }
+                            "next_match" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "next_match"])?
+                                {
+                                    config.keybindings.next_match = val
+                                }
+                            }
+                            "previous_match" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "previous_match"])?
+                                {
+                                    config.keybindings.previous_match = val
+                                }
+                            }
+                            "stats" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "stats"])? {
+                                    config.keybindings.stats = val
+                                }
+                            }
+                            "append" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "append"])? {
+                                    config.keybindings.append = val
+                                }
+                            }
+                            "pin" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "pin"])? {
+                                    config.keybindings.pin = val
+                                }
+                            }
+                            "parse" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "parse"])? {
+                                    config.keybindings.parse = val
+                                }
+                            }
+                            "deeper_preview" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "deeper_preview"])?
+                                {
+                                    config.keybindings.deeper_preview = val
+                                }
+                            }
+                            "shallower_preview" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "shallower_preview"])?
+                                {
+                                    config.keybindings.shallower_preview = val
+                                }
+                            }
+                            "split" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "split"])? {
+                                    config.keybindings.split = val
+                                }
+                            }
+                            "other_pane" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "other_pane"])?
+                                {
+                                    config.keybindings.other_pane = val
+                                }
+                            }
+                            "open" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "open"])? {
+                                    config.keybindings.open = val
+                                }
+                            }
+                            "record_macro" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "record_macro"])?
+                                {
+                                    config.keybindings.record_macro = val
+                                }
+                            }
+                            "replay_macro" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "replay_macro"])?
+                                {
+                                    config.keybindings.replay_macro = val
+                                }
+                            }
+                            "validate" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "validate"])? {
+                                    config.keybindings.validate = val
+                                }
+                            }
+                            "next_failure" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "next_failure"])?
+                                {
+                                    config.keybindings.next_failure = val
+                                }
+                            }
+                            "previous_failure" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "previous_failure"])?
+                                {
+                                    config.keybindings.previous_failure = val
+                                }
+                            }
+                            "next_sibling" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "next_sibling"])?
+                                {
+                                    config.keybindings.next_sibling = val
+                                }
+                            }
+                            "previous_sibling" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "previous_sibling"])?
+                                {
+                                    config.keybindings.previous_sibling = val
+                                }
+                            }
+                            "info" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "info"])? {
+                                    config.keybindings.info = val
+                                }
+                            }
+                            "descend" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "descend"])? {
+                                    config.keybindings.descend = val
+                                }
+                            }
+                            "column_width" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "column_width"])?
+                                {
+                                    config.keybindings.column_width = val
+                                }
+                            }
+                            "range" => {
+                                if let Some(val) = try_key(&value, &["keybindings", "range"])? {
+                                    config.keybindings.range = val
+                                }
+                            }
+                            "delete_range" => {
+                                if let Some(val) =
+                                    try_key(&value, &["keybindings", "delete_range"])?
+                                {
+                                    config.keybindings.delete_range = val
+                                }
+                            }
                             x => return Err(invalid_field(&["keybindings", x], Some(cell.span()))),
                         }
                     }
@@ -580,12 +1606,15 @@ impl Config {
 /// represent a [`KeyCode`] as a simple string
 pub fn repr_keycode(keycode: &KeyCode) -> String {
     match keycode {
+        KeyCode::Char(' ') => "<space>".into(),
         KeyCode::Char(c) => c.to_string(),
         KeyCode::Left => char::from_u32(0x2190).unwrap().into(),
         KeyCode::Up => char::from_u32(0x2191).unwrap().into(),
         KeyCode::Right => char::from_u32(0x2192).unwrap().into(),
         KeyCode::Down => char::from_u32(0x2193).unwrap().into(),
         KeyCode::Esc => "<esc>".into(),
+        KeyCode::Tab => "<tab>".into(),
+        KeyCode::BackTab => "<s-tab>".into(),
         KeyCode::Enter => char::from_u32(0x23ce).unwrap().into(),
         KeyCode::Backspace => char::from_u32(0x232b).unwrap().into(),
         KeyCode::Delete => char::from_u32(0x2326).unwrap().into(),
@@ -597,19 +1626,43 @@ pub fn repr_keycode(keycode: &KeyCode) -> String {
 #[cfg(test)]
 mod tests {
     use crossterm::event::KeyCode;
-    use nu_protocol::{record, Record, Value};
+    use nu_protocol::{engine::Closure, record, Record, Span, Value};
+    use ratatui::style::{Color, Modifier};
 
-    use super::{repr_keycode, Config};
+    use super::{
+        repr_keycode, AccelerationConfig, ColumnWidth, Config, LogConfig, LogLevel, WidthSpec,
+        MIN_COLUMN_WIDTH,
+    };
 
     #[test]
     fn keycode_representation() {
         assert_eq!(repr_keycode(&KeyCode::Char('x')), "x".to_string());
         assert_eq!(repr_keycode(&KeyCode::Left), "←".to_string());
         assert_eq!(repr_keycode(&KeyCode::Esc), "<esc>".to_string());
+        assert_eq!(repr_keycode(&KeyCode::Char(' ')), "<space>".to_string());
         assert_eq!(repr_keycode(&KeyCode::Enter), "⏎".to_string());
         assert_eq!(repr_keycode(&KeyCode::Home), "??".to_string());
     }
 
+    #[test]
+    fn distinct_status_bar_backgrounds() {
+        let status_bar = Config::default().colors.status_bar;
+        let backgrounds = [
+            status_bar.normal.background,
+            status_bar.insert.background,
+            status_bar.peek.background,
+            status_bar.bottom.background,
+            status_bar.goto.background,
+            status_bar.search.background,
+            status_bar.command.background,
+        ];
+        for (i, a) in backgrounds.iter().enumerate() {
+            for b in &backgrounds[i + 1..] {
+                assert_ne!(a, b, "two modes share the same background");
+            }
+        }
+    }
+
     #[test]
     fn parse_invalid_config() {
         assert_eq!(
@@ -661,6 +1714,51 @@ mod tests {
         expected.show_cell_path = false;
         assert_eq!(Config::from_value(value), Ok(expected));
 
+        let value = Value::test_record(record! {
+            "restore_session" => Value::test_bool(false),
+        });
+        let expected = Config {
+            restore_session: false,
+            ..Config::default()
+        };
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "read_only" => Value::test_bool(true),
+        });
+        let expected = Config {
+            read_only: true,
+            ..Config::default()
+        };
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "show_context" => Value::test_bool(true),
+        });
+        let expected = Config {
+            show_context: true,
+            ..Config::default()
+        };
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "peek_status_bar" => Value::test_bool(true),
+        });
+        let expected = Config {
+            peek_status_bar: true,
+            ..Config::default()
+        };
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "allow_open" => Value::test_bool(true),
+        });
+        let expected = Config {
+            allow_open: true,
+            ..Config::default()
+        };
+        assert_eq!(Config::from_value(value), Ok(expected));
+
         let value = Value::test_record(record! {
             "keybindings" => Value::test_record(record!{
                 "navigation" => Value::test_record(record!{
@@ -673,4 +1771,381 @@ mod tests {
         expected.keybindings.navigation.up = KeyCode::Char('x');
         assert_eq!(Config::from_value(value), Ok(expected));
     }
+
+    #[test]
+    fn parse_display_config() {
+        let value = Value::test_record(record! {
+            "display" => Value::test_record(record!{
+                "preview_length" => Value::test_int(10),
+                "render_ansi" => Value::test_bool(true),
+                "colorize_values" => Value::test_bool(true),
+                "collapse_runs" => Value::test_bool(true),
+                "preview_depth" => Value::test_int(0),
+                "monochrome" => Value::test_bool(true),
+                "bottom_context" => Value::test_bool(false),
+            }),
+        });
+
+        let mut expected = Config::default();
+        expected.display.preview_length = 10;
+        expected.display.render_ansi = true;
+        expected.display.colorize_values = true;
+        expected.display.collapse_runs = true;
+        expected.display.preview_depth = 0;
+        expected.display.monochrome = true;
+        expected.display.bottom_context = false;
+        assert_eq!(Config::from_value(value), Ok(expected));
+    }
+
+    #[test]
+    fn parse_substitutions() {
+        let config = |substitutions: Value| {
+            Config::from_value(Value::test_record(record! {
+                "display" => Value::test_record(record!{
+                    "substitutions" => substitutions,
+                }),
+            }))
+            .map(|config| config.display.substitutions)
+        };
+
+        let substitutions = config(Value::test_record(record! {
+            "status" => Value::test_record(record! {
+                "ok" => Value::test_string("✔"),
+                "error" => Value::test_string("✘"),
+            }),
+            "bool" => Value::test_record(record! { "true" => Value::test_string("yes") }),
+        }))
+        .unwrap();
+        assert_eq!(substitutions.len(), 2);
+        assert_eq!(substitutions["status"]["ok"], "✔");
+        assert_eq!(substitutions["bool"]["true"], "yes");
+
+        assert!(config(Value::test_record(record! {
+            "status" => Value::test_record(record! { "ok" => Value::test_int(1) }),
+        }))
+        .is_err());
+        assert!(config(Value::test_string("foo")).is_err());
+
+        let closure = Value::closure(
+            Closure {
+                block_id: 0,
+                captures: vec![],
+            },
+            Span::test_data(),
+        );
+        let err = config(Value::test_record(record! { "date" => closure.clone() })).unwrap_err();
+        assert!(err.msg.contains("substitutions are not closures"));
+
+        let err = Config::from_value(Value::test_record(record! {
+            "display" => Value::test_record(record! {
+                "renderers" => Value::test_record(record! { "date" => closure }),
+            }),
+        }))
+        .unwrap_err();
+        assert!(err.msg.contains("`$.display.substitutions`"));
+    }
+
+    #[test]
+    fn parse_column_width() {
+        let config = |column_width: Value| {
+            Config::from_value(Value::test_record(record! {
+                "display" => Value::test_record(record!{
+                    "column_width" => column_width,
+                }),
+            }))
+            .map(|config| config.display.column_width)
+        };
+
+        assert_eq!(config(Value::test_string("auto")), Ok(ColumnWidth::Auto));
+        assert_eq!(config(Value::test_string("equal")), Ok(ColumnWidth::Equal));
+        assert_eq!(
+            config(Value::test_list(vec![
+                Value::test_string("50%"),
+                Value::test_int(10),
+            ])),
+            Ok(ColumnWidth::Custom(vec![
+                WidthSpec::Percent(50),
+                WidthSpec::Fixed(10)
+            ]))
+        );
+        assert!(config(Value::test_string("fit")).is_err());
+        assert!(config(Value::test_list(vec![Value::test_string("half")])).is_err());
+        assert!(config(Value::test_list(vec![Value::test_bool(true)])).is_err());
+
+        let (clamped, warnings) = ColumnWidth::Custom(vec![
+            WidthSpec::Fixed(0),
+            WidthSpec::Percent(150),
+            WidthSpec::Fixed(10),
+        ])
+        .clamp();
+        assert_eq!(
+            clamped,
+            ColumnWidth::Custom(vec![
+                WidthSpec::Fixed(MIN_COLUMN_WIDTH),
+                WidthSpec::Percent(100),
+                WidthSpec::Fixed(10),
+            ])
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                "the width of column 0 has been clamped to 3",
+                "the width of column 1 has been clamped to 100%",
+            ]
+        );
+
+        let custom = ColumnWidth::Custom(vec![WidthSpec::Fixed(10)]);
+        assert_eq!(ColumnWidth::Auto.next(&custom), ColumnWidth::Equal);
+        assert_eq!(ColumnWidth::Equal.next(&custom), custom);
+        assert_eq!(custom.next(&custom), ColumnWidth::Auto);
+        assert_eq!(
+            ColumnWidth::Equal.next(&ColumnWidth::Auto),
+            ColumnWidth::Auto
+        );
+    }
+
+    #[test]
+    fn parse_search_config() {
+        let value = Value::test_record(record! {
+            "search" => Value::test_record(record!{
+                "max_depth" => Value::test_int(3),
+                "max_matches" => Value::test_int(10),
+            }),
+        });
+
+        let mut expected = Config::default();
+        expected.search.max_depth = 3;
+        expected.search.max_matches = 10;
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "search" => Value::test_record(record!{
+                "max_depth" => Value::test_int(-3),
+            }),
+        });
+        assert!(Config::from_value(value).is_err());
+    }
+
+    #[test]
+    fn parse_schema_config() {
+        let value = Value::test_record(record! {
+            "schema" => Value::test_record(record!{
+                "max_samples" => Value::test_int(10),
+            }),
+        });
+
+        let mut expected = Config::default();
+        expected.schema.max_samples = 10;
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "schema" => Value::test_int(10),
+        });
+        assert!(Config::from_value(value).is_err());
+    }
+
+    #[test]
+    fn parse_group_config() {
+        let value = Value::test_record(record! {
+            "group" => Value::test_record(record!{
+                "max_groups" => Value::test_int(10),
+            }),
+        });
+
+        let mut expected = Config::default();
+        expected.group.max_groups = 10;
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "group" => Value::test_record(record!{
+                "max_group" => Value::test_int(10),
+            }),
+        });
+        assert!(Config::from_value(value).is_err());
+    }
+
+    #[test]
+    fn parse_navigation_config() {
+        let value = Value::test_record(record! {
+            "navigation" => Value::test_record(record!{
+                "auto_descend" => Value::test_bool(true),
+            }),
+        });
+
+        let mut expected = Config::default();
+        expected.navigation.auto_descend = true;
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "navigation" => Value::test_record(record!{
+                "auto_descend" => Value::test_int(1),
+            }),
+        });
+        assert!(Config::from_value(value).is_err());
+    }
+
+    #[test]
+    fn parse_acceleration_config() {
+        let value = Value::test_record(record! {
+            "acceleration" => Value::test_record(record!{
+                "enabled" => Value::test_bool(true),
+                "max_step" => Value::test_int(32),
+            }),
+        });
+
+        let expected = Config {
+            acceleration: AccelerationConfig {
+                enabled: true,
+                max_step: 32,
+                ..Config::default().acceleration
+            },
+            ..Config::default()
+        };
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "acceleration" => Value::test_record(record!{
+                "foo" => Value::test_int(1),
+            }),
+        });
+        assert!(Config::from_value(value).is_err());
+    }
+
+    #[test]
+    fn parse_log_config() {
+        let value = Value::test_record(record! {
+            "log" => Value::test_record(record!{
+                "file" => Value::test_string("explore.log"),
+            }),
+        });
+        let expected = Config {
+            log: LogConfig {
+                file: Some("explore.log".into()),
+                level: LogLevel::Info,
+            },
+            ..Config::default()
+        };
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "log" => Value::test_record(record!{
+                "file" => Value::test_string("explore.log"),
+                "level" => Value::test_string("debug"),
+            }),
+        });
+        let expected = Config {
+            log: LogConfig {
+                file: Some("explore.log".into()),
+                level: LogLevel::Debug,
+            },
+            ..Config::default()
+        };
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        for log in [
+            record! { "level" => Value::test_string("trace") },
+            record! { "level" => Value::test_int(1) },
+            record! { "path" => Value::test_string("explore.log") },
+        ] {
+            let value = Value::test_record(record! { "log" => Value::test_record(log) });
+            assert!(Config::from_value(value).is_err());
+        }
+    }
+
+    #[test]
+    fn parse_diff_config() {
+        let value = Value::test_record(record! {
+            "diff" => Value::test_record(record!{
+                "max_depth" => Value::test_int(2),
+                "max_differences" => Value::test_int(5),
+            }),
+        });
+
+        let mut expected = Config::default();
+        expected.diff.max_depth = 2;
+        expected.diff.max_differences = 5;
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "diff" => Value::test_record(record!{
+                "depth" => Value::test_int(2),
+            }),
+        });
+        assert!(Config::from_value(value).is_err());
+    }
+
+    #[test]
+    fn parse_row_colors_config() {
+        let value = Value::test_record(record! {
+            "colors" => Value::test_record(record!{
+                "row" => Value::test_string("black"),
+                "row_alt" => Value::test_list(vec![
+                    Value::test_int(30),
+                    Value::test_int(30),
+                    Value::test_int(30),
+                ]),
+                "selected_modifier" => Value::test_string("reversed"),
+                "header_modifier" => Value::test_string("bold"),
+            }),
+        });
+
+        let mut expected = Config::default();
+        expected.colors.row = Color::Black;
+        expected.colors.row_alt = Color::Rgb(30, 30, 30);
+        expected.colors.selected_modifier = Modifier::REVERSED;
+        expected.colors.header_modifier = Modifier::BOLD;
+        assert_eq!(Config::from_value(value), Ok(expected));
+    }
+
+    #[test]
+    fn parse_status_bar_config() {
+        let value = Value::test_record(record! {
+            "colors" => Value::test_record(record!{
+                "status_bar" => Value::test_record(record!{
+                    "insert" => Value::test_record(record!{
+                        "background" => Value::test_string("red"),
+                    }),
+                }),
+            }),
+        });
+
+        let mut expected = Config::default();
+        expected.colors.status_bar.insert.background = Color::Red;
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        // the flat form is applied to all the modes
+        let value = Value::test_record(record! {
+            "colors" => Value::test_record(record!{
+                "status_bar" => Value::test_record(record!{
+                    "background" => Value::test_string("red"),
+                }),
+            }),
+        });
+
+        let mut expected = Config::default();
+        expected.colors.status_bar.normal.background = Color::Red;
+        expected.colors.status_bar.insert.background = Color::Red;
+        expected.colors.status_bar.peek.background = Color::Red;
+        expected.colors.status_bar.bottom.background = Color::Red;
+        expected.colors.status_bar.goto.background = Color::Red;
+        expected.colors.status_bar.search.background = Color::Red;
+        expected.colors.status_bar.command.background = Color::Red;
+        assert_eq!(Config::from_value(value), Ok(expected));
+
+        let value = Value::test_record(record! {
+            "colors" => Value::test_record(record!{
+                "status_bar" => Value::test_record(record!{
+                    "background" => Value::test_string("red"),
+                    "insert" => Value::test_record(record!{}),
+                }),
+            }),
+        });
+        let result = Config::from_value(value);
+        assert!(result.is_err());
+        assert!(result
+            .err()
+            .unwrap()
+            .msg
+            .contains("not a valid config field"));
+    }
 }
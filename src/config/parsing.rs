@@ -1,12 +1,14 @@
 //! utilities to parse a [`Value`](https://docs.rs/nu-protocol/0.83.1/nu_protocol/enum.Value.html)
 //! into a configuration
-use crossterm::event::KeyCode;
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::style::{Color, Modifier};
 
 use nu_plugin::LabeledError;
 use nu_protocol::{ast::PathMember, Span, Value};
 
-use super::{BgFgColorConfig, Layout};
+use super::{BgFgColorConfig, ColumnWidth, Layout, LogLevel, Substitutions, WidthSpec};
 
 /// return an *invalid field* error
 ///
@@ -88,6 +90,26 @@ pub fn try_string(value: &Value, cell_path: &[&str]) -> Result<Option<String>, L
     }
 }
 
+/// try to parse a non-negative integer in the *value* at the given *cell path*
+pub fn try_usize(value: &Value, cell_path: &[&str]) -> Result<Option<usize>, LabeledError> {
+    match follow_cell_path(value, cell_path) {
+        Some(Value::Int { val, .. }) => match usize::try_from(val) {
+            Ok(x) => Ok(Some(x)),
+            Err(_) => Err(LabeledError {
+                label: "invalid config".into(),
+                msg: format!(
+                    "`$.{}` should be a non-negative integer, found {}",
+                    cell_path.join("."),
+                    val
+                ),
+                span: Some(value.span()),
+            }),
+        },
+        Some(x) => Err(invalid_type(&x, cell_path, "int")),
+        _ => Ok(None),
+    }
+}
+
 /// try to parse an ANSI modifier in the *value* at the given *cell path*
 pub fn try_modifier(value: &Value, cell_path: &[&str]) -> Result<Option<Modifier>, LabeledError> {
     match follow_cell_path(value, cell_path) {
@@ -98,10 +120,11 @@ pub fn try_modifier(value: &Value, cell_path: &[&str]) -> Result<Option<Modifier
             "italic" => Ok(Some(Modifier::ITALIC)),
             "underline" => Ok(Some(Modifier::UNDERLINED)),
             "blink" => Ok(Some(Modifier::SLOW_BLINK)),
+            "reversed" => Ok(Some(Modifier::REVERSED)),
             x => Err(LabeledError {
                 label: "invalid config".into(),
                 msg: format!(
-                    r#"`$.{}` should be the empty string, one of [italic, bold, underline, blink] or null, found {}"#,
+                    r#"`$.{}` should be the empty string, one of [italic, bold, underline, blink, reversed] or null, found {}"#,
                     cell_path.join("."),
                     x
                 ),
@@ -241,12 +264,16 @@ pub fn try_key(value: &Value, cell_path: &[&str]) -> Result<Option<KeyCode>, Lab
             "left" => Ok(Some(KeyCode::Left)),
             "right" => Ok(Some(KeyCode::Right)),
             "escape" => Ok(Some(KeyCode::Esc)),
+            "space" => Ok(Some(KeyCode::Char(' '))),
+            "tab" => Ok(Some(KeyCode::Tab)),
+            "backtab" => Ok(Some(KeyCode::BackTab)),
+            "enter" => Ok(Some(KeyCode::Enter)),
             x => {
                 if x.len() != 1 {
                     return Err(LabeledError {
                         label: "invalid config".into(),
                         msg: format!(
-                            r#"`$.{}` should be a character or one of [up, down, left, right, escape] , found {}"#,
+                            r#"`$.{}` should be a character or one of [up, down, left, right, escape, space, tab, backtab, enter] , found {}"#,
                             cell_path.join("."),
                             x
                         ),
@@ -263,6 +290,85 @@ pub fn try_key(value: &Value, cell_path: &[&str]) -> Result<Option<KeyCode>, Lab
     }
 }
 
+/// the names of the keys that are not a single character, see [`parse_key`]
+const KEY_NAMES: &str = "up, down, left, right, escape, space, tab, backtab, enter, backspace";
+
+/// parse a single key, i.e. a name accepted by [`try_key`] or `backspace`, optionally
+/// prefixed by modifiers, e.g. `ctrl-q` or `alt-shift-x`
+fn parse_key(name: &str, span: Span) -> Result<KeyEvent, LabeledError> {
+    let invalid = || LabeledError {
+        label: "invalid key".into(),
+        msg: format!(
+            "expected a character, a modifier like `ctrl-` or one of [{}], found `{}`",
+            KEY_NAMES, name
+        ),
+        span: Some(span),
+    };
+
+    let mut modifiers = KeyModifiers::empty();
+    let mut rest = name;
+    loop {
+        // NOTE: `-` alone is a key, not a modifier
+        let (modifier, tail) = match rest.split_once('-') {
+            Some((modifier, tail)) if !tail.is_empty() => (modifier, tail),
+            _ => break,
+        };
+        modifiers |= match modifier {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => break,
+        };
+        rest = tail;
+    }
+
+    let code = match rest {
+        "backspace" => KeyCode::Backspace,
+        x => match try_key(&Value::string(x, span), &[]) {
+            Ok(Some(code)) => code,
+            _ => return Err(invalid()),
+        },
+    };
+
+    Ok(KeyEvent::new(code, modifiers))
+}
+
+/// parse a sequence of keys, e.g. to replay them before or instead of reading the terminal
+///
+/// - a list is a list of keys, see [`parse_key`]
+/// - a string is a list of keys separated by whitespaces, a word that is not the name of a key
+///   being a sequence of single characters, e.g. `jj l p u` is `j`, `j`, `l`, `p` and `u`
+///
+/// > see the tests for detailed examples
+pub fn parse_keys(value: &Value) -> Result<Vec<KeyEvent>, LabeledError> {
+    let span = value.span();
+    match value {
+        Value::List { vals, .. } => vals
+            .iter()
+            .map(|val| match val {
+                Value::String { val, .. } => parse_key(val, span),
+                x => Err(invalid_type(x, &[], "string")),
+            })
+            .collect(),
+        Value::String { val, .. } => {
+            let mut keys = vec![];
+            for word in val.split_whitespace() {
+                match parse_key(word, span) {
+                    Ok(key) => keys.push(key),
+                    Err(err) if word.contains('-') && word.len() > 1 => return Err(err),
+                    Err(_) => {
+                        for c in word.chars() {
+                            keys.push(parse_key(&c.to_string(), span)?);
+                        }
+                    }
+                }
+            }
+            Ok(keys)
+        }
+        x => Err(invalid_type(x, &[], "string or list<string>")),
+    }
+}
+
 /// try to parse a layout in the *value* at the given *cell path*
 pub fn try_layout(value: &Value, cell_path: &[&str]) -> Result<Option<Layout>, LabeledError> {
     match follow_cell_path(value, cell_path) {
@@ -284,6 +390,120 @@ pub fn try_layout(value: &Value, cell_path: &[&str]) -> Result<Option<Layout>, L
     }
 }
 
+/// parse the widths of the columns of the tables, i.e. `"auto"`, `"equal"` or a list of fixed
+/// widths and percentages, e.g. `[30%, 10]`
+///
+/// > :bulb: **Note**  
+/// > the widths are not clamped here, see [`ColumnWidth::clamp`]
+pub fn try_column_width(
+    value: &Value,
+    cell_path: &[&str],
+) -> Result<Option<ColumnWidth>, LabeledError> {
+    let invalid = |x: &str| LabeledError {
+        label: "invalid config".into(),
+        msg: format!(
+            r#"`$.{}` should be one of [auto, equal] or a list of widths, e.g. `["30%", 10]`, found {}"#,
+            cell_path.join("."),
+            x
+        ),
+        span: Some(value.span()),
+    };
+
+    match follow_cell_path(value, cell_path) {
+        Some(Value::String { val, .. }) => match val.as_str() {
+            "auto" => Ok(Some(ColumnWidth::Auto)),
+            "equal" => Ok(Some(ColumnWidth::Equal)),
+            x => Err(invalid(x)),
+        },
+        Some(Value::List { vals, .. }) => vals
+            .iter()
+            .map(|val| match val {
+                Value::Int { val, .. } => {
+                    Ok(WidthSpec::Fixed((*val).clamp(0, u16::MAX as i64) as u16))
+                }
+                Value::String { val, .. } => {
+                    match val.strip_suffix('%').map(|p| p.trim().parse::<i64>()) {
+                        Some(Ok(p)) => Ok(WidthSpec::Percent(p.clamp(0, u16::MAX as i64) as u16)),
+                        _ => Err(invalid(val)),
+                    }
+                }
+                x => Err(invalid(&x.get_type().to_string())),
+            })
+            .collect::<Result<Vec<WidthSpec>, LabeledError>>()
+            .map(|specs| Some(ColumnWidth::Custom(specs))),
+        Some(x) => Err(invalid_type(&x, cell_path, "string or list")),
+        _ => Ok(None),
+    }
+}
+
+/// parse the substitutions of the simple values, i.e. a record from the names of columns or types
+/// to records from the default rendering of the values to the text shown instead, see
+/// [`Substitutions`]
+pub fn try_substitutions(
+    value: &Value,
+    cell_path: &[&str],
+) -> Result<Option<Substitutions>, LabeledError> {
+    let substitutions = match follow_cell_path(value, cell_path) {
+        Some(Value::Record { val, .. }) => val,
+        Some(x) => return Err(invalid_type(&x, cell_path, "record")),
+        _ => return Ok(None),
+    };
+
+    let mut res = Substitutions::new();
+    for (name, substitution) in substitutions.iter() {
+        let path = [cell_path, &[name.as_str()]].concat();
+        let substitution = match substitution {
+            Value::Record { val, .. } => val,
+            Value::Closure { internal_span, .. } => {
+                return Err(LabeledError {
+                    label: "invalid config".into(),
+                    msg: format!(
+                        r#"`$.{}`: substitutions are not closures, give a record from the values to their rendering instead, e.g. `{{ok: "✔"}}`"#,
+                        path.join(".")
+                    ),
+                    span: Some(*internal_span),
+                })
+            }
+            x => return Err(invalid_type(x, &path, "record")),
+        };
+
+        let mut renderings = HashMap::new();
+        for (from, to) in substitution.iter() {
+            match to {
+                Value::String { val, .. } => renderings.insert(from.clone(), val.clone()),
+                x => {
+                    let path = [path.as_slice(), &[from.as_str()]].concat();
+                    return Err(invalid_type(x, &path, "string"));
+                }
+            };
+        }
+        res.insert(name.clone(), renderings);
+    }
+
+    Ok(Some(res))
+}
+
+pub fn try_log_level(value: &Value, cell_path: &[&str]) -> Result<Option<LogLevel>, LabeledError> {
+    match follow_cell_path(value, cell_path) {
+        Some(Value::String { val, .. }) => match val.as_str() {
+            "error" => Ok(Some(LogLevel::Error)),
+            "info" => Ok(Some(LogLevel::Info)),
+            "debug" => Ok(Some(LogLevel::Debug)),
+            x => Err(LabeledError {
+                label: "invalid config".into(),
+                msg: format!(
+                    r#"`$.{}` should be one of [error, info, debug] , found {}"#,
+                    cell_path.join("."),
+                    x
+                ),
+                span: Some(value.span()),
+            }),
+        },
+        Some(x) => Err(invalid_type(&x, cell_path, "string")),
+        _ => Ok(None),
+    }
+}
+
 /// follow a cell path into a Value, giving the resulting Value if it exists
 ///
 /// # Example
@@ -316,14 +536,14 @@ pub fn follow_cell_path(value: &Value, cell_path: &[&str]) -> Option<Value> {
 // TODO: add proper assert error messages
 #[cfg(test)]
 mod tests {
-    use crossterm::event::KeyCode;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
     use nu_plugin::LabeledError;
     use nu_protocol::{record, Record, Value};
     use ratatui::style::{Color, Modifier};
 
     use super::{
-        follow_cell_path, try_bool, try_color, try_fg_bg_colors, try_key, try_layout, try_modifier,
-        try_string,
+        follow_cell_path, parse_keys, try_bool, try_color, try_fg_bg_colors, try_key, try_layout,
+        try_modifier, try_string, try_usize,
     };
     use crate::config::{BgFgColorConfig, Layout};
 
@@ -406,6 +626,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn trying_usize() {
+        test_tried_error(
+            try_usize(&Value::test_string("not an int"), &[]),
+            "",
+            "should be a int, found string",
+        );
+        test_tried_error(
+            try_usize(&Value::test_int(-1), &[]),
+            "",
+            "should be a non-negative integer, found -1",
+        );
+
+        assert_eq!(try_usize(&Value::test_int(0), &[]), Ok(Some(0)));
+        assert_eq!(try_usize(&Value::test_int(123), &[]), Ok(Some(123)));
+        assert_eq!(try_usize(&Value::test_int(123), &["x"]), Ok(None));
+    }
+
     #[test]
     fn trying_key() {
         test_tried_error(
@@ -419,9 +657,9 @@ mod tests {
             "should be a string, found int",
         );
         test_tried_error(
-            try_key(&Value::test_string("enter"), &[]),
+            try_key(&Value::test_string("home"), &[]),
             "",
-            "should be a character or one of [up, down, left, right, escape] , found enter",
+            "should be a character or one of [up, down, left, right, escape, space, tab, backtab, enter] , found home",
         );
 
         let cases = vec![
@@ -430,6 +668,10 @@ mod tests {
             ("left", KeyCode::Left),
             ("right", KeyCode::Right),
             ("escape", KeyCode::Esc),
+            ("enter", KeyCode::Enter),
+            ("space", KeyCode::Char(' ')),
+            ("tab", KeyCode::Tab),
+            ("backtab", KeyCode::BackTab),
             ("a", KeyCode::Char('a')),
             ("b", KeyCode::Char('b')),
             ("x", KeyCode::Char('x')),
@@ -483,7 +725,7 @@ mod tests {
         test_tried_error(
             try_modifier(&Value::test_string("x"), &[]),
             "",
-            "should be the empty string, one of [italic, bold, underline, blink] or null, found x",
+            "should be the empty string, one of [italic, bold, underline, blink, reversed] or null, found x",
         );
 
         assert_eq!(
@@ -497,6 +739,7 @@ mod tests {
             ("bold", Modifier::BOLD),
             ("underline", Modifier::UNDERLINED),
             ("blink", Modifier::SLOW_BLINK),
+            ("reversed", Modifier::REVERSED),
         ];
 
         for (input, expected) in cases {
@@ -642,4 +885,45 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn parse_sequences_of_keys() {
+        let key = |code: KeyCode| KeyEvent::new(code, KeyModifiers::empty());
+        let char = |c: char| key(KeyCode::Char(c));
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (
+                Value::test_string("jj l p u"),
+                vec![char('j'), char('j'), char('l'), char('p'), char('u')],
+            ),
+            (
+                Value::test_string("g 12 enter escape space"),
+                vec![
+                    char('g'), char('1'), char('2'), key(KeyCode::Enter), key(KeyCode::Esc),
+                    char(' '),
+                ],
+            ),
+            (
+                Value::test_string("ctrl-q - alt-shift-x"),
+                vec![
+                    KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL),
+                    char('-'),
+                    KeyEvent::new(KeyCode::Char('x'), KeyModifiers::ALT | KeyModifiers::SHIFT),
+                ],
+            ),
+            (
+                Value::test_list(vec![Value::test_string("down"), Value::test_string("backspace")]),
+                vec![key(KeyCode::Down), key(KeyCode::Backspace)],
+            ),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(parse_keys(&value), Ok(expected));
+        }
+
+        assert!(parse_keys(&Value::test_string("ctrl-foo")).is_err());
+        assert!(parse_keys(&Value::test_list(vec![Value::test_string("jj")])).is_err());
+        assert!(parse_keys(&Value::test_int(1)).is_err());
+    }
 }
@@ -7,22 +7,25 @@ use ratatui::{
 };
 
 use nu_protocol::{Span, Value};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crate::config::Config;
 
+#[derive(Clone, PartialEq)]
 pub struct Editor {
     pub buffer: String,
     cursor_position: (usize, usize),
     width: usize,
+    title: String,
 }
 
-#[allow(clippy::derivable_impls)]
 impl Default for Editor {
     fn default() -> Self {
         Self {
             buffer: String::new(),
             cursor_position: (0, 0),
             width: 0,
+            title: "Editor".into(),
         }
     }
 }
@@ -38,8 +41,28 @@ impl Editor {
     pub(super) fn from_value(value: &Value) -> Self {
         Self {
             buffer: value.into_string(" ", &nu_protocol::Config::default()),
-            cursor_position: (0, 0),
-            width: 0,
+            ..Default::default()
+        }
+    }
+
+    /// an empty editor, to prompt the user for some input
+    pub(super) fn prompt(title: &str) -> Self {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    /// an editor with some *text* already typed in, to prompt the user for the rest of the input
+    ///
+    /// the cursor is at the end of the text.
+    pub(super) fn prompt_with(title: &str, text: &str) -> Self {
+        Self {
+            buffer: text.into(),
+            // NOTE: the cursor is wrapped on the next move, once the width of the editor is known
+            cursor_position: (text.chars().count(), 0),
+            title: title.into(),
+            ..Default::default()
         }
     }
 
@@ -48,47 +71,68 @@ impl Editor {
         y * self.width + x
     }
 
+    /// the number of characters in the buffer, which is not its length in bytes
+    fn nb_chars(&self) -> usize {
+        self.buffer.chars().count()
+    }
+
+    /// the position of the cursor on the screen, relative to the top left corner of the buffer
+    ///
+    /// > :bulb: **Note**  
+    /// > characters can be wider than one column, e.g. `日` takes two columns in the terminal
+    ///
+    /// > see the tests for detailed examples
+    fn cursor_on_screen(&self) -> (usize, usize) {
+        let width = self
+            .buffer
+            .chars()
+            .take(self.position())
+            .map(|c| c.width().unwrap_or(0))
+            .sum::<usize>();
+
+        (width % self.width, width / self.width)
+    }
+
     fn move_cursor_left(&mut self) {
-        let position = self
-            .position()
-            .saturating_sub(1)
-            .clamp(0, self.buffer.len());
+        let position = self.position().saturating_sub(1).clamp(0, self.nb_chars());
 
         self.cursor_position = (position % self.width, position / self.width);
     }
 
     fn move_cursor_right(&mut self) {
-        let position = self
-            .position()
-            .saturating_add(1)
-            .clamp(0, self.buffer.len());
+        let position = self.position().saturating_add(1).clamp(0, self.nb_chars());
 
         self.cursor_position = (position % self.width, position / self.width);
     }
 
     fn move_cursor_up(&mut self) {
         let (x, y) = self.cursor_position;
-        let y = y.saturating_sub(1).clamp(0, self.buffer.len() / self.width);
+        let y = y.saturating_sub(1).clamp(0, self.nb_chars() / self.width);
 
         self.cursor_position = (x, y);
     }
 
     fn move_cursor_down(&mut self) {
         let (x, y) = self.cursor_position;
-        let y = y.saturating_add(1).clamp(0, self.buffer.len() / self.width);
+        let y = y.saturating_add(1).clamp(0, self.nb_chars() / self.width);
 
         self.cursor_position = (x, y);
 
-        if self.position() > self.buffer.len() {
-            self.cursor_position = (
-                self.buffer.len() % self.width,
-                self.buffer.len() / self.width,
-            );
+        let nb_chars = self.nb_chars();
+        if self.position() > nb_chars {
+            self.cursor_position = (nb_chars % self.width, nb_chars / self.width);
         }
     }
 
     fn enter_char(&mut self, c: char) {
-        self.buffer.insert(self.position(), c);
+        // NOTE: the position is in chars but `insert` works on bytes
+        let index = self
+            .buffer
+            .char_indices()
+            .nth(self.position())
+            .map(|(i, _)| i)
+            .unwrap_or(self.buffer.len());
+        self.buffer.insert(index, c);
         self.move_cursor_right();
     }
 
@@ -138,8 +182,6 @@ impl Editor {
     }
 
     pub(super) fn render(&self, frame: &mut Frame, config: &Config) {
-        let title = "Editor";
-
         let block = Paragraph::new(self.buffer.as_str())
             .style(
                 Style::default()
@@ -147,19 +189,23 @@ impl Editor {
                     .bg(config.colors.editor.buffer.background),
             )
             .block(
-                Block::default().borders(Borders::ALL).title(title).style(
-                    Style::default()
-                        .fg(config.colors.editor.frame.foreground)
-                        .bg(config.colors.editor.frame.background),
-                ),
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(self.title.as_str())
+                    .style(
+                        Style::default()
+                            .fg(config.colors.editor.frame.foreground)
+                            .bg(config.colors.editor.frame.background),
+                    ),
             );
 
+        let buffer_width = self.buffer.width();
         let height = if self.buffer.is_empty() {
             1
-        } else if (self.buffer.len() % self.width) == 0 {
-            self.buffer.len() / self.width
+        } else if (buffer_width % self.width) == 0 {
+            buffer_width / self.width
         } else {
-            self.buffer.len() / self.width + 1
+            buffer_width / self.width + 1
         } as u16;
         let area = Rect {
             x: (frame.size().width - (self.width as u16 + 2)) / 2,
@@ -171,7 +217,7 @@ impl Editor {
         frame.render_widget(Clear, area); //this clears out the background
         frame.render_widget(block.wrap(Wrap { trim: false }), area);
 
-        let (x, y) = self.cursor_position;
+        let (x, y) = self.cursor_on_screen();
         frame.set_cursor(area.x + 1 + (x as u16), area.y + 1 + (y as u16))
     }
 }
@@ -253,4 +299,38 @@ mod tests {
             assert_eq!(editor.buffer, expected_buffer.to_string());
         }
     }
+
+    #[test]
+    fn edit_wide_characters() {
+        let mut editor = Editor::default();
+        editor.set_width(10 + 2);
+
+        let strokes = vec![
+            (KeyCode::Char('日'), "日", (2, 0)),
+            (KeyCode::Char('本'), "日本", (4, 0)),
+            (KeyCode::Left, "日本", (2, 0)),
+            (KeyCode::Char('a'), "日a本", (3, 0)),
+            (KeyCode::Right, "日a本", (5, 0)),
+            (KeyCode::Char('語'), "日a本語", (7, 0)),
+            (KeyCode::Char('é'), "日a本語é", (8, 0)),
+            (KeyCode::Char('😀'), "日a本語é😀", (0, 1)),
+            (KeyCode::Backspace, "日a本語é", (8, 0)),
+            (KeyCode::Left, "日a本語é", (7, 0)),
+            (KeyCode::Left, "日a本語é", (5, 0)),
+            (KeyCode::Delete, "日a本é", (5, 0)),
+        ];
+
+        for (key, expected_buffer, expected_cursor) in strokes {
+            editor.handle_key(&key);
+
+            assert_eq!(editor.buffer, expected_buffer.to_string());
+            assert_eq!(
+                editor.cursor_on_screen(),
+                expected_cursor,
+                "unexpected cursor after {:?} in {}",
+                key,
+                expected_buffer
+            );
+        }
+    }
 }
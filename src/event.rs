@@ -1,5 +1,6 @@
 use anyhow::Result;
-use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
+use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent};
+use std::collections::VecDeque;
 use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -17,14 +18,17 @@ pub enum Event {
     Resize(u16, u16),
 }
 
+/// a key waiting in the queue for longer than this is stale, see [`coalesce`]
+pub const STALE_KEY: Duration = Duration::from_millis(500);
+
 /// Terminal event handler.
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct EventHandler {
-    /// Event sender channel.
-    sender: mpsc::Sender<Event>,
+    /// Event sender channel, each event with the time it was read at.
+    sender: mpsc::Sender<(Event, Instant)>,
     /// Event receiver channel.
-    receiver: mpsc::Receiver<Event>,
+    receiver: mpsc::Receiver<(Event, Instant)>,
     /// Event handler thread.
     handler: thread::JoinHandle<()>,
 }
@@ -44,17 +48,23 @@ impl EventHandler {
                         .unwrap_or(tick_rate);
 
                     if event::poll(timeout).expect("no events available") {
-                        match event::read().expect("unable to read event") {
-                            CrosstermEvent::Key(e) => sender.send(Event::Key(e)),
-                            CrosstermEvent::Mouse(e) => sender.send(Event::Mouse(e)),
-                            CrosstermEvent::Resize(w, h) => sender.send(Event::Resize(w, h)),
-                            _ => Ok(()),
+                        let event = match event::read().expect("unable to read event") {
+                            CrosstermEvent::Key(e) => Some(Event::Key(e)),
+                            CrosstermEvent::Mouse(e) => Some(Event::Mouse(e)),
+                            CrosstermEvent::Resize(w, h) => Some(Event::Resize(w, h)),
+                            _ => None,
+                        };
+                        if let Some(event) = event {
+                            sender
+                                .send((event, Instant::now()))
+                                .expect("failed to send terminal event")
                         }
-                        .expect("failed to send terminal event")
                     }
 
                     if last_tick.elapsed() >= tick_rate {
-                        sender.send(Event::Tick).expect("failed to send tick event");
+                        sender
+                            .send((Event::Tick, Instant::now()))
+                            .expect("failed to send tick event");
                         last_tick = Instant::now();
                     }
                 }
@@ -71,7 +81,109 @@ impl EventHandler {
     ///
     /// This function will always block the current thread if
     /// there is no data available and it's possible for more data to be sent.
-    pub fn next(&self) -> Result<Event> {
+    pub fn next(&self) -> Result<(Event, Instant)> {
         Ok(self.receiver.recv()?)
     }
+
+    /// take all the events already waiting, without blocking, e.g. the keys typed while a frame
+    /// was being drawn
+    pub fn pending(&self) -> Vec<(Event, Instant)> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+/// take the repetitions of a *key* pressed at *at* off the front of the *queue* and give the
+/// number of times the key should be handled, to move several rows at once when a key is held
+/// down faster than the frames are drawn
+///
+/// - only the presses of the exact same key are taken, the ticks in between being dropped too,
+///   any other event ends the repetitions and is left in the queue
+/// - the repetitions that have waited for longer than *stale* at *now* are dropped, e.g. when the
+///   key has been released for a while, but the key is always handled at least once
+///
+/// > see the tests for detailed examples
+pub fn coalesce(
+    key: &KeyEvent,
+    at: Instant,
+    queue: &mut VecDeque<(Event, Instant)>,
+    now: Instant,
+    stale: Duration,
+) -> usize {
+    let is_fresh = |at: Instant| now.saturating_duration_since(at) <= stale;
+
+    let mut count = usize::from(is_fresh(at));
+    while let Some((event, at)) = queue.front() {
+        match event {
+            Event::Tick => {}
+            Event::Key(other)
+                if other.code == key.code
+                    && other.modifiers == key.modifiers
+                    && other.kind != KeyEventKind::Release =>
+            {
+                if is_fresh(*at) {
+                    count += 1;
+                }
+            }
+            _ => break,
+        }
+        queue.pop_front();
+    }
+
+    count.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::time::{Duration, Instant};
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{coalesce, Event};
+
+    #[test]
+    fn coalesce_repeated_keys() {
+        let now = Instant::now();
+        let stale = Duration::from_millis(500);
+        let key = |c: char| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::empty()));
+        let ago = |ms: u64| now - Duration::from_millis(ms);
+        let j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::empty());
+
+        // NOTE: fifty `j` and a `q` behind them
+        let mut queue: VecDeque<(Event, Instant)> = (0..50).map(|_| (key('j'), ago(10))).collect();
+        queue.push_back((key('q'), ago(5)));
+        assert_eq!(coalesce(&j, ago(20), &mut queue, now, stale), 51);
+        assert_eq!(queue.len(), 1, "the `q` is never dropped");
+        assert!(matches!(queue[0].0, Event::Key(k) if k.code == KeyCode::Char('q')));
+
+        // NOTE: the ticks are skipped, but the modifiers have to be the same
+        let mut queue = VecDeque::from(vec![
+            (key('j'), ago(10)),
+            (Event::Tick, ago(8)),
+            (key('j'), ago(5)),
+            (
+                Event::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL)),
+                ago(1),
+            ),
+        ]);
+        assert_eq!(coalesce(&j, now, &mut queue, now, stale), 3);
+        assert_eq!(queue.len(), 1);
+
+        // NOTE: the stale repetitions are dropped, the key itself always counts
+        let mut queue = VecDeque::from(vec![
+            (key('j'), ago(900)),
+            (key('j'), ago(800)),
+            (key('j'), ago(100)),
+            (key('k'), ago(700)),
+        ]);
+        assert_eq!(coalesce(&j, ago(1000), &mut queue, now, stale), 1);
+        assert_eq!(queue.len(), 1, "the stale `k` is still there");
+        let mut queue = VecDeque::from(vec![(key('j'), ago(900))]);
+        assert_eq!(coalesce(&j, ago(1000), &mut queue, now, stale), 1);
+        assert!(queue.is_empty());
+
+        // NOTE: nothing to coalesce
+        let mut queue = VecDeque::new();
+        assert_eq!(coalesce(&j, now, &mut queue, now, stale), 1);
+    }
 }
@@ -0,0 +1,704 @@
+//! open and save files from the disk as Nushell values, without the help of Nushell itself
+use std::{path::Path, time::SystemTime};
+
+use nu_plugin::LabeledError;
+use nu_protocol::{Record, Span, Value};
+
+use crate::nu::value::table_columns;
+
+/// the formats that can be opened, based on the extension of the file
+#[derive(Debug, PartialEq)]
+enum Format {
+    Nuon,
+    Json,
+    Toml,
+    Yaml,
+    Csv,
+    Tsv,
+}
+
+impl Format {
+    fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "nuon" => Some(Self::Nuon),
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "csv" => Some(Self::Csv),
+            "tsv" => Some(Self::Tsv),
+            _ => None,
+        }
+    }
+
+    fn from_path(path: &str) -> Option<Self> {
+        Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(Self::from_extension)
+    }
+}
+
+/// open the file at *path* and parse it based on its extension
+///
+/// the supported formats are JSON, TOML, YAML, CSV and TSV.
+///
+/// > :bulb: **Note**  
+/// > NUON files can only be parsed by Nushell itself, e.g. with `open file.nuon | explore`
+pub fn open_file(path: &str, span: Span) -> Result<Value, LabeledError> {
+    let error = |msg: String| LabeledError {
+        label: "could not open file".into(),
+        msg,
+        span: Some(span),
+    };
+
+    let format = match Format::from_path(path) {
+        Some(Format::Nuon) => {
+            return Err(error(format!(
+                "`{}` is a NUON file, please run `open {} | explore` instead",
+                path, path
+            )))
+        }
+        Some(format) => format,
+        None => {
+            return Err(error(format!(
+            "`{}` has an unsupported extension, expected one of [json, toml, yaml, yml, csv, tsv]",
+            path
+        )))
+        }
+    };
+
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| error(format!("could not read `{}`: {}", path, err)))?;
+
+    parse(&contents, &format, span)
+        .map_err(|err| error(format!("could not parse `{}`: {}", path, err)))
+}
+
+/// a file that is watched for changes on disk
+pub struct Watcher {
+    path: String,
+    span: Span,
+    /// the last time the file was modified, if it could be read
+    modified: Option<SystemTime>,
+}
+
+impl Watcher {
+    pub fn new(path: &str, span: Span) -> Self {
+        Self {
+            path: path.to_string(),
+            span,
+            modified: last_modified(path),
+        }
+    }
+
+    /// open the file again if it has been modified since the last poll
+    ///
+    /// > :bulb: **Note**  
+    /// > a file that disappears is reported once, as an error, and will be opened again as soon as
+    /// > it comes back
+    pub(crate) fn poll(&mut self) -> Option<Result<Value, LabeledError>> {
+        let modified = last_modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+
+        self.modified = modified;
+        Some(open_file(&self.path, self.span))
+    }
+}
+
+fn last_modified(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// parse the *contents* of a file in the given *format*
+fn parse(contents: &str, format: &Format, span: Span) -> Result<Value, String> {
+    match format {
+        Format::Json => serde_json::from_str::<serde_json::Value>(contents)
+            .map(|v| from_json(v, span))
+            .map_err(|e| e.to_string()),
+        Format::Toml => toml::from_str::<toml::Value>(contents)
+            .map(|v| from_toml(v, span))
+            .map_err(|e| e.to_string()),
+        Format::Yaml => serde_yaml::from_str::<serde_yaml::Value>(contents)
+            .map_err(|e| e.to_string())
+            .and_then(|v| from_yaml(v, span)),
+        Format::Csv => from_csv(contents, b',', span),
+        Format::Tsv => from_csv(contents, b'\t', span),
+        Format::Nuon => Err("NUON can only be parsed by Nushell".into()),
+    }
+}
+
+/// parse a string of the data as structured data, e.g. a JSON payload in a log
+///
+/// the string is tried as JSON first and then as TOML, only records and lists being accepted.
+///
+/// > :bulb: **Note**  
+/// > NUON can only be parsed by Nushell itself, so it is only supported when it is also valid
+/// > JSON
+pub(crate) fn parse_string(contents: &str) -> Result<Value, String> {
+    let value = parse(contents, &Format::Json, Span::unknown())
+        .or_else(|_| parse(contents, &Format::Toml, Span::unknown()))
+        .map_err(|_| "the string is neither JSON nor TOML".to_string())?;
+
+    match value {
+        Value::Record { .. } | Value::List { .. } => Ok(value),
+        x => Err(format!(
+            "the string is not structured data, found {}",
+            x.get_type()
+        )),
+    }
+}
+
+/// write a *value* to the file at *path*, in the format given by its extension
+///
+/// the supported formats are NUON, JSON, TOML and YAML.
+///
+/// > :bulb: **Note**  
+/// > dates are saved as RFC 3339 strings, file sizes as numbers of bytes and durations as numbers
+/// > of nanoseconds in all the formats but NUON
+pub(crate) fn save_file(value: &Value, path: &str) -> Result<(), String> {
+    let contents = match Format::from_path(path) {
+        Some(format @ (Format::Nuon | Format::Json | Format::Toml | Format::Yaml)) => {
+            serialize(value, &format)?
+        }
+        _ => {
+            return Err(format!(
+                "`{}` has an unsupported extension, expected one of [nuon, json, toml, yaml, yml]",
+                path
+            ))
+        }
+    };
+
+    std::fs::write(path, contents).map_err(|e| format!("could not write `{}`: {}", path, e))
+}
+
+/// the names of the formats a value can be serialized to, see [`to_format`]
+pub const OUTPUT_FORMATS: &str = "nuon, json, toml, yaml, csv, tsv";
+
+/// whether *name* is one of the [`OUTPUT_FORMATS`], e.g. to check it before exploring
+pub fn is_output_format(name: &str) -> bool {
+    Format::from_extension(name).is_some()
+}
+
+/// serialize a *value* as a string in the format called *name*, e.g. `json` or `csv`, see
+/// [`OUTPUT_FORMATS`]
+///
+/// > :bulb: **Note**  
+/// > only the tables, i.e. the lists of records, can be serialized as CSV or TSV
+pub fn to_format(value: &Value, name: &str) -> Result<String, String> {
+    match Format::from_extension(name) {
+        Some(format) => serialize(value, &format),
+        None => Err(format!(
+            "unsupported format `{}`, expected one of [{}]",
+            name, OUTPUT_FORMATS
+        )),
+    }
+}
+
+/// serialize a *value* in the given *format*
+fn serialize(value: &Value, format: &Format) -> Result<String, String> {
+    match format {
+        Format::Nuon => to_nuon(value),
+        Format::Json => serde_json::to_string_pretty(&to_json(value)?).map_err(|e| e.to_string()),
+        Format::Toml => toml::to_string_pretty(&to_json(value)?).map_err(|e| e.to_string()),
+        Format::Yaml => serde_yaml::to_string(&to_json(value)?).map_err(|e| e.to_string()),
+        Format::Csv => to_csv(value, b','),
+        Format::Tsv => to_csv(value, b'\t'),
+    }
+}
+
+/// serialize a table as CSV, with the given *delimiter*, the missing cells being empty
+fn to_csv(value: &Value, delimiter: u8) -> Result<String, String> {
+    let not_a_table = |x: &Value| {
+        format!(
+            "only tables can be serialized as CSV, found {}",
+            x.get_type()
+        )
+    };
+    let rows = match value {
+        Value::List { vals, .. } => vals
+            .iter()
+            .map(|v| v.as_record().map_err(|_| not_a_table(value)))
+            .collect::<Result<Vec<&Record>, String>>()?,
+        x => return Err(not_a_table(x)),
+    };
+    let columns = table_columns(rows.iter().copied());
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(vec![]);
+    writer.write_record(&columns).map_err(|e| e.to_string())?;
+    for row in rows {
+        let cells = columns
+            .iter()
+            .map(|column| match row.get(column) {
+                None | Some(Value::Nothing { .. }) => Ok(String::new()),
+                Some(Value::String { val, .. }) => Ok(val.clone()),
+                Some(Value::List { .. } | Value::Record { .. }) => Err(format!(
+                    "the cells of a CSV can only be simple values, found a nested `{}`",
+                    column
+                )),
+                Some(val) => to_nuon(val),
+            })
+            .collect::<Result<Vec<String>, String>>()?;
+        writer.write_record(&cells).map_err(|e| e.to_string())?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+fn to_json(value: &Value) -> Result<serde_json::Value, String> {
+    let json = match value {
+        Value::Nothing { .. } => serde_json::Value::Null,
+        Value::Bool { val, .. } => serde_json::Value::Bool(*val),
+        Value::Int { val, .. } | Value::Filesize { val, .. } | Value::Duration { val, .. } => {
+            serde_json::Value::from(*val)
+        }
+        Value::Float { val, .. } => serde_json::Number::from_f64(*val)
+            .map(serde_json::Value::Number)
+            .ok_or_else(|| format!("{} is not a valid JSON number", val))?,
+        Value::String { val, .. } => serde_json::Value::String(val.clone()),
+        Value::Date { val, .. } => serde_json::Value::String(val.to_rfc3339()),
+        Value::Binary { val, .. } => serde_json::Value::from(val.clone()),
+        Value::List { vals, .. } => serde_json::Value::Array(
+            vals.iter()
+                .map(to_json)
+                .collect::<Result<Vec<serde_json::Value>, String>>()?,
+        ),
+        Value::Record { val: rec, .. } => {
+            let mut map = serde_json::Map::new();
+            for (k, v) in rec.iter() {
+                map.insert(k.clone(), to_json(v)?);
+            }
+            serde_json::Value::Object(map)
+        }
+        x => return Err(format!("cannot save values of type {}", x.get_type())),
+    };
+
+    Ok(json)
+}
+
+fn to_nuon(value: &Value) -> Result<String, String> {
+    let nuon = match value {
+        Value::Nothing { .. } => "null".into(),
+        Value::Bool { val, .. } => val.to_string(),
+        Value::Int { val, .. } => val.to_string(),
+        Value::Float { val, .. } if val.is_nan() => "NaN".into(),
+        Value::Float { val, .. } if val.is_infinite() && *val > 0.0 => "inf".into(),
+        Value::Float { val, .. } if val.is_infinite() => "-inf".into(),
+        Value::Float { val, .. } => format!("{:?}", val),
+        Value::Filesize { val, .. } => format!("{}b", val),
+        Value::Duration { val, .. } => format!("{}ns", val),
+        Value::String { val, .. } => serde_json::to_string(val).map_err(|e| e.to_string())?,
+        Value::Date { val, .. } => val.to_rfc3339(),
+        Value::Binary { val, .. } => format!(
+            "0x[{}]",
+            val.iter().map(|b| format!("{:02x}", b)).collect::<String>()
+        ),
+        Value::List { vals, .. } => format!(
+            "[{}]",
+            vals.iter()
+                .map(to_nuon)
+                .collect::<Result<Vec<String>, String>>()?
+                .join(", ")
+        ),
+        Value::Record { val: rec, .. } => format!(
+            "{{{}}}",
+            rec.iter()
+                .map(|(k, v)| {
+                    let key = serde_json::to_string(k).map_err(|e| e.to_string())?;
+                    Ok(format!("{}: {}", key, to_nuon(v)?))
+                })
+                .collect::<Result<Vec<String>, String>>()?
+                .join(", ")
+        ),
+        x => return Err(format!("cannot save values of type {}", x.get_type())),
+    };
+
+    Ok(nuon)
+}
+
+fn from_json(value: serde_json::Value, span: Span) -> Value {
+    match value {
+        serde_json::Value::Null => Value::nothing(span),
+        serde_json::Value::Bool(b) => Value::bool(b, span),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::int(i, span),
+            None => Value::float(n.as_f64().unwrap_or(f64::NAN), span),
+        },
+        serde_json::Value::String(s) => Value::string(s, span),
+        serde_json::Value::Array(vals) => {
+            Value::list(vals.into_iter().map(|v| from_json(v, span)).collect(), span)
+        }
+        serde_json::Value::Object(map) => {
+            let mut record = Record::new();
+            for (k, v) in map {
+                record.push(k, from_json(v, span));
+            }
+            Value::record(record, span)
+        }
+    }
+}
+
+fn from_toml(value: toml::Value, span: Span) -> Value {
+    match value {
+        toml::Value::String(s) => Value::string(s, span),
+        toml::Value::Integer(i) => Value::int(i, span),
+        toml::Value::Float(f) => Value::float(f, span),
+        toml::Value::Boolean(b) => Value::bool(b, span),
+        // NOTE: TOML dates can be partial, e.g. only a time, so they are kept as strings
+        toml::Value::Datetime(d) => Value::string(d.to_string(), span),
+        toml::Value::Array(vals) => {
+            Value::list(vals.into_iter().map(|v| from_toml(v, span)).collect(), span)
+        }
+        toml::Value::Table(table) => {
+            let mut record = Record::new();
+            for (k, v) in table {
+                record.push(k, from_toml(v, span));
+            }
+            Value::record(record, span)
+        }
+    }
+}
+
+fn from_yaml(value: serde_yaml::Value, span: Span) -> Result<Value, String> {
+    let value = match value {
+        serde_yaml::Value::Null => Value::nothing(span),
+        serde_yaml::Value::Bool(b) => Value::bool(b, span),
+        serde_yaml::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::int(i, span),
+            None => Value::float(n.as_f64().unwrap_or(f64::NAN), span),
+        },
+        serde_yaml::Value::String(s) => Value::string(s, span),
+        serde_yaml::Value::Sequence(vals) => Value::list(
+            vals.into_iter()
+                .map(|v| from_yaml(v, span))
+                .collect::<Result<Vec<Value>, String>>()?,
+            span,
+        ),
+        serde_yaml::Value::Mapping(map) => {
+            let mut record = Record::new();
+            for (k, v) in map {
+                let key = match k {
+                    serde_yaml::Value::String(s) => s,
+                    serde_yaml::Value::Number(n) => n.to_string(),
+                    serde_yaml::Value::Bool(b) => b.to_string(),
+                    _ => return Err("only simple keys are supported in YAML mappings".into()),
+                };
+                record.push(key, from_yaml(v, span)?);
+            }
+            Value::record(record, span)
+        }
+        serde_yaml::Value::Tagged(tagged) => from_yaml(tagged.value, span)?,
+    };
+
+    Ok(value)
+}
+
+/// parse a CSV-like table, where the first row is the header
+///
+/// as in Nushell, cells that look like integers or floats are parsed as such.
+fn from_csv(contents: &str, delimiter: u8, span: Span) -> Result<Value, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(contents.as_bytes());
+
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+
+    let mut rows = vec![];
+    for row in reader.records() {
+        let row = row.map_err(|e| e.to_string())?;
+
+        let mut record = Record::new();
+        for (header, cell) in headers.iter().zip(row.iter()) {
+            let cell = if let Ok(i) = cell.parse::<i64>() {
+                Value::int(i, span)
+            } else if let Ok(f) = cell.parse::<f64>() {
+                Value::float(f, span)
+            } else {
+                Value::string(cell, span)
+            };
+            record.push(header, cell);
+        }
+        rows.push(Value::record(record, span));
+    }
+
+    Ok(Value::list(rows, span))
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::{record, Span, Value};
+
+    use super::{parse, parse_string, save_file, to_format, Format, Watcher};
+
+    #[test]
+    fn format_from_extension() {
+        #[rustfmt::skip]
+        let cases = vec![
+            ("json", Some(Format::Json)),
+            ("JSON", Some(Format::Json)),
+            ("toml", Some(Format::Toml)),
+            ("yaml", Some(Format::Yaml)),
+            ("yml", Some(Format::Yaml)),
+            ("csv", Some(Format::Csv)),
+            ("tsv", Some(Format::Tsv)),
+            ("nuon", Some(Format::Nuon)),
+            ("txt", None),
+        ];
+
+        for (extension, expected) in cases {
+            assert_eq!(Format::from_extension(extension), expected);
+        }
+    }
+
+    #[test]
+    fn parse_formats() {
+        let expected = Value::test_record(record! {
+            "a" => Value::test_int(1),
+            "b" => Value::test_list(vec![
+                Value::test_string("foo"),
+                Value::test_float(2.5),
+            ]),
+            "c" => Value::test_record(record! {
+                "d" => Value::test_bool(true),
+            }),
+        });
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (r#"{"a": 1, "b": ["foo", 2.5], "c": {"d": true}}"#, Format::Json),
+            ("a = 1\nb = [\"foo\", 2.5]\n[c]\nd = true\n", Format::Toml),
+            ("a: 1\nb:\n  - foo\n  - 2.5\nc:\n  d: true\n", Format::Yaml),
+        ];
+
+        for (contents, format) in cases {
+            assert_eq!(
+                parse(contents, &format, Span::test_data()),
+                Ok(expected.clone()),
+                "could not parse {:?} as {:?}",
+                contents,
+                format
+            );
+        }
+
+        let expected = Value::test_list(vec![
+            Value::test_record(record! {
+                "name" => Value::test_string("foo"),
+                "size" => Value::test_int(1),
+            }),
+            Value::test_record(record! {
+                "name" => Value::test_string("bar"),
+                "size" => Value::test_float(2.5),
+            }),
+        ]);
+        assert_eq!(
+            parse(
+                "name,size\nfoo,1\nbar,2.5\n",
+                &Format::Csv,
+                Span::test_data()
+            ),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            parse(
+                "name\tsize\nfoo\t1\nbar\t2.5\n",
+                &Format::Tsv,
+                Span::test_data()
+            ),
+            Ok(expected)
+        );
+
+        assert!(parse("{", &Format::Json, Span::test_data()).is_err());
+        assert!(parse("a = ", &Format::Toml, Span::test_data()).is_err());
+    }
+
+    #[test]
+    fn parse_strings() {
+        let expected = Value::test_record(record! {
+            "a" => Value::test_list(vec![Value::test_int(1), Value::test_int(2)]),
+        });
+        assert_eq!(parse_string(r#"{"a": [1, 2]}"#), Ok(expected.clone()));
+        assert_eq!(parse_string("a = [1, 2]"), Ok(expected));
+        assert_eq!(
+            parse_string("[1, 2]"),
+            Ok(Value::test_list(vec![
+                Value::test_int(1),
+                Value::test_int(2)
+            ]))
+        );
+
+        #[rustfmt::skip]
+        let cases = vec![
+            ("123", "the string is not structured data, found int"),
+            (r#""foo""#, "the string is not structured data, found string"),
+            ("some text", "the string is neither JSON nor TOML"),
+            ("{a: 1}", "the string is neither JSON nor TOML"),
+        ];
+        for (contents, expected) in cases {
+            assert_eq!(
+                parse_string(contents),
+                Err(expected.into()),
+                "parsing {:?}",
+                contents
+            );
+        }
+    }
+
+    #[test]
+    fn watch_file() {
+        let path =
+            std::env::temp_dir().join(format!("nu_plugin_explore-{}.json", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        std::fs::write(&path, "[1]").unwrap();
+
+        let mut watcher = Watcher::new(path_str, Span::test_data());
+        assert_eq!(watcher.poll(), None);
+
+        // NOTE: the modification time is forced because some file systems have a coarse resolution
+        std::fs::write(&path, "[1, 2]").unwrap();
+        watcher.modified = Some(std::time::SystemTime::UNIX_EPOCH);
+        assert_eq!(
+            watcher.poll(),
+            Some(Ok(Value::test_list(vec![
+                Value::test_int(1),
+                Value::test_int(2)
+            ])))
+        );
+        assert_eq!(watcher.poll(), None);
+
+        std::fs::write(&path, "[1, ").unwrap();
+        watcher.modified = Some(std::time::SystemTime::UNIX_EPOCH);
+        assert!(matches!(watcher.poll(), Some(Err(_))));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(watcher.poll(), Some(Err(_))));
+        assert_eq!(watcher.poll(), None);
+    }
+
+    #[test]
+    fn save_formats() {
+        let value = Value::test_record(record! {
+            "a" => Value::test_int(1),
+            "b" => Value::test_list(vec![
+                Value::test_string("foo \"bar\""),
+                Value::test_float(2.0),
+            ]),
+            "c" => Value::test_record(record! {
+                "d" => Value::test_bool(true),
+            }),
+        });
+
+        let dir = std::env::temp_dir();
+        let path = |extension: &str| {
+            dir.join(format!(
+                "nu_plugin_explore-save-{}.{}",
+                std::process::id(),
+                extension
+            ))
+            .to_str()
+            .unwrap()
+            .to_string()
+        };
+
+        // NOTE: the saved files can be opened back to the same value
+        for (extension, format) in [
+            ("json", Format::Json),
+            ("toml", Format::Toml),
+            ("yaml", Format::Yaml),
+        ] {
+            let path = path(extension);
+            save_file(&value, &path).unwrap();
+            let contents = std::fs::read_to_string(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(
+                parse(&contents, &format, Span::test_data()),
+                Ok(value.clone()),
+                "could not save {:?}",
+                format
+            );
+        }
+
+        let nuon = path("nuon");
+        save_file(&value, &nuon).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&nuon).unwrap(),
+            r#"{"a": 1, "b": ["foo \"bar\"", 2.0], "c": {"d": true}}"#
+        );
+        std::fs::remove_file(&nuon).unwrap();
+
+        assert!(save_file(&value, &path("csv")).is_err());
+        assert!(save_file(&Value::test_int(1), &path("toml")).is_err());
+    }
+
+    #[test]
+    fn serialize_formats() {
+        let record = Value::test_record(record! {
+            "a" => Value::test_int(1),
+            "b" => Value::test_list(vec![
+                Value::test_string("foo, \"bar\""),
+                Value::test_float(2.5),
+            ]),
+            "c" => Value::test_record(record! {
+                "d" => Value::test_bool(true),
+            }),
+        });
+        let table = Value::test_list(vec![
+            Value::test_record(record! {
+                "name" => Value::test_string("foo, bar"),
+                "size" => Value::test_int(1),
+            }),
+            Value::test_record(record! {
+                "name" => Value::test_string("baz"),
+                "size" => Value::test_float(2.5),
+            }),
+        ]);
+
+        // NOTE: the serialized values can be parsed back to the same value
+        #[rustfmt::skip]
+        let cases = vec![
+            (&record, "json", Format::Json),
+            (&table, "json", Format::Json),
+            (&record, "toml", Format::Toml),
+            (&record, "yaml", Format::Yaml),
+            (&table, "yaml", Format::Yaml),
+            (&table, "csv", Format::Csv),
+            (&table, "tsv", Format::Tsv),
+        ];
+        for (value, name, format) in cases {
+            let text = to_format(value, name).unwrap();
+            assert_eq!(
+                parse(&text, &format, Span::test_data()),
+                Ok(value.clone()),
+                "could not serialize {} as {}",
+                value.get_type(),
+                name
+            );
+        }
+
+        assert_eq!(
+            to_format(&record, "nuon"),
+            Ok(r#"{"a": 1, "b": ["foo, \"bar\"", 2.5], "c": {"d": true}}"#.into())
+        );
+
+        // NOTE: the missing cells of a table are empty
+        let ragged = Value::test_list(vec![
+            Value::test_record(record! { "a" => Value::test_int(1) }),
+            Value::test_record(record! { "b" => Value::test_nothing() }),
+        ]);
+        assert_eq!(to_format(&ragged, "csv"), Ok("a,b\n1,\n,\n".into()));
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (&record, "csv", "only tables can be serialized as CSV, found record<a: int, b: list<any>, c: record<d: bool>>"),
+            (&table, "xml", "unsupported format `xml`, expected one of [nuon, json, toml, yaml, csv, tsv]"),
+        ];
+        for (value, name, expected) in cases {
+            assert_eq!(to_format(value, name), Err(expected.into()));
+        }
+        assert!(to_format(&table, "toml").is_err());
+    }
+}
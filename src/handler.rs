@@ -1,4 +1,9 @@
-use crossterm::event::KeyEvent;
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use nu_protocol::{
     ast::{CellPath, PathMember},
@@ -6,10 +11,16 @@ use nu_protocol::{
 };
 
 use crate::{
-    app::{App, Mode},
-    config::Config,
+    app::{App, Confirmation, Mode, PendingMacro, StatusMessage},
+    command::Command,
+    config::{repr_keycode, Config, LogLevel},
+    file::save_file,
     navigation::{self, Direction},
-    nu::value::transpose,
+    nu::{
+        search::search,
+        value::{follow, is_nushell_cell_path, to_columns, transpose},
+    },
+    ui,
 };
 
 /// the result of a state transition
@@ -17,7 +28,8 @@ use crate::{
 pub enum TransitionResult {
     Quit,
     Continue,
-    Return(Value),
+    /// the value to give back to Nushell, shared with the [`App`] when peeking the whole data
+    Return(Rc<Value>),
     Mutate(Value, CellPath),
     Error(String),
 }
@@ -29,53 +41,538 @@ impl TransitionResult {
     }
 }
 
+/// the maximum time between two `ctrl-c` for the second one to force quit, see [`ForceQuit`]
+const DOUBLE_CTRL_C: Duration = Duration::from_secs(1);
+
+/// the hard-wired escape hatch, to quit whatever the state of the [`App`]
+///
+/// `ctrl-q`, or `ctrl-c` twice within a second, always quits, before the keys are dispatched
+/// by [`handle_key_events`], so that neither the mode, a pending confirmation, unsaved edits nor
+/// a broken config can get in the way.
+///
+/// > :bulb: **Note**  
+/// > these keys can not be remapped
+#[derive(Default)]
+pub struct ForceQuit {
+    /// when the last `ctrl-c` was pressed, if it was the last key
+    last_ctrl_c: Option<Instant>,
+}
+
+impl ForceQuit {
+    /// whether the key event forces to quit
+    ///
+    /// > :bulb: **Note**  
+    /// > a first `ctrl-c` sets a hint in the status bar and is not dispatched any further, see
+    /// > [`ForceQuit::is_armed`]
+    pub fn pressed(&mut self, key_event: &KeyEvent) -> bool {
+        if !key_event.modifiers.contains(KeyModifiers::CONTROL) {
+            self.last_ctrl_c = None;
+            return false;
+        }
+
+        match key_event.code {
+            KeyCode::Char('q') => true,
+            KeyCode::Char('c') => match self.last_ctrl_c.take() {
+                Some(last) if last.elapsed() <= DOUBLE_CTRL_C => true,
+                _ => {
+                    self.last_ctrl_c = Some(Instant::now());
+                    false
+                }
+            },
+            _ => {
+                self.last_ctrl_c = None;
+                false
+            }
+        }
+    }
+
+    /// whether the last key was a first `ctrl-c`, waiting for the second one
+    pub fn is_armed(&self) -> bool {
+        self.last_ctrl_c.is_some()
+    }
+}
+
 /// Handles the key events and updates the state of [`App`].
-#[allow(clippy::collapsible_if)]
+///
+/// in NORMAL mode, the keys of the macros are handled first, see
+/// [`crate::config::KeyBindingsMap::record_macro`]: the keys are recorded while a macro is being
+/// recorded, and the keys of a replayed macro go through the exact same transitions as the typed
+/// ones, see [`replay`].
+///
+/// a count typed before a replay replays the macro that many times, and a count typed before a
+/// navigation key moves that many times, as `3j` in Vim, the other keys ignoring it.
+///
+/// > :bulb: **Note**  
+/// > the keys of the macros themselves, and the numbers typed before a replay, are not recorded,
+/// > a counted move being recorded as that many moves
 pub fn handle_key_events(
     key_event: KeyEvent,
     app: &mut App,
     config: &Config,
 ) -> Result<TransitionResult, ShellError> {
+    // NOTE: the key after the record or the replay key is the register of the macro
+    if let Some(pending) = app.pending_macro.take() {
+        let count = std::mem::take(&mut app.count).max(1);
+        let register = match key_event.code {
+            KeyCode::Char(c) if c.is_ascii_alphanumeric() => c,
+            KeyCode::Char('@') if pending == PendingMacro::Replay => match app.last_macro {
+                Some(register) => register,
+                None => return Ok(TransitionResult::Error("no macro replayed yet".into())),
+            },
+            _ => {
+                app.status_message = Some(StatusMessage::Info("cancelled".into()));
+                return Ok(TransitionResult::Continue);
+            }
+        };
+
+        match pending {
+            PendingMacro::Record => {
+                app.recording = Some((register, vec![]));
+                return Ok(TransitionResult::Continue);
+            }
+            PendingMacro::Replay => return replay(app, config, register, count),
+        }
+    }
+
+    let is_free = app.mode == Mode::Normal
+        && app.confirmation.is_none()
+        && app.stats.is_none()
+        && app.info.is_none()
+        && app.differences.is_empty();
+    if is_free {
+        if key_event.code == config.keybindings.record_macro {
+            match app.recording.take() {
+                Some((register, keys)) => {
+                    app.status_message = Some(StatusMessage::Info(format!(
+                        "recorded {} keys in @{}",
+                        keys.len(),
+                        register
+                    )));
+                    app.macros.insert(register, keys);
+                }
+                None => app.pending_macro = Some(PendingMacro::Record),
+            }
+            return Ok(TransitionResult::Continue);
+        } else if key_event.code == config.keybindings.replay_macro {
+            app.pending_macro = Some(PendingMacro::Replay);
+            return Ok(TransitionResult::Continue);
+        } else if let KeyCode::Char(c @ '0'..='9') = key_event.code {
+            if c != '0' || app.count > 0 {
+                let digit = c.to_digit(10).unwrap() as usize;
+                app.count = app.count.saturating_mul(10).saturating_add(digit);
+                return Ok(TransitionResult::Continue);
+            }
+        }
+    }
+    let count = std::mem::take(&mut app.count);
+
+    let navigation = &config.keybindings.navigation;
+    let is_move = [
+        navigation.up,
+        navigation.down,
+        navigation.left,
+        navigation.right,
+    ]
+    .contains(&key_event.code);
+    let repeat = if is_free && is_move { count.max(1) } else { 1 };
+
+    let mut result = Ok(TransitionResult::Continue);
+    for _ in 0..repeat {
+        if let Some((_, keys)) = &mut app.recording {
+            keys.push(key_event);
+        }
+        result = logged_transition(key_event, app, config);
+        app.forget_range_outside_view();
+        if !matches!(result, Ok(TransitionResult::Continue)) {
+            break;
+        }
+    }
+    result
+}
+
+/// replay the keys of the macro in a *register* *count* times, through the same transitions as
+/// the typed keys
+///
+/// the edits are applied as they are replayed, for the next keys to see them, and the editor is
+/// given the width of the screen as if a frame had been drawn between the keys.
+///
+/// > :bulb: **Note**  
+/// > the replay stops at the first error, so that a macro that does not fit the data anymore
+/// > does not go on editing it
+fn replay(
+    app: &mut App,
+    config: &Config,
+    register: char,
+    count: usize,
+) -> Result<TransitionResult, ShellError> {
+    let keys = match app.macros.get(&register) {
+        Some(keys) => keys.clone(),
+        None => {
+            return Ok(TransitionResult::Error(format!(
+                "no macro in @{}",
+                register
+            )))
+        }
+    };
+    app.last_macro = Some(register);
+
+    for _ in 0..count {
+        for key_event in &keys {
+            app.fit_editor();
+            match logged_transition(*key_event, app, config)? {
+                TransitionResult::Continue => {}
+                TransitionResult::Mutate(cell, path) => app.apply_mutation(&cell, &path),
+                TransitionResult::Error(err) => {
+                    return Ok(TransitionResult::Error(format!(
+                        "replay of @{} stopped: {}",
+                        register, err
+                    )))
+                }
+                result @ (TransitionResult::Quit | TransitionResult::Return(_)) => {
+                    return Ok(result)
+                }
+            }
+        }
+    }
+
+    Ok(TransitionResult::Continue)
+}
+
+/// make the transition of a key and log it
+///
+/// the transition is logged, see [`crate::config::LogConfig`], as a line with the mode before
+/// the key, the key, the resulting action and the *cell path* after the transition, e.g.
+/// `INFO mode=NORMAL key=l action=continue path=$.a`
+/// - errors are logged at the `error` level
+/// - actions other than `continue`, and changes of mode or *cell path*, at the `info` level
+/// - any other key at the `debug` level
+///
+/// > :bulb: **Note**  
+/// > the first failure to write to the log is shown in the status bar, the log is then disabled
+fn logged_transition(
+    key_event: KeyEvent,
+    app: &mut App,
+    config: &Config,
+) -> Result<TransitionResult, ShellError> {
+    // NOTE: the state before the transition is only copied when something could be logged
+    let before = app
+        .logger
+        .enabled(LogLevel::Error)
+        .then(|| (app.mode.clone(), app.position.members.clone()));
+
+    let result = transition(key_event, app, config);
+
+    if let Some((mode, members)) = before {
+        let (level, action) = match &result {
+            Ok(TransitionResult::Continue) => {
+                if mode != app.mode || members != app.position.members {
+                    (LogLevel::Info, "continue".to_string())
+                } else {
+                    (LogLevel::Debug, "continue".to_string())
+                }
+            }
+            Ok(TransitionResult::Quit) => (LogLevel::Info, "quit".to_string()),
+            Ok(TransitionResult::Return(_)) => (LogLevel::Info, "return".to_string()),
+            Ok(TransitionResult::Mutate(_, path)) => (
+                LogLevel::Info,
+                format!("mutate({})", ui::repr_cell_path(&path.members)),
+            ),
+            Ok(TransitionResult::Error(err)) => (LogLevel::Error, format!("error({})", err)),
+            Err(err) => (LogLevel::Error, format!("error({})", err)),
+        };
+        let line = format!(
+            "mode={} key={} action={} path={}",
+            mode,
+            repr_keycode(&key_event.code),
+            action,
+            ui::repr_cell_path(&app.position.members),
+        );
+        if let Err(err) = app.logger.log(level, &line) {
+            app.status_message = Some(StatusMessage::Error(err));
+        }
+    }
+
+    result
+}
+
+/// the actual transition of [`logged_transition`], without the log
+#[allow(clippy::collapsible_if)]
+fn transition(
+    key_event: KeyEvent,
+    app: &mut App,
+    config: &Config,
+) -> Result<TransitionResult, ShellError> {
+    // NOTE: a pending confirmation takes precedence over all the modes
+    if let Some(confirmation) = app.confirmation.take() {
+        if key_event.code == KeyCode::Char('y') {
+            return Ok(run_command(app, &confirmation.command, config, true));
+        }
+        app.status_message = Some(StatusMessage::Info("cancelled".into()));
+        return Ok(TransitionResult::Continue);
+    }
+
+    // NOTE: the statistics of a column are shown on top of everything until the next key press
+    if app.stats.take().is_some() {
+        return Ok(TransitionResult::Continue);
+    }
+    // NOTE: the same goes for the information about the value under the cursor
+    if app.info.take().is_some() {
+        return Ok(TransitionResult::Continue);
+    }
+
+    // NOTE: the differences of a diff are shown on top of everything until closed
+    if !app.differences.is_empty() {
+        if key_event.code == config.keybindings.normal {
+            app.differences = vec![];
+        } else if key_event.code == config.keybindings.navigation.down {
+            app.selected_difference = (app.selected_difference + 1).min(app.differences.len() - 1);
+        } else if key_event.code == config.keybindings.navigation.up {
+            app.selected_difference = app.selected_difference.saturating_sub(1);
+        }
+        return Ok(TransitionResult::Continue);
+    }
+
+    let navigation = &config.keybindings.navigation;
+    if key_event.code != navigation.down && key_event.code != navigation.up {
+        app.stop_accelerating();
+    }
+
     match app.mode {
         Mode::Normal => {
             if key_event.code == config.keybindings.quit {
-                return Ok(TransitionResult::Quit);
+                return Ok(run_command(
+                    app,
+                    &Command::Quit { force: false },
+                    config,
+                    false,
+                ));
             } else if key_event.code == config.keybindings.insert {
+                if config.read_only {
+                    app.status_message = Some(StatusMessage::Info("read-only".into()));
+                    return Ok(TransitionResult::Continue);
+                }
                 match app.enter_editor() {
                     Ok(_) => return Ok(TransitionResult::Continue),
                     Err(err) => return Ok(TransitionResult::Error(err)),
                 }
+            } else if key_event.code == config.keybindings.pin {
+                match app.toggle_pin() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.split {
+                match app.toggle_split() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.other_pane {
+                match app.switch_pane() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.info {
+                match app.show_info() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.next_sibling {
+                match navigation::go_to_ancestor_sibling(app, Direction::Down) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.previous_sibling {
+                match navigation::go_to_ancestor_sibling(app, Direction::Up) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.open {
+                match app.open_under(config.allow_open) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.validate {
+                match app.validate_view() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.next_failure {
+                match app.jump_to_failure(true) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.previous_failure {
+                match app.jump_to_failure(false) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.append {
+                if config.read_only {
+                    app.status_message = Some(StatusMessage::Info("read-only".into()));
+                    return Ok(TransitionResult::Continue);
+                }
+                match app.enter_insertion() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
             } else if key_event.code == config.keybindings.peek {
                 app.mode = Mode::Peeking;
                 return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.peek_under_quick {
+                return Ok(run_command(
+                    app,
+                    &Command::Peek { force: false },
+                    config,
+                    false,
+                ));
+            } else if key_event.code == config.keybindings.range {
+                match app.toggle_range() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.normal && app.range.is_some() {
+                app.range = None;
+                app.status_message = Some(StatusMessage::Info("range cancelled".into()));
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.delete_range {
+                return Ok(run_command(
+                    app,
+                    &Command::DeleteRange { force: false },
+                    config,
+                    false,
+                ));
+            } else if key_event.code == config.keybindings.goto {
+                app.enter_goto();
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.search {
+                app.enter_search();
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.next_match {
+                match app.jump_to_next_match(true) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.previous_match {
+                match app.jump_to_next_match(false) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.command {
+                app.enter_command("");
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.write {
+                app.enter_command("write ");
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.diff {
+                match app.diff(&config.diff) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.stats {
+                match app.column_stats() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.raw {
+                app.raw = !app.raw;
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.deeper_preview {
+                app.preview_depth = app.preview_depth.saturating_add(1);
+                app.status_message = Some(StatusMessage::Info(format!(
+                    "preview depth: {}",
+                    app.preview_depth
+                )));
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.shallower_preview {
+                app.preview_depth = app.preview_depth.saturating_sub(1);
+                app.status_message = Some(StatusMessage::Info(format!(
+                    "preview depth: {}",
+                    app.preview_depth
+                )));
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.mark {
+                app.toggle_mark();
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.clear_marks {
+                app.clear_marks();
+                return Ok(TransitionResult::Continue);
             } else if key_event.code == config.keybindings.navigation.down {
-                navigation::go_up_or_down_in_data(app, Direction::Down);
+                let step = app.accelerate(key_event.code, Instant::now(), &config.acceleration);
+                for _ in 0..step {
+                    navigation::go_up_or_down_in_data(app, Direction::Down);
+                }
                 return Ok(TransitionResult::Continue);
             } else if key_event.code == config.keybindings.navigation.up {
-                navigation::go_up_or_down_in_data(app, Direction::Up);
+                let step = app.accelerate(key_event.code, Instant::now(), &config.acceleration);
+                for _ in 0..step {
+                    navigation::go_up_or_down_in_data(app, Direction::Up);
+                }
                 return Ok(TransitionResult::Continue);
             } else if key_event.code == config.keybindings.navigation.right {
                 navigation::go_deeper_in_data(app);
+                if config.navigation.auto_descend {
+                    navigation::auto_descend(app);
+                }
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.column_width {
+                app.column_width = app.column_width.next(&config.display.column_width);
+                app.status_message = Some(StatusMessage::Info(format!(
+                    "column width: {}",
+                    app.column_width
+                )));
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.descend {
+                navigation::go_deeper_in_data(app);
+                navigation::auto_descend(app);
                 return Ok(TransitionResult::Continue);
             } else if key_event.code == config.keybindings.navigation.left {
-                navigation::go_back_in_data(app);
+                // NOTE: the top of the data parsed from a string goes back to the string, and the
+                // top of the groups of a table back to the table
+                if app.position.members.len() <= 1 && app.column.is_none() {
+                    app.leave_parsed_view();
+                } else {
+                    navigation::go_back_in_data(app);
+                }
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.parse {
+                match app.parse_string() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.navigation.next_column {
+                navigation::go_to_next_column(app, true);
+                return Ok(TransitionResult::Continue);
+            } else if key_event.code == config.keybindings.navigation.previous_column {
+                navigation::go_to_next_column(app, false);
                 return Ok(TransitionResult::Continue);
             } else if key_event.code == config.keybindings.transpose {
+                if config.read_only {
+                    app.status_message = Some(StatusMessage::Info("read-only".into()));
+                    return Ok(TransitionResult::Continue);
+                }
+
                 let mut path = app.position.clone();
                 path.members.pop();
+                // NOTE: the hidden items could not be put back after a transposed list
+                if path.members.is_empty() && app.parsed.is_empty() && !app.hidden.is_empty() {
+                    return Ok(TransitionResult::Error(
+                        "can not transpose a list limited by `--limit`".into(),
+                    ));
+                }
+                // NOTE: the columns entered above the transposed view are still there
+                app.column = None;
+                app.column_depths.retain(|&d| d <= path.members.len());
 
-                let view = app.value.clone().follow_cell_path(&path.members, false)?;
+                let view = follow(&app.value, &path.members).unwrap_or_else(|| {
+                    panic!("unexpected error when following {:?}", path.members)
+                });
                 let transpose = transpose(&view);
 
-                if transpose != view {
+                if transpose != *view {
                     match transpose.clone() {
                         Value::Record { val: rec, .. } => {
                             // NOTE: app.position.members should never be empty by construction
-                            *app.position.members.last_mut().unwrap() = PathMember::String {
-                                val: rec.cols.first().unwrap_or(&"".to_string()).to_string(),
-                                span: Span::unknown(),
-                                optional: rec.cols.is_empty(),
-                            };
+                            *app.position.members.last_mut().unwrap() =
+                                navigation::first_member(&rec);
                         }
                         _ => {
                             // NOTE: app.position.members should never be empty by construction
@@ -93,16 +590,113 @@ pub fn handle_key_events(
             }
         }
         Mode::Insert => {
-            if key_event.code == config.keybindings.normal {
+            // NOTE: INSERT mode can't be entered when read-only, this is only a safety net
+            if key_event.code == config.keybindings.normal || config.read_only {
                 app.mode = Mode::Normal;
+                app.insertion = false;
                 return Ok(TransitionResult::Continue);
             }
 
             match app.editor.handle_key(&key_event.code) {
+                Some(Some(v)) if app.insertion => {
+                    app.mode = Mode::Normal;
+                    match app.insert(&v.as_string()?) {
+                        Ok((view, path)) => return Ok(TransitionResult::Mutate(view, path)),
+                        Err(err) => return Ok(TransitionResult::Error(err)),
+                    }
+                }
                 Some(Some(v)) => {
                     app.mode = Mode::Normal;
                     return Ok(TransitionResult::Mutate(v, app.position.clone()));
                 }
+                Some(None) => {
+                    app.mode = Mode::Normal;
+                    app.insertion = false;
+                    return Ok(TransitionResult::Continue);
+                }
+                None => return Ok(TransitionResult::Continue),
+            }
+        }
+        Mode::Goto => {
+            if key_event.code == config.keybindings.normal {
+                app.mode = Mode::Normal;
+                return Ok(TransitionResult::Continue);
+            }
+
+            match app.editor.handle_key(&key_event.code) {
+                Some(Some(v)) => {
+                    app.mode = Mode::Normal;
+                    match navigation::go_to(app, &v.as_string()?) {
+                        Ok(_) => return Ok(TransitionResult::Continue),
+                        Err(err) => return Ok(TransitionResult::Error(err)),
+                    }
+                }
+                Some(None) => {
+                    app.mode = Mode::Normal;
+                    return Ok(TransitionResult::Continue);
+                }
+                None => return Ok(TransitionResult::Continue),
+            }
+        }
+        Mode::Search => {
+            if key_event.code == config.keybindings.normal {
+                app.mode = Mode::Normal;
+                app.matches = vec![];
+                return Ok(TransitionResult::Continue);
+            }
+
+            // NOTE: the matches are picked from a list once the query has been typed
+            if !app.matches.is_empty() {
+                if key_event.code == KeyCode::Enter {
+                    app.jump_to_match();
+                } else if key_event.code == config.keybindings.navigation.down {
+                    app.selected_match = (app.selected_match + 1).min(app.matches.len() - 1);
+                } else if key_event.code == config.keybindings.navigation.up {
+                    app.selected_match = app.selected_match.saturating_sub(1);
+                }
+                return Ok(TransitionResult::Continue);
+            }
+
+            match app.editor.handle_key(&key_event.code) {
+                Some(Some(v)) => {
+                    let query = v.as_string()?;
+                    if query.is_empty() {
+                        app.mode = Mode::Normal;
+                        return Ok(TransitionResult::Continue);
+                    }
+
+                    app.matches = search(&app.value, &query, &config.search);
+                    if app.matches.is_empty() {
+                        app.mode = Mode::Normal;
+                        return Ok(TransitionResult::Error(format!("no match for `{}`", query)));
+                    }
+                    return Ok(TransitionResult::Continue);
+                }
+                Some(None) => {
+                    app.mode = Mode::Normal;
+                    return Ok(TransitionResult::Continue);
+                }
+                None => return Ok(TransitionResult::Continue),
+            }
+        }
+        Mode::Command => {
+            if key_event.code == config.keybindings.normal {
+                app.mode = Mode::Normal;
+                return Ok(TransitionResult::Continue);
+            }
+
+            match app.editor.handle_key(&key_event.code) {
+                Some(Some(v)) => {
+                    app.mode = Mode::Normal;
+                    match Command::parse(&v.as_string()?) {
+                        Ok(Command::Transform { .. }) if config.read_only => {
+                            app.status_message = Some(StatusMessage::Info("read-only".into()));
+                            return Ok(TransitionResult::Continue);
+                        }
+                        Ok(command) => return Ok(run_command(app, &command, config, false)),
+                        Err(err) => return Ok(TransitionResult::Error(err)),
+                    }
+                }
                 Some(None) => {
                     app.mode = Mode::Normal;
                     return Ok(TransitionResult::Continue);
@@ -112,64 +706,267 @@ pub fn handle_key_events(
         }
         Mode::Peeking => {
             if key_event.code == config.keybindings.quit {
-                return Ok(TransitionResult::Quit);
+                return Ok(run_command(
+                    app,
+                    &Command::Quit { force: false },
+                    config,
+                    false,
+                ));
             } else if key_event.code == config.keybindings.normal {
                 app.mode = Mode::Normal;
                 return Ok(TransitionResult::Continue);
             } else if key_event.code == config.keybindings.peeking.all {
-                return Ok(TransitionResult::Return(app.value.clone()));
+                app.dirty = false;
+                return Ok(TransitionResult::Return(app.whole_value()));
             } else if key_event.code == config.keybindings.peeking.view {
                 app.position.members.pop();
-                return Ok(TransitionResult::Return(
-                    app.value
-                        .clone()
-                        .follow_cell_path(&app.position.members, false)?,
-                ));
+                if app.position.members.is_empty() {
+                    return Ok(TransitionResult::Return(app.whole_value()));
+                }
+                return Ok(TransitionResult::Return(Rc::new(app.cell_under())));
             } else if key_event.code == config.keybindings.peeking.under {
-                return Ok(TransitionResult::Return(
-                    app.value
-                        .clone()
-                        .follow_cell_path(&app.position.members, false)?,
-                ));
+                let under = app.peek_range().unwrap_or_else(|| app.cell_under());
+                return Ok(TransitionResult::Return(Rc::new(under)));
             } else if key_event.code == config.keybindings.peeking.cell_path {
-                return Ok(TransitionResult::Return(Value::cell_path(
+                if !is_nushell_cell_path(&app.value, &app.position.members) {
+                    return Ok(TransitionResult::Error(
+                        "the cell path of a duplicate column can not be used in Nushell".into(),
+                    ));
+                }
+                return Ok(TransitionResult::Return(Rc::new(Value::cell_path(
                     app.position.clone(),
                     Span::unknown(),
-                )));
+                ))));
+            } else if key_event.code == config.keybindings.peeking.screen {
+                let screen = ui::render_to_string(app, config);
+                return Ok(TransitionResult::Return(Rc::new(Value::string(
+                    screen,
+                    Span::unknown(),
+                ))));
+            } else if key_event.code == config.keybindings.peeking.columns {
+                let under = app.cell_under();
+                match to_columns(&under) {
+                    Some(columns) => return Ok(TransitionResult::Return(Rc::new(columns))),
+                    None => {
+                        app.mode = Mode::Normal;
+                        return Ok(TransitionResult::Error(format!(
+                            "can only peek the columns of a table, found {}",
+                            under.get_type()
+                        )));
+                    }
+                }
+            } else if key_event.code == config.keybindings.peeking.marks {
+                match app.peek_marks() {
+                    Some(marks) => return Ok(TransitionResult::Return(Rc::new(marks))),
+                    None => {
+                        if config.peek_under_without_marks {
+                            return Ok(TransitionResult::Return(Rc::new(app.cell_under())));
+                        }
+
+                        app.mode = Mode::Normal;
+                        return Ok(TransitionResult::Error(
+                            "there is no marked element to peek in the current view".into(),
+                        ));
+                    }
+                }
             }
         }
         Mode::Bottom => {
             if key_event.code == config.keybindings.quit {
-                return Ok(TransitionResult::Quit);
+                return Ok(run_command(
+                    app,
+                    &Command::Quit { force: false },
+                    config,
+                    false,
+                ));
+            } else if key_event.code == config.keybindings.raw {
+                app.raw = !app.raw;
+                return Ok(TransitionResult::Continue);
             } else if key_event.code == config.keybindings.navigation.left {
                 app.mode = Mode::Normal;
                 return Ok(TransitionResult::Continue);
-            } else if key_event.code == config.keybindings.peek {
-                return Ok(TransitionResult::Return(
-                    app.value
-                        .clone()
-                        .follow_cell_path(&app.position.members, false)?,
-                ));
-            }
-        }
-    }
-
-    Ok(TransitionResult::Continue)
-}
-
-#[cfg(test)]
+            } else if key_event.code == config.keybindings.other_pane {
+                match app.switch_pane() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.info {
+                match app.show_info() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.next_sibling {
+                match navigation::go_to_ancestor_sibling(app, Direction::Down) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.previous_sibling {
+                match navigation::go_to_ancestor_sibling(app, Direction::Up) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.open {
+                match app.open_under(config.allow_open) {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            } else if key_event.code == config.keybindings.peek {
+                return Ok(TransitionResult::Return(Rc::new(app.cell_under())));
+            } else if key_event.code == config.keybindings.parse {
+                match app.parse_string() {
+                    Ok(_) => return Ok(TransitionResult::Continue),
+                    Err(err) => return Ok(TransitionResult::Error(err)),
+                }
+            }
+        }
+    }
+
+    Ok(TransitionResult::Continue)
+}
+
+/// run a [`Command`], the outcome being reported in the status bar
+///
+/// > :bulb: **Note**  
+/// > a command that would overwrite or discard something asks for a confirmation first, unless
+/// > it is *forced*
+fn run_command(app: &mut App, command: &Command, config: &Config, force: bool) -> TransitionResult {
+    match command {
+        Command::Write { path, all } => {
+            if !force && !all && std::path::Path::new(path).exists() {
+                app.confirmation = Some(Confirmation {
+                    question: format!("`{}` already exists, overwrite it? (y/n)", path),
+                    command: command.clone(),
+                });
+                return TransitionResult::Continue;
+            }
+
+            let result = if *all {
+                save_file(&app.whole_value(), path)
+            } else {
+                save_file(&app.cell_under(), path)
+            };
+            app.status_message = Some(match result {
+                Ok(()) => {
+                    // NOTE: the edits are not lost anymore once the whole data has been written,
+                    // not only the data parsed from one of its strings
+                    if *all && app.parsed.is_empty() {
+                        app.dirty = false;
+                    }
+                    StatusMessage::Info(format!("written to `{}`", path))
+                }
+                Err(err) => StatusMessage::Error(err),
+            });
+        }
+        Command::ClearSearch => {
+            app.matches = vec![];
+            app.selected_match = 0;
+        }
+        Command::Quit { force: bang } => {
+            if !force && !bang && app.dirty {
+                app.confirmation = Some(Confirmation {
+                    question: "discard changes? (y/n)".into(),
+                    command: command.clone(),
+                });
+                return TransitionResult::Continue;
+            }
+
+            return TransitionResult::Quit;
+        }
+        Command::DeleteRange { force: bang } => {
+            if config.read_only {
+                app.status_message = Some(StatusMessage::Info("read-only".into()));
+                return TransitionResult::Continue;
+            }
+            let (start, end) = match app.range_in_view() {
+                Some(range) => range,
+                None => {
+                    return TransitionResult::Error("no range selected in the current list".into())
+                }
+            };
+            if !force && !bang {
+                app.confirmation = Some(Confirmation {
+                    question: format!("delete {} items? (y/n)", end - start + 1),
+                    command: command.clone(),
+                });
+                return TransitionResult::Continue;
+            }
+
+            if let Err(err) = app.delete_range() {
+                return TransitionResult::Error(err);
+            }
+        }
+        Command::Peek { force: bang } => {
+            // NOTE: a read-only and strict exploration is only a viewer, nothing is peeked from it
+            if config.read_only && app.strict {
+                app.status_message = Some(StatusMessage::Info("read-only".into()));
+                return TransitionResult::Continue;
+            }
+            if !force && !bang && app.dirty {
+                app.confirmation = Some(Confirmation {
+                    question: "discard changes? (y/n)".into(),
+                    command: command.clone(),
+                });
+                return TransitionResult::Continue;
+            }
+
+            let under = app.peek_range().unwrap_or_else(|| app.cell_under());
+            return TransitionResult::Return(Rc::new(under));
+        }
+        Command::GroupBy { column } => {
+            if let Err(err) = app.group_by(column.as_deref(), config.group.max_groups) {
+                return TransitionResult::Error(err);
+            }
+        }
+        Command::Ungroup => {
+            if let Err(err) = app.ungroup() {
+                return TransitionResult::Error(err);
+            }
+        }
+        Command::Transform { transform, column } => {
+            // NOTE: a whole column is rewritten at once, it is worth a confirmation
+            if !force && *column {
+                if let Some(col) = app.focused_column() {
+                    app.confirmation = Some(Confirmation {
+                        question: format!("{} all the strings of `{}`? (y/n)", transform, col),
+                        command: command.clone(),
+                    });
+                    return TransitionResult::Continue;
+                }
+            }
+
+            return match app.transform_strings(*transform, *column) {
+                Ok((value, path)) => TransitionResult::Mutate(value, path),
+                Err(err) => TransitionResult::Error(err),
+            };
+        }
+    }
+
+    TransitionResult::Continue
+}
+
+#[cfg(test)]
 mod tests {
+    use std::{
+        rc::Rc,
+        time::{Duration, Instant},
+    };
+
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
     use nu_protocol::{
         ast::{CellPath, PathMember},
         record, Span, Value,
     };
 
-    use super::{handle_key_events, App, TransitionResult};
+    use super::{handle_key_events, App, ForceQuit, TransitionResult};
     use crate::{
-        app::Mode,
-        config::{repr_keycode, Config},
-        nu::cell_path::{to_path_member_vec, PM},
+        app::{Mode, StatusMessage},
+        config::{repr_keycode, Config, LogConfig, LogLevel},
+        log::Logger,
+        nu::{
+            cell_path::{to_path_member_vec, PM},
+            diff::{Change, Difference},
+        },
+        ui,
     };
 
     /// {
@@ -239,6 +1036,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn toggle_raw_values() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+
+        let mut app = App::from_value(test_value());
+        assert!(!app.raw);
+
+        // NOTE: the toggle is global, it persists across levels and modes
+        let transitions = vec![
+            (keybindings.raw, true),
+            (keybindings.navigation.down, true),
+            (keybindings.navigation.right, true),
+            (keybindings.raw, false),
+            (keybindings.navigation.left, false),
+            (keybindings.raw, true),
+        ];
+
+        for (key, expected) in transitions {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), &mut app, &config)
+                .unwrap();
+            assert_eq!(
+                app.raw,
+                expected,
+                "unexpected raw toggle after pressing {}",
+                repr_keycode(&key)
+            );
+        }
+    }
+
     #[test]
     fn quit() {
         let config = Config::default();
@@ -426,7 +1253,7 @@ mod tests {
                     TransitionResult::Return(val) => {
                         assert_eq!(
                             value,
-                            val,
+                            *val,
                             "unexpected data after pressing {} in {} mode",
                             repr_keycode(&key),
                             mode
@@ -498,6 +1325,14 @@ mod tests {
         ];
         run_peeking_scenario(go_in_the_data_and_peek_under, &config, value.clone());
 
+        // NOTE: peeking under the cursor at once does not go through PEEKING mode
+        let go_in_the_data_and_peek_under_quick = vec![
+            (keybindings.navigation.down, false, None), // on {r: {a: 1, b: 2}}
+            (keybindings.navigation.right, false, None), // on {a: 1}
+            (keybindings.peek_under_quick, true, Some(Value::test_int(1))),
+        ];
+        run_peeking_scenario(go_in_the_data_and_peek_under_quick, &config, value.clone());
+
         let go_in_the_data_and_peek_cell_path = vec![
             (keybindings.navigation.down, false, None), // on {r: {a: 1, b: 2}}
             (keybindings.navigation.right, false, None), // on {a: 1}
@@ -523,6 +1358,49 @@ mod tests {
         ];
         run_peeking_scenario(go_in_the_data_and_peek_cell_path, &config, value.clone());
 
+        let peek_marks = vec![
+            (keybindings.navigation.right, false, None), // on l: ["my", "list", "elements"],
+            (keybindings.mark, false, None),             // mark "my"
+            (keybindings.navigation.up, false, None),
+            (keybindings.mark, false, None), // mark "elements"
+            (keybindings.peek, false, None),
+            (
+                keybindings.peeking.marks,
+                true,
+                Some(Value::test_list(vec![
+                    Value::test_string("my"),
+                    Value::test_string("elements"),
+                ])),
+            ),
+        ];
+        run_peeking_scenario(peek_marks, &config, value.clone());
+
+        let peek_without_marks = vec![
+            (keybindings.peek, false, None),
+            (
+                keybindings.peeking.marks,
+                true,
+                Some(Value::test_list(vec![
+                    Value::test_string("my"),
+                    Value::test_string("list"),
+                    Value::test_string("elements"),
+                ])),
+            ),
+        ];
+        run_peeking_scenario(peek_without_marks, &config, value.clone());
+
+        let mut config_without_fallback = config.clone();
+        config_without_fallback.peek_under_without_marks = false;
+        let peek_without_marks_nor_fallback = vec![
+            (keybindings.peek, false, None),
+            (keybindings.peeking.marks, false, None),
+        ];
+        run_peeking_scenario(
+            peek_without_marks_nor_fallback,
+            &config_without_fallback,
+            value.clone(),
+        );
+
         let peek_at_the_bottom = vec![
             (keybindings.navigation.right, false, None), // on l: ["my", "list", "elements"],
             (keybindings.navigation.right, false, None), // on "my"
@@ -531,6 +1409,129 @@ mod tests {
         run_peeking_scenario(peek_at_the_bottom, &config, value);
     }
 
+    #[test]
+    fn explore_without_copying_the_data() {
+        let config = Config::default();
+        let kmap = config.clone().keybindings;
+
+        let value = Value::test_list(
+            (0..100_000)
+                .map(|i| {
+                    Value::test_record(record! {
+                        "id" => Value::test_int(i),
+                        "name" => Value::test_string(format!("item {}", i)),
+                    })
+                })
+                .collect(),
+        );
+        let mut app = App::from_value(value);
+        let shared = app.value.clone();
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 20)).unwrap();
+
+        let keys = vec![
+            kmap.navigation.down,
+            kmap.navigation.down,
+            kmap.navigation.right,
+            kmap.navigation.down,
+            kmap.navigation.up,
+            kmap.navigation.left,
+            kmap.navigation.up,
+            kmap.peek,
+        ];
+        for key in keys {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), &mut app, &config)
+                .unwrap();
+            terminal
+                .draw(|frame| crate::ui::render_ui(frame, &mut app, &config, None))
+                .unwrap();
+        }
+
+        // NOTE: the data has not been copied to navigate nor to peek the whole of it
+        match handle_key_events(
+            KeyEvent::new(kmap.peeking.all, KeyModifiers::empty()),
+            &mut app,
+            &config,
+        )
+        .unwrap()
+        {
+            TransitionResult::Return(value) => assert!(Rc::ptr_eq(&value, &shared)),
+            _ => panic!("expected to peek the whole data"),
+        }
+        assert!(Rc::ptr_eq(&app.value, &shared));
+
+        // NOTE: an edit builds a new value and leaves the original one untouched
+        app.mode = Mode::Normal;
+        let path = CellPath {
+            members: to_path_member_vec(&[PM::I(1), PM::S("name")]),
+        };
+        app.value = Rc::new(crate::nu::value::mutate_value_cell(
+            &app.value,
+            &path,
+            &Value::test_string("new"),
+        ));
+        assert!(!Rc::ptr_eq(&app.value, &shared));
+        assert_eq!(
+            crate::nu::value::follow(&shared, &path.members).map(|v| v.into_owned()),
+            Some(Value::test_string("item 1"))
+        );
+    }
+
+    #[test]
+    fn read_only() {
+        let config = Config {
+            read_only: true,
+            ..Config::default()
+        };
+        let kmap = config.clone().keybindings;
+
+        let value = Value::test_list(vec![
+            Value::test_record(record!("a" => Value::test_int(1))),
+            Value::test_record(record!("a" => Value::test_int(2))),
+        ]);
+        let mut app = App::from_value(value.clone());
+        app.editor.set_width(80);
+
+        let keys = vec![
+            kmap.insert,
+            KeyCode::Char('x'),
+            KeyCode::Enter,
+            kmap.transpose,
+            kmap.navigation.right,
+            kmap.insert,
+            KeyCode::Backspace,
+            KeyCode::Enter,
+            kmap.navigation.right,
+            kmap.insert,
+            KeyCode::Enter,
+        ];
+
+        for key in keys {
+            let result =
+                handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), &mut app, &config)
+                    .unwrap();
+            assert!(
+                !matches!(result, TransitionResult::Mutate(..)),
+                "pressing {} should not mutate the data",
+                repr_keycode(&key)
+            );
+            assert_ne!(app.mode, Mode::Insert);
+            assert_eq!(*app.value, value);
+        }
+
+        handle_key_events(
+            KeyEvent::new(kmap.insert, KeyModifiers::empty()),
+            &mut app,
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info("read-only".into()))
+        );
+    }
+
     #[test]
     fn transpose_the_data() {
         let config = Config::default();
@@ -559,7 +1560,9 @@ mod tests {
                 .unwrap()
             {
                 TransitionResult::Mutate(cell, path) => {
-                    app.value = crate::nu::value::mutate_value_cell(&app.value, &path, &cell)
+                    app.value = Rc::new(crate::nu::value::mutate_value_cell(
+                        &app.value, &path, &cell,
+                    ))
                 }
                 _ => {}
             }
@@ -579,4 +1582,1220 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn transpose_with_marks() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let row = |a: i64| Value::test_record(record! { "a" => Value::test_int(a) });
+        let mut app = App::from_value(Value::test_record(record! {
+            "t" => Value::test_list(vec![row(1), row(2)]),
+            "n" => Value::test_int(0),
+        }));
+        app.position.members = to_path_member_vec(&[PM::S("t"), PM::I(0)]);
+        app.marks = vec![
+            to_path_member_vec(&[PM::S("t"), PM::I(1)]),
+            to_path_member_vec(&[PM::S("t")]),
+            to_path_member_vec(&[PM::S("n")]),
+        ];
+
+        match handle_key_events(
+            KeyEvent::new(keybindings.transpose, KeyModifiers::empty()),
+            &mut app,
+            &config,
+        )
+        .unwrap()
+        {
+            TransitionResult::Mutate(cell, path) => app.apply_mutation(&cell, &path),
+            x => panic!("expected the table to be transposed, found {:?}", x),
+        }
+
+        // NOTE: the rows of the table are gone, the marks outside of it are kept
+        assert_eq!(
+            app.marks,
+            vec![
+                to_path_member_vec(&[PM::S("t")]),
+                to_path_member_vec(&[PM::S("n")]),
+            ]
+        );
+        assert_eq!(app.peek_marks(), None);
+    }
+
+    #[test]
+    fn restore_the_column_after_a_transpose() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let inner = |a: i64| Value::test_record(record! { "x" => Value::test_int(a) });
+        let row = |name: &str| {
+            Value::test_record(record! {
+                "name" => Value::test_string(name),
+                "inner" => Value::test_list(vec![inner(1), inner(2)]),
+            })
+        };
+        let mut app = App::from_value(Value::test_list(vec![row("a"), row("b")]));
+
+        let press = |app: &mut App, key: KeyCode| {
+            let event = KeyEvent::new(key, KeyModifiers::empty());
+            if let TransitionResult::Mutate(cell, path) =
+                handle_key_events(event, app, &config).unwrap()
+            {
+                app.apply_mutation(&cell, &path);
+            }
+        };
+
+        press(&mut app, keybindings.navigation.next_column);
+        press(&mut app, keybindings.navigation.next_column);
+        assert_eq!(app.column, Some(1));
+        press(&mut app, keybindings.navigation.right);
+        press(&mut app, keybindings.navigation.next_column);
+        press(&mut app, keybindings.transpose);
+        assert_eq!(
+            app.position.members,
+            to_path_member_vec(&[PM::I(0), PM::S("inner"), PM::I(0)])
+        );
+
+        // NOTE: the table is found as it was left, with its column focused
+        press(&mut app, keybindings.navigation.left);
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::I(0)]));
+        assert_eq!(app.column, Some(1));
+    }
+
+    #[test]
+    fn deep_search() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let mut app = App::from_value(test_value());
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        press(&mut app, keybindings.search);
+        assert!(app.mode == Mode::Search);
+        app.editor.set_width(20);
+        for c in "nothing".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert_eq!(
+            press(&mut app, KeyCode::Enter),
+            TransitionResult::Error("no match for `nothing`".into())
+        );
+        assert!(app.mode == Mode::Normal);
+
+        press(&mut app, keybindings.search);
+        app.editor.set_width(20);
+        press(&mut app, KeyCode::Char('s'));
+        press(&mut app, KeyCode::Enter);
+        assert!(app.mode == Mode::Search);
+        assert_eq!(app.matches.len(), 3);
+
+        press(&mut app, keybindings.navigation.down);
+        press(&mut app, keybindings.navigation.down);
+        press(&mut app, keybindings.navigation.down);
+        press(&mut app, keybindings.navigation.up);
+        press(&mut app, KeyCode::Enter);
+
+        assert!(app.mode == Mode::Normal);
+        assert_eq!(
+            app.position.members,
+            to_path_member_vec(&[PM::S("l"), PM::I(2)])
+        );
+    }
+
+    #[test]
+    fn jump_between_matches() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let mut app = App::from_value(test_value());
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        assert_eq!(
+            press(&mut app, keybindings.next_match),
+            TransitionResult::Error("no previous search".into())
+        );
+
+        press(&mut app, keybindings.search);
+        app.editor.set_width(20);
+        press(&mut app, KeyCode::Char('s'));
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Enter);
+        assert!(app.mode == Mode::Normal);
+        assert_eq!(app.matches.len(), 3);
+
+        let paths: Vec<Vec<PathMember>> = app.matches.iter().map(|m| m.path.clone()).collect();
+        assert_eq!(app.position.members, paths[0]);
+
+        #[rustfmt::skip]
+        let transitions = vec![
+            (keybindings.next_match, 1),
+            (keybindings.next_match, 2),
+            (keybindings.next_match, 0),
+            (keybindings.previous_match, 2),
+            (keybindings.previous_match, 1),
+        ];
+        for (key, expected) in transitions {
+            press(&mut app, key);
+            assert_eq!(app.selected_match, expected);
+            assert_eq!(app.position.members, paths[expected]);
+        }
+
+        // NOTE: the matches inside an edited element are forgotten
+        app.invalidate_matches(&paths[2]);
+        assert_eq!(app.matches.len(), 2);
+        assert_eq!(app.matches[app.selected_match].path, paths[1]);
+
+        press(&mut app, keybindings.command);
+        app.editor.set_width(20);
+        for c in "nohl".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+        assert!(app.matches.is_empty());
+        assert_eq!(
+            press(&mut app, keybindings.previous_match),
+            TransitionResult::Error("no previous search".into())
+        );
+    }
+
+    #[test]
+    fn column_stats() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let mut app = App::from_value(Value::test_list(vec![
+            Value::test_record(record! {
+                "name" => Value::test_string("foo"),
+                "size" => Value::test_filesize(1024),
+            }),
+            Value::test_record(record! {
+                "name" => Value::test_string("bar"),
+                "size" => Value::test_nothing(),
+            }),
+        ]));
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        assert_eq!(
+            press(&mut app, keybindings.stats),
+            TransitionResult::Error("no column under the cursor".into())
+        );
+
+        press(&mut app, keybindings.navigation.next_column);
+        assert_eq!(
+            press(&mut app, keybindings.stats),
+            TransitionResult::Error("column `name` is not numeric, found string".into())
+        );
+
+        press(&mut app, keybindings.navigation.next_column);
+        press(&mut app, keybindings.stats);
+        let stats = app.stats.clone().unwrap();
+        assert_eq!(
+            (stats.column.as_str(), stats.count, stats.skipped),
+            ("size", 1, 1)
+        );
+
+        // NOTE: the next key only closes the popup
+        press(&mut app, keybindings.navigation.down);
+        assert!(app.stats.is_none());
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::I(0)]));
+    }
+
+    #[test]
+    fn quit_with_unsaved_edits() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let mut app = App::from_value(test_value());
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        assert!(press(&mut app, keybindings.quit).is_quit());
+
+        app.dirty = true;
+        assert_eq!(
+            press(&mut app, keybindings.quit),
+            TransitionResult::Continue
+        );
+        assert_eq!(
+            app.confirmation.as_ref().map(|c| c.question.as_str()),
+            Some("discard changes? (y/n)")
+        );
+        assert_eq!(
+            press(&mut app, KeyCode::Char('n')),
+            TransitionResult::Continue
+        );
+        assert!(app.confirmation.is_none());
+
+        press(&mut app, keybindings.quit);
+        assert!(press(&mut app, KeyCode::Char('y')).is_quit());
+
+        // NOTE: `:q!` never asks
+        press(&mut app, keybindings.command);
+        app.editor.set_width(20);
+        for c in "q!".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert!(press(&mut app, KeyCode::Enter).is_quit());
+
+        // NOTE: peeking the whole data gives the edits back
+        press(&mut app, keybindings.peek);
+        assert!(press(&mut app, keybindings.peeking.all).is_quit());
+        assert!(!app.dirty);
+    }
+
+    #[test]
+    fn peek_under_quick() {
+        let mut config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let mut app = App::from_value(test_value());
+        app.position.members = to_path_member_vec(&[PM::S("i")]);
+
+        let press = |app: &mut App, config: &Config, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, config).unwrap()
+        };
+
+        app.dirty = true;
+        assert_eq!(
+            press(&mut app, &config, keybindings.peek_under_quick),
+            TransitionResult::Continue
+        );
+        assert_eq!(
+            app.confirmation.as_ref().map(|c| c.question.as_str()),
+            Some("discard changes? (y/n)")
+        );
+        press(&mut app, &config, KeyCode::Char('n'));
+        assert!(app.confirmation.is_none());
+
+        press(&mut app, &config, keybindings.peek_under_quick);
+        assert_eq!(
+            press(&mut app, &config, KeyCode::Char('y')),
+            TransitionResult::Return(Rc::new(Value::test_int(123)))
+        );
+
+        // NOTE: nothing is peeked from a read-only and strict exploration
+        app.dirty = false;
+        config.read_only = true;
+        app.strict = true;
+        assert_eq!(
+            press(&mut app, &config, keybindings.peek_under_quick),
+            TransitionResult::Continue
+        );
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info("read-only".into()))
+        );
+
+        app.strict = false;
+        assert_eq!(
+            press(&mut app, &config, keybindings.peek_under_quick),
+            TransitionResult::Return(Rc::new(Value::test_int(123)))
+        );
+    }
+
+    #[test]
+    fn transform_a_column_after_a_confirmation() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let row = |name: &str| Value::test_record(record! { "name" => Value::test_string(name) });
+        let mut app = App::from_value(Value::test_list(vec![row("a"), row("b")]));
+        app.column = Some(0);
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+        let upper = |app: &mut App| {
+            press(app, keybindings.command);
+            app.editor.set_width(20);
+            for c in "upper!".chars() {
+                press(app, KeyCode::Char(c));
+            }
+            press(app, KeyCode::Enter)
+        };
+
+        assert_eq!(upper(&mut app), TransitionResult::Continue);
+        assert_eq!(
+            app.confirmation.as_ref().map(|c| c.question.as_str()),
+            Some("upper all the strings of `name`? (y/n)")
+        );
+        assert_eq!(
+            press(&mut app, KeyCode::Char('n')),
+            TransitionResult::Continue
+        );
+
+        upper(&mut app);
+        assert_eq!(
+            press(&mut app, KeyCode::Char('y')),
+            TransitionResult::Mutate(
+                Value::test_list(vec![row("A"), row("B")]),
+                CellPath { members: vec![] }
+            )
+        );
+    }
+
+    #[test]
+    fn start_in_each_mode() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+        let start_in = |mode: Mode, members: &[PM]| {
+            let mut app = App::from_value(test_value());
+            app.position.members = to_path_member_vec(members);
+            app.enter_mode(mode.clone()).map(|_| app)
+        };
+
+        let mut app = start_in(Mode::Insert, &[PM::S("s")]).unwrap();
+        assert!(app.mode == Mode::Insert);
+        app.editor.set_width(20);
+        press(&mut app, KeyCode::Char('!'));
+        assert!(matches!(
+            press(&mut app, KeyCode::Enter),
+            TransitionResult::Mutate(..)
+        ));
+        assert!(
+            start_in(Mode::Insert, &[PM::S("i")]).is_err(),
+            "only strings can be edited"
+        );
+
+        let mut app = start_in(Mode::Peeking, &[PM::S("s")]).unwrap();
+        assert_eq!(
+            press(&mut app, keybindings.peeking.under),
+            TransitionResult::Return(Rc::new(Value::test_string("some string")))
+        );
+
+        let mut app = start_in(Mode::Goto, &[PM::S("l")]).unwrap();
+        app.editor.set_width(20);
+        press(&mut app, KeyCode::Char('i'));
+        press(&mut app, KeyCode::Enter);
+        assert!(app.mode == Mode::Normal);
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::S("i")]));
+
+        let mut app = start_in(Mode::Search, &[PM::S("l")]).unwrap();
+        app.editor.set_width(20);
+        press(&mut app, KeyCode::Char('1'));
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(
+            app.position.members,
+            to_path_member_vec(&[PM::S("r"), PM::S("a")])
+        );
+
+        let mut app = start_in(Mode::Command, &[PM::S("l")]).unwrap();
+        app.editor.set_width(20);
+        press(&mut app, KeyCode::Char('q'));
+        assert!(press(&mut app, KeyCode::Enter).is_quit());
+
+        let mut app = start_in(Mode::Normal, &[PM::S("l")]).unwrap();
+        assert!(app.mode == Mode::Normal);
+        assert!(press(&mut app, keybindings.quit).is_quit());
+    }
+
+    #[test]
+    fn write_to_file() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let mut app = App::from_value(test_value());
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+        let type_in = |app: &mut App, text: &str| {
+            app.editor.set_width(80);
+            for c in text.chars() {
+                press(app, KeyCode::Char(c));
+            }
+            press(app, KeyCode::Enter)
+        };
+
+        let path = std::env::temp_dir().join(format!(
+            "nu_plugin_explore-write-{}.json",
+            std::process::id()
+        ));
+        let path_str = path.to_str().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        // the value under the cursor, i.e. the list at `l`
+        press(&mut app, keybindings.write);
+        assert!(app.mode == Mode::Command);
+        type_in(&mut app, path_str);
+        assert!(app.mode == Mode::Normal);
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info(format!("written to `{}`", path_str)))
+        );
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("\"elements\""));
+        assert!(!written.contains("\"some string\""));
+
+        // an existing file needs a confirmation
+        press(&mut app, keybindings.navigation.down);
+        for (key, expected) in [(KeyCode::Char('n'), false), (KeyCode::Char('y'), true)] {
+            press(&mut app, keybindings.write);
+            type_in(&mut app, path_str);
+            assert!(app.confirmation.is_some());
+
+            press(&mut app, key);
+            assert!(app.confirmation.is_none());
+            assert_eq!(
+                std::fs::read_to_string(&path).unwrap().contains("\"a\": 1"),
+                expected,
+                "unexpected content after answering {}",
+                repr_keycode(&key)
+            );
+        }
+
+        // the whole data, without any confirmation
+        press(&mut app, keybindings.command);
+        type_in(&mut app, &format!("write! {}", path_str));
+        assert!(app.confirmation.is_none());
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("\"some string\""));
+
+        press(&mut app, keybindings.command);
+        assert_eq!(
+            type_in(&mut app, "foo"),
+            TransitionResult::Error("unknown command `foo`".into())
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn diff_siblings() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+
+        let value = Value::test_list(vec![
+            Value::test_record(record! {
+                "a" => Value::test_int(1),
+                "b" => Value::test_string("foo"),
+            }),
+            Value::test_record(record! {
+                "a" => Value::test_int(2),
+                "b" => Value::test_string("foo"),
+            }),
+            Value::test_record(record! {
+                "c" => Value::test_int(3),
+            }),
+        ]);
+        let mut app = App::from_value(value);
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        // setting the same base twice clears it
+        press(&mut app, keybindings.diff);
+        assert_eq!(app.diff_base, Some(to_path_member_vec(&[PM::I(0)])));
+        press(&mut app, keybindings.diff);
+        assert_eq!(app.diff_base, None);
+
+        press(&mut app, keybindings.diff);
+        press(&mut app, keybindings.navigation.down);
+        press(&mut app, keybindings.diff);
+        assert_eq!(app.diff_base, None);
+        assert_eq!(
+            app.differences,
+            vec![Difference {
+                path: to_path_member_vec(&[PM::S("a")]),
+                change: Change::Changed("1".into(), "2".into()),
+            }]
+        );
+
+        // the popup catches the keys until it's closed
+        press(&mut app, keybindings.navigation.down);
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::I(1)]));
+        press(&mut app, keybindings.normal);
+        assert!(app.differences.is_empty());
+
+        press(&mut app, keybindings.diff);
+        press(&mut app, keybindings.navigation.down);
+        press(&mut app, keybindings.diff);
+        assert_eq!(app.differences.len(), 3);
+        press(&mut app, keybindings.normal);
+
+        // only siblings can be compared
+        press(&mut app, keybindings.diff);
+        press(&mut app, keybindings.navigation.right);
+        assert_eq!(
+            press(&mut app, keybindings.diff),
+            TransitionResult::Error("can only diff two elements of the same view".into())
+        );
+        assert!(app.diff_base.is_some());
+
+        // an edit of the list forgets the base, which might not exist anymore
+        press(&mut app, keybindings.navigation.left);
+        app.apply_mutation(
+            &Value::test_list(vec![Value::test_int(0), Value::test_int(1)]),
+            &CellPath { members: vec![] },
+        );
+        assert_eq!(app.diff_base, None);
+
+        app.diff_base = Some(to_path_member_vec(&[PM::I(5)]));
+        assert_eq!(
+            press(&mut app, keybindings.diff),
+            TransitionResult::Error("the diff base does not exist anymore".into())
+        );
+    }
+
+    #[test]
+    fn log_transitions() {
+        let file = std::env::temp_dir().join(format!(
+            "nu_plugin_explore-transitions-{}.log",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&file);
+
+        let config = Config {
+            log: LogConfig {
+                file: Some(file.to_string_lossy().to_string()),
+                level: LogLevel::Debug,
+            },
+            ..Config::default()
+        };
+        let nav = config.keybindings.navigation.clone();
+        let keybindings = config.clone().keybindings;
+
+        let mut app = App::from_value(test_value());
+        app.logger = Logger::new(&config.log);
+
+        for key in [
+            nav.down,
+            nav.right,
+            nav.up,
+            KeyCode::Char('z'),
+            keybindings.next_match,
+            nav.left,
+            keybindings.peek,
+            keybindings.peeking.under,
+        ] {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), &mut app, &config)
+                .unwrap();
+        }
+
+        // NOTE: the timestamps are removed
+        let log: Vec<String> = std::fs::read_to_string(&file)
+            .unwrap()
+            .lines()
+            .map(|l| l.split_once(' ').unwrap().1.to_string())
+            .collect();
+        let expected = vec![
+            format!(
+                "INFO mode=NORMAL key={} action=continue path=$.r",
+                repr_keycode(&nav.down)
+            ),
+            format!(
+                "INFO mode=NORMAL key={} action=continue path=$.r.a",
+                repr_keycode(&nav.right)
+            ),
+            format!(
+                "INFO mode=NORMAL key={} action=continue path=$.r.b",
+                repr_keycode(&nav.up)
+            ),
+            "DEBUG mode=NORMAL key=z action=continue path=$.r.b".into(),
+            format!(
+                "ERROR mode=NORMAL key={} action=error(no previous search) path=$.r.b",
+                repr_keycode(&keybindings.next_match)
+            ),
+            format!(
+                "INFO mode=NORMAL key={} action=continue path=$.r",
+                repr_keycode(&nav.left)
+            ),
+            format!(
+                "INFO mode=NORMAL key={} action=continue path=$.r",
+                repr_keycode(&keybindings.peek)
+            ),
+            format!(
+                "INFO mode=PEEKING key={} action=return path=$.r",
+                repr_keycode(&keybindings.peeking.under)
+            ),
+        ];
+        assert_eq!(log, expected);
+
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn log_failures() {
+        let config = Config {
+            log: LogConfig {
+                // NOTE: a directory cannot be appended to
+                file: Some(std::env::temp_dir().to_string_lossy().to_string()),
+                level: LogLevel::Debug,
+            },
+            ..Config::default()
+        };
+        let nav = config.keybindings.navigation.clone();
+
+        let mut app = App::from_value(test_value());
+        app.logger = Logger::new(&config.log);
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        assert_eq!(press(&mut app, nav.down), TransitionResult::Continue);
+        assert!(matches!(
+            app.status_message,
+            Some(StatusMessage::Error(ref err)) if err.starts_with("could not write to the log file")
+        ));
+
+        // NOTE: the failure is only shown once, the navigation goes on
+        app.status_message = None;
+        assert_eq!(press(&mut app, nav.down), TransitionResult::Continue);
+        assert!(app.status_message.is_none());
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::S("s")]));
+    }
+
+    #[test]
+    fn build_data_from_scratch() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let mut app = App::from_value(Value::test_record(record! {}));
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+        let type_in = |app: &mut App, text: &str| {
+            app.editor.set_width(20);
+            for c in text.chars() {
+                press(app, KeyCode::Char(c));
+            }
+            match press(app, KeyCode::Enter) {
+                TransitionResult::Mutate(cell, path) => {
+                    app.value = Rc::new(crate::nu::value::mutate_value_cell(
+                        &app.value, &path, &cell,
+                    ));
+                }
+                x => panic!("expected a mutation, found {:?}", x),
+            }
+        };
+
+        press(&mut app, keybindings.insert);
+        assert!(app.mode == Mode::Insert);
+        type_in(&mut app, "name: foo");
+        press(&mut app, keybindings.append);
+        type_in(&mut app, "tags: []");
+        press(&mut app, keybindings.navigation.right);
+        press(&mut app, keybindings.insert);
+        type_in(&mut app, "bar");
+        press(&mut app, keybindings.append);
+        type_in(&mut app, "baz");
+
+        // NOTE: cancelling an insertion leaves the data untouched
+        press(&mut app, keybindings.append);
+        app.editor.set_width(20);
+        press(&mut app, KeyCode::Char('x'));
+        press(&mut app, keybindings.normal);
+        assert!(!app.insertion);
+
+        assert!(app.mode == Mode::Normal);
+        assert_eq!(
+            app.position.members,
+            to_path_member_vec(&[PM::S("tags"), PM::I(1)])
+        );
+        assert_eq!(
+            *app.value,
+            Value::test_record(record! {
+                "name" => Value::test_string("foo"),
+                "tags" => Value::test_list(vec![
+                    Value::test_string("bar"),
+                    Value::test_string("baz"),
+                ]),
+            })
+        );
+
+        let config = Config {
+            read_only: true,
+            ..config
+        };
+        let result = handle_key_events(
+            KeyEvent::new(keybindings.append, KeyModifiers::empty()),
+            &mut app,
+            &config,
+        );
+        assert_eq!(result.unwrap(), TransitionResult::Continue);
+        assert!(app.mode == Mode::Normal);
+    }
+
+    #[test]
+    fn explore_parsed_strings() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let nav = keybindings.navigation.clone();
+        let mut app = App::from_value(Value::test_record(record! {
+            "payload" => Value::test_string(r#"{"a": [1, 2], "b": "x"}"#),
+            "text" => Value::test_string("not data"),
+            "n" => Value::test_int(1),
+        }));
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        assert_eq!(
+            press(&mut app, keybindings.parse),
+            TransitionResult::Continue
+        );
+        assert_eq!(app.parsed.len(), 1);
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::S("a")]));
+        assert_eq!(
+            app.root_position().members,
+            to_path_member_vec(&[PM::S("payload")])
+        );
+        assert_eq!(
+            press(&mut app, keybindings.insert),
+            TransitionResult::Error("the data parsed from a string can not be edited".into())
+        );
+
+        // NOTE: peeking gives the parsed data
+        press(&mut app, nav.right);
+        press(&mut app, nav.down);
+        press(&mut app, keybindings.peek);
+        assert_eq!(
+            press(&mut app, keybindings.peeking.under),
+            TransitionResult::Return(Rc::new(Value::test_int(2)))
+        );
+
+        app.mode = Mode::Normal;
+        press(&mut app, nav.left);
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::S("a")]));
+        press(&mut app, nav.left);
+        assert!(app.parsed.is_empty());
+        assert_eq!(
+            app.position.members,
+            to_path_member_vec(&[PM::S("payload")])
+        );
+        // NOTE: the original data is explored again, as it was
+        press(&mut app, nav.left);
+        assert_eq!(
+            app.position.members,
+            to_path_member_vec(&[PM::S("payload")])
+        );
+
+        press(&mut app, nav.down);
+        assert_eq!(
+            press(&mut app, keybindings.parse),
+            TransitionResult::Error("the string is neither JSON nor TOML".into())
+        );
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::S("text")]));
+        press(&mut app, nav.down);
+        assert_eq!(
+            press(&mut app, keybindings.parse),
+            TransitionResult::Error("can only parse strings, found int".into())
+        );
+        assert!(app.parsed.is_empty());
+    }
+
+    #[test]
+    fn change_the_depth_of_the_previews() {
+        let config = Config::default();
+        let mut app = App::from_value(test_value());
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        let position = app.position.clone();
+        assert_eq!(app.preview_depth, 2);
+
+        press(&mut app, KeyCode::Char('+'));
+        assert_eq!(app.preview_depth, 3);
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info("preview depth: 3".into()))
+        );
+
+        for _ in 0..5 {
+            press(&mut app, KeyCode::Char('-'));
+        }
+        assert_eq!(app.preview_depth, 0);
+
+        // NOTE: the depth of the previews never changes the navigation
+        assert_eq!(app.position, position);
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn record_and_replay_macros() {
+        let config = Config::default();
+        let row = |name: Value| {
+            Value::test_record(record! {
+                "name" => name,
+                "debug" => Value::test_bool(true),
+            })
+        };
+        let name = |s: &str| row(Value::test_string(s));
+        let mut app = App::from_value(Value::test_list(vec![
+            name("a"),
+            name("b"),
+            name("c"),
+            name("d"),
+            row(Value::test_int(5)),
+            name("f"),
+        ]));
+
+        let press = |app: &mut App, key: KeyCode| match handle_key_events(
+            KeyEvent::new(key, KeyModifiers::empty()),
+            app,
+            &config,
+        )
+        .unwrap()
+        {
+            TransitionResult::Mutate(cell, path) => {
+                app.apply_mutation(&cell, &path);
+                TransitionResult::Continue
+            }
+            result => result,
+        };
+        let keys = |app: &mut App, keys: &str| {
+            for c in keys.chars() {
+                let key = match c {
+                    '\n' => KeyCode::Enter,
+                    c => KeyCode::Char(c),
+                };
+                assert_eq!(
+                    press(app, key),
+                    TransitionResult::Continue,
+                    "pressing {:?}",
+                    c
+                );
+            }
+        };
+        let names = |app: &App| {
+            app.value
+                .as_list()
+                .unwrap()
+                .iter()
+                .map(|row| row.as_record().unwrap().get("name").unwrap().clone())
+                .collect::<Vec<Value>>()
+        };
+
+        // NOTE: the editor is only given its width when a frame is drawn
+        keys(&mut app, "Qal");
+        press(&mut app, KeyCode::Char('i'));
+        app.editor.set_width(80);
+        keys(&mut app, "!\nhjQ");
+        assert!(app.recording.is_none());
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info("recorded 6 keys in @a".into()))
+        );
+
+        keys(&mut app, "2@a");
+        keys(&mut app, "@@");
+        assert_eq!(
+            names(&app)[..4],
+            [
+                Value::test_string("!a"),
+                Value::test_string("!b"),
+                Value::test_string("!c"),
+                Value::test_string("!d"),
+            ]
+        );
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::I(4)]));
+
+        // NOTE: the replay stops at the first error, the next rows are left untouched
+        assert_eq!(
+            press(&mut app, KeyCode::Char('@')),
+            TransitionResult::Continue
+        );
+        assert_eq!(
+            press(&mut app, KeyCode::Char('a')),
+            TransitionResult::Error(
+                "replay of @a stopped: can only edit string cells, found int".into()
+            )
+        );
+        assert_eq!(
+            names(&app)[4..],
+            [Value::test_int(5), Value::test_string("f")]
+        );
+        assert_eq!(app.mode, Mode::Normal);
+
+        assert_eq!(
+            press(&mut app, KeyCode::Char('@')),
+            TransitionResult::Continue
+        );
+        assert_eq!(
+            press(&mut app, KeyCode::Char('z')),
+            TransitionResult::Error("no macro in @z".into())
+        );
+
+        // NOTE: a count moves that many times, and is recorded as that many moves
+        app.position.members = to_path_member_vec(&[PM::I(4)]);
+        keys(&mut app, "3k");
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::I(1)]));
+        keys(&mut app, "Qb2jQ");
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::I(3)]));
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info("recorded 2 keys in @b".into()))
+        );
+        keys(&mut app, "2k@b");
+        assert_eq!(app.position.members, to_path_member_vec(&[PM::I(3)]));
+    }
+
+    #[test]
+    fn peek_table_as_columns() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let row = |name: &str, size: i64| {
+            Value::test_record(record! {
+                "name" => Value::test_string(name),
+                "size" => Value::test_int(size),
+            })
+        };
+        let mut app = App::from_value(Value::test_record(record! {
+            "t" => Value::test_list(vec![row("a", 1), row("b", 2)]),
+            "n" => Value::test_int(1),
+        }));
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        press(&mut app, keybindings.peek);
+        assert_eq!(
+            press(&mut app, keybindings.peeking.columns),
+            TransitionResult::Return(Rc::new(Value::test_record(record! {
+                "name" => Value::test_list(vec![Value::test_string("a"), Value::test_string("b")]),
+                "size" => Value::test_list(vec![Value::test_int(1), Value::test_int(2)]),
+            })))
+        );
+
+        press(&mut app, keybindings.normal);
+        press(&mut app, keybindings.navigation.down);
+        press(&mut app, keybindings.peek);
+        assert_eq!(
+            press(&mut app, keybindings.peeking.columns),
+            TransitionResult::Error("can only peek the columns of a table, found int".into())
+        );
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn show_the_source_of_values() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let source = "{\n  \"a\": 1,\n  \"b\": 2\n}";
+        let span = |needle: &str| {
+            let start = source.find(needle).unwrap();
+            Span::new(start, start + needle.len())
+        };
+        let mut app = App::from_value(Value::record(
+            record! {
+                "a" => Value::int(1, span("1")),
+                "b" => Value::int(2, span("2")),
+                "c" => Value::test_int(3),
+            },
+            span(source),
+        ));
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        press(&mut app, keybindings.navigation.down);
+        press(&mut app, keybindings.info);
+        let info = app.info.clone().unwrap();
+        assert_eq!(info.span, Some(span("2")));
+        assert_eq!(info.note, Some("no source text, see `--source`".into()));
+
+        // NOTE: the popup is closed by the next key, which does nothing else
+        press(&mut app, keybindings.navigation.down);
+        assert!(app.info.is_none());
+        assert_eq!(app.cell_under(), Value::int(2, span("2")));
+
+        app.source = Some(source.into());
+        press(&mut app, keybindings.info);
+        let info = app.info.take().unwrap();
+        assert_eq!(
+            info.lines
+                .iter()
+                .filter(|line| line.in_span)
+                .map(|line| line.text.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["  \"b\": 2"]
+        );
+
+        press(&mut app, keybindings.navigation.down);
+        press(&mut app, keybindings.info);
+        assert_eq!(
+            app.info.clone().unwrap().note,
+            Some("no source information".into())
+        );
+    }
+
+    #[test]
+    fn force_quit() {
+        let ctrl = |c: char| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        let key = |c: char| KeyEvent::new(KeyCode::Char(c), KeyModifiers::empty());
+
+        let mut force_quit = ForceQuit::default();
+        assert!(force_quit.pressed(&ctrl('q')));
+        assert!(!force_quit.pressed(&key('q')));
+
+        assert!(!force_quit.pressed(&ctrl('c')));
+        assert!(force_quit.is_armed());
+        assert!(force_quit.pressed(&ctrl('c')));
+
+        // NOTE: any other key in between disarms the first `ctrl-c`
+        assert!(!force_quit.pressed(&ctrl('c')));
+        assert!(!force_quit.pressed(&key('j')));
+        assert!(!force_quit.is_armed());
+        assert!(!force_quit.pressed(&ctrl('c')));
+
+        force_quit.last_ctrl_c = Some(Instant::now() - Duration::from_secs(2));
+        assert!(!force_quit.pressed(&ctrl('c')));
+        assert!(force_quit.is_armed());
+    }
+
+    #[test]
+    fn select_a_range_of_a_list() {
+        let config = Config {
+            peek_status_bar: true,
+            ..Config::default()
+        };
+        let keybindings = config.clone().keybindings;
+        let list = Value::test_list((0..6).map(Value::test_int).collect());
+        let mut app = App::from_value(Value::test_record(record! {
+            "l" => list.clone(),
+            "n" => Value::test_int(1),
+        }));
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        // NOTE: only lists have ranges
+        assert_eq!(
+            press(&mut app, keybindings.range),
+            TransitionResult::Error("can only select a range of a list, found record".into())
+        );
+
+        press(&mut app, keybindings.navigation.right);
+        press(&mut app, keybindings.navigation.down);
+        press(&mut app, keybindings.range);
+        for _ in 0..3 {
+            press(&mut app, keybindings.navigation.down);
+        }
+        assert_eq!(app.range_in_view(), Some((1, 4)));
+        // NOTE: the screen is wide enough for the hints not to hide the status
+        app.screen_size = (250, 10);
+        let screen = ui::render_to_string(&mut app, &config);
+        assert!(screen.contains("rows 1–4 selected"), "{}", screen);
+
+        // NOTE: the range goes both ways from where it started
+        for _ in 0..2 {
+            press(&mut app, keybindings.navigation.up);
+        }
+        for _ in 0..2 {
+            press(&mut app, keybindings.navigation.up);
+        }
+        assert_eq!(app.range_in_view(), Some((0, 1)));
+
+        press(&mut app, keybindings.normal);
+        assert_eq!(app.range_in_view(), None);
+
+        press(&mut app, keybindings.range);
+        for _ in 0..2 {
+            press(&mut app, keybindings.navigation.down);
+        }
+        assert_eq!(
+            press(&mut app, keybindings.peek_under_quick),
+            TransitionResult::Return(Rc::new(Value::test_list(
+                (0..3).map(Value::test_int).collect()
+            )))
+        );
+
+        // NOTE: the range is deleted at once, and the marks after it follow their elements
+        app.marks = vec![
+            to_path_member_vec(&[PM::S("l"), PM::I(1)]),
+            to_path_member_vec(&[PM::S("l"), PM::I(4)]),
+            to_path_member_vec(&[PM::S("n")]),
+        ];
+        assert_eq!(
+            press(&mut app, keybindings.delete_range),
+            TransitionResult::Continue
+        );
+        assert_eq!(
+            app.confirmation.as_ref().map(|c| c.question.as_str()),
+            Some("delete 3 items? (y/n)")
+        );
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(
+            app.value
+                .get_data_by_key("l")
+                .unwrap()
+                .as_list()
+                .unwrap()
+                .len(),
+            6
+        );
+        press(&mut app, keybindings.delete_range);
+        press(&mut app, KeyCode::Char('y'));
+        assert_eq!(
+            app.value.get_data_by_key("l"),
+            Some(Value::test_list((3..6).map(Value::test_int).collect()))
+        );
+        assert_eq!(app.range, None);
+        assert_eq!(
+            app.position.members,
+            to_path_member_vec(&[PM::S("l"), PM::I(0)])
+        );
+        assert_eq!(
+            app.marks,
+            vec![
+                to_path_member_vec(&[PM::S("l"), PM::I(1)]),
+                to_path_member_vec(&[PM::S("n")]),
+            ]
+        );
+        assert_eq!(
+            press(&mut app, keybindings.delete_range),
+            TransitionResult::Error("no range selected in the current list".into())
+        );
+
+        // NOTE: leaving the list forgets the range
+        press(&mut app, keybindings.range);
+        press(&mut app, keybindings.navigation.left);
+        press(&mut app, keybindings.navigation.right);
+        assert_eq!(app.range_in_view(), None);
+    }
+
+    #[test]
+    fn only_changes_draw_frames() {
+        let config = Config::default();
+        let keybindings = config.clone().keybindings;
+        let mut app = App::from_value(Value::test_list(vec![
+            Value::test_int(1),
+            Value::test_int(2),
+        ]));
+
+        let press = |app: &mut App, key: KeyCode| {
+            handle_key_events(KeyEvent::new(key, KeyModifiers::empty()), app, &config).unwrap()
+        };
+
+        let before = app.frame_state();
+        assert_eq!(press(&mut app, KeyCode::F(12)), TransitionResult::Continue);
+        assert!(
+            app.frame_state() == before,
+            "an unbound key changes nothing"
+        );
+
+        press(&mut app, keybindings.navigation.down);
+        assert!(app.frame_state() != before);
+
+        // NOTE: typing in an editor changes the screen without moving in the data
+        press(&mut app, keybindings.insert);
+        let before = app.frame_state();
+        press(&mut app, KeyCode::Char('3'));
+        assert!(app.frame_state() != before);
+    }
 }
@@ -1,83 +1,459 @@
 #![doc = include_str!("../README.md")]
+mod ansi;
 mod app;
+mod command;
 mod config;
 mod edit;
 mod event;
+mod file;
 mod handler;
+mod log;
 mod navigation;
 mod nu;
+mod open;
+mod paths;
+mod session;
 mod tui;
 mod ui;
 
+use std::{collections::VecDeque, rc::Rc, time::Instant};
+
 use anyhow::Result;
-use crossterm::event::KeyEventKind;
-use ratatui::backend::CrosstermBackend;
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::backend::{CrosstermBackend, TestBackend};
 use ratatui::Terminal;
-use std::io;
 
-use nu_protocol::{Record, Span, Value};
+use nu_protocol::{Record, ShellError, Span, Value};
+
+use app::{App, StatusMessage};
+use config::{repr_keycode, Config};
+use event::{coalesce, Event, EventHandler, STALE_KEY};
+use handler::{handle_key_events, ForceQuit, TransitionResult};
+use log::Logger;
+use session::{fingerprint, Sessions};
+use tui::{open_terminal, Tui};
+
+pub use app::Mode;
+pub use config::parse_keys;
+pub use file::{is_output_format, open_file, to_format, Watcher, OUTPUT_FORMATS};
+
+/// the options of an exploration, usually given on the command line
+#[derive(Default)]
+pub struct Options {
+    /// start from the top of the data, instead of where the previous exploration of the same
+    /// data stopped, see the `restore_session` option of the config
+    pub fresh: bool,
+    /// explore the schema of the input instead of its data
+    pub schema: bool,
+    /// disable all the edition of the data, whatever the `read_only` option of the config
+    pub read_only: bool,
+    /// give an error when quitting without peeking anything, instead of `null`, so that a
+    /// script can tell a cancellation apart from a peeked `null`
+    pub strict: bool,
+    /// the [`Mode`] to start in, instead of NORMAL mode
+    pub mode: Mode,
+    /// only render and explore the first items of the top-level list, the other ones being
+    /// given back untouched with the whole data
+    pub limit: Option<usize>,
+    /// the schema to check the rows of the tables against, i.e. a record from the columns to
+    /// their expected type, e.g. `{age: int, email: string}`
+    pub expect: Option<Record>,
+    /// the text the input has been parsed from, to show where its values come from
+    pub source: Option<String>,
+    /// the keys to replay before reading the ones of the terminal, see [`parse_keys`]
+    pub keys: Vec<KeyEvent>,
+    /// only replay the [`Options::keys`], without any terminal, the result of the last one
+    /// being the result of the exploration, e.g. to script it in a test
+    ///
+    /// > :bulb: **Note**  
+    /// > the sessions are neither restored nor saved
+    pub keys_only: bool,
+}
 
-use app::{App, Mode};
-use config::Config;
-use event::{Event, EventHandler};
-use handler::{handle_key_events, TransitionResult};
-use tui::Tui;
+/// explore the *input* interactively
+pub fn explore(
+    config: &Option<Value>,
+    input: Value,
+    watcher: Option<Watcher>,
+    options: Options,
+) -> Result<Value> {
+    let mut watcher = watcher;
 
-pub fn explore(config: &Option<Value>, input: Value) -> Result<Value> {
-    let config = Config::from_value(
+    let mut config = Config::from_value(
         config
             .clone()
             .unwrap_or(Value::record(Record::new(), Span::unknown())),
     )
     .expect("Could not convert config value to an actual config");
+    if options.read_only {
+        config.read_only = true;
+    }
+    // NOTE: see https://no-color.org/
+    if std::env::var_os("NO_COLOR").is_some_and(|val| !val.is_empty()) {
+        config.display.monochrome = true;
+    }
+
+    // NOTE: the items past the limit are put aside, to be given back with the whole data, and
+    // the schema only describes the explored items
+    let (input, hidden) = match (input, options.limit) {
+        (
+            Value::List {
+                mut vals,
+                internal_span,
+            },
+            Some(limit),
+        ) if limit < vals.len() => {
+            let hidden = vals.split_off(limit);
+            (Value::list(vals, internal_span), hidden)
+        }
+        (input, _) => (input, vec![]),
+    };
+    let (input, hidden) = if options.schema {
+        (nu::schema::schema(&input, &config.schema), vec![])
+    } else {
+        (input, hidden)
+    };
 
+    let restore_session = config.restore_session && !options.fresh && !options.keys_only;
+    // NOTE: the fingerprint goes through the whole top-level shape, only compute it when needed
+    let mut sessions = if restore_session {
+        Some((Sessions::open(), fingerprint(&input)))
+    } else {
+        None
+    };
+
+    let mut app = App::from_value(input);
+    app.logger = Logger::new(&config.log);
+    app.preview_depth = config.display.preview_depth;
+    app.source = options.source.clone();
+    app.strict = options.strict;
+    if !hidden.is_empty() {
+        let shown = app.value.as_list().map_or(0, |vals| vals.len());
+        app.status_message = Some(StatusMessage::Info(format!(
+            "showing the first {} of {} items",
+            shown,
+            shown + hidden.len()
+        )));
+    }
+    app.hidden = hidden;
+    let (column_width, warnings) = config.display.column_width.clone().clamp();
+    config.display.column_width = column_width.clone();
+    app.column_width = column_width;
+    if !warnings.is_empty() {
+        app.status_message = Some(StatusMessage::Error(warnings.join(", ")));
+    }
+    if let Some(schema) = options.expect.clone() {
+        app.validation = Some(schema);
+        // NOTE: only the top-level table is validated right away, the others on demand
+        if let Err(err) = app.validate_at(&[]) {
+            app.status_message = Some(StatusMessage::Error(err));
+        }
+    }
+    if let Some(members) = sessions
+        .as_ref()
+        .and_then(|(sessions, id)| sessions.restore(id, &app.value))
+    {
+        app.position.members = members;
+        app.recenter = true;
+    } else if config.navigation.auto_descend {
+        navigation::auto_descend(&mut app);
+    }
+    if config.read_only && options.mode == Mode::Insert {
+        app.status_message = Some(StatusMessage::Info("read-only".into()));
+    } else if let Err(err) = app.enter_mode(options.mode.clone()) {
+        app.status_message = Some(StatusMessage::Error(err));
+    }
+
+    if options.keys_only {
+        return replay_headless(app, &config, &options.keys, options.strict);
+    }
+
+    let output = open_terminal()?;
     let mut tui = Tui::new(
-        Terminal::new(CrosstermBackend::new(io::stderr()))?,
+        Terminal::new(CrosstermBackend::new(output))?,
         EventHandler::new(250),
     );
     tui.init()?;
 
-    let mut app = App::from_value(input);
-
+    let mut keys: VecDeque<KeyEvent> = options.keys.iter().copied().collect();
+    // NOTE: the events read while a frame was being drawn, see [`event::coalesce`]
+    let mut queue: VecDeque<(Event, Instant)> = VecDeque::new();
+    let mut force_quit = ForceQuit::default();
+    // NOTE: a frame is only drawn when something might have changed, i.e. not on every tick
+    let mut redraw = true;
     loop {
-        if app.mode == Mode::Insert {
-            app.editor.set_width(tui.size()?.width as usize)
+        if redraw {
+            let size = tui.size()?;
+            app.screen_size = (size.width, size.height);
+            app.fit_editor();
+
+            tui.draw(&mut app, &config, None)?;
+            redraw = false;
         }
 
-        tui.draw(&mut app, &config, None)?;
+        // NOTE: the replayed keys come first, as if they had been typed
+        let (event, at) = match keys.pop_front() {
+            Some(key) => (Event::Key(key), Instant::now()),
+            None => match queue.pop_front() {
+                Some(event) => event,
+                None => tui.events.next()?,
+            },
+        };
+        match event {
+            Event::Tick => {
+                app.tick();
 
-        match tui.events.next()? {
-            Event::Tick => app.tick(),
+                if let Some(watcher) = watcher.as_mut() {
+                    match watcher.poll() {
+                        Some(Ok(value)) => {
+                            redraw = true;
+                            if options.schema {
+                                app.reload(nu::schema::schema(&value, &config.schema));
+                            } else {
+                                app.reload(value);
+                            }
+                            app.status_message = Some(StatusMessage::Info("reloaded".into()));
+                        }
+                        Some(Err(err)) => {
+                            redraw = true;
+                            app.status_message = Some(StatusMessage::Error(err.msg));
+                        }
+                        None => {}
+                    }
+                }
+            }
             Event::Key(key_event) => {
                 if key_event.kind == KeyEventKind::Press {
-                    match handle_key_events(key_event, &mut app, &config)? {
-                        TransitionResult::Quit => break,
-                        TransitionResult::Continue => {}
-                        TransitionResult::Mutate(cell, path) => {
-                            app.value =
-                                crate::nu::value::mutate_value_cell(&app.value, &path, &cell)
+                    // NOTE: a key that changes nothing on the screen, e.g. an unbound key or a
+                    // move at the edge of a view, does not draw a frame
+                    let before = app.frame_state();
+                    redraw = true;
+                    app.status_message = None;
+                    // NOTE: the escape hatch comes before anything that could get in the way
+                    if force_quit.pressed(&key_event) {
+                        break;
+                    }
+                    if force_quit.is_armed() {
+                        app.status_message = Some(StatusMessage::Info(
+                            "press ctrl-c again to force quit".into(),
+                        ));
+                        continue;
+                    }
+                    // NOTE: the repetitions of a held navigation key are handled at once, before
+                    // drawing a single frame, so that they do not pile up behind slow frames
+                    let navigation = &config.keybindings.navigation;
+                    let repetitions = if app.mode == Mode::Normal
+                        && app.pending_macro.is_none()
+                        && app.count == 0
+                        && (key_event.code == navigation.up || key_event.code == navigation.down)
+                    {
+                        queue.extend(tui.events.pending());
+                        coalesce(&key_event, at, &mut queue, Instant::now(), STALE_KEY)
+                    } else {
+                        1
+                    };
+                    let mut result = TransitionResult::Continue;
+                    for _ in 0..repetitions {
+                        result = handle_key_events(key_event, &mut app, &config)?;
+                        if !matches!(result, TransitionResult::Continue) {
+                            break;
                         }
+                    }
+                    match result {
+                        TransitionResult::Quit => break,
+                        TransitionResult::Continue => redraw = app.frame_state() != before,
+                        TransitionResult::Mutate(cell, path) => app.apply_mutation(&cell, &path),
                         TransitionResult::Error(error) => {
                             tui.draw(&mut app, &config, Some(&error))?;
-                            loop {
-                                if let Event::Key(_) = tui.events.next()? {
-                                    break;
+                            let forced = loop {
+                                if let Some(key_event) = keys.pop_front() {
+                                    break force_quit.pressed(&key_event);
+                                }
+                                let (event, _) = match queue.pop_front() {
+                                    Some(event) => event,
+                                    None => tui.events.next()?,
+                                };
+                                if let Event::Key(key_event) = event {
+                                    break force_quit.pressed(&key_event);
                                 }
+                            };
+                            if forced {
+                                break;
                             }
                         }
                         TransitionResult::Return(value) => {
                             tui.exit()?;
-                            return Ok(value);
+                            save_session(sessions.as_mut(), &app);
+                            // NOTE: the app is dropped first so that peeking the whole data does
+                            // not copy it
+                            drop(app);
+                            return Ok(Rc::unwrap_or_clone(value));
                         }
                     }
                 }
             }
             Event::Mouse(_) => {}
-            Event::Resize(_, _) => {}
+            Event::Resize(_, _) => redraw = true,
         }
     }
 
     tui.exit()?;
+    save_session(sessions.as_mut(), &app);
+
+    nothing_peeked(options.strict)
+}
+
+/// the result of an exploration quit without peeking anything, an error when *strict*
+fn nothing_peeked(strict: bool) -> Result<Value> {
+    if strict {
+        return Err(ShellError::GenericError {
+            error: "nothing peeked".into(),
+            msg: "the exploration was quit without peeking anything".into(),
+            span: None,
+            help: Some("remove `--strict` to get `null` instead".into()),
+            inner: vec![],
+        }
+        .into());
+    }
 
     Ok(Value::nothing(Span::unknown()))
 }
+
+/// the size of the screen the keys are replayed on without a terminal, see
+/// [`Options::keys_only`]
+const HEADLESS_SIZE: (u16, u16) = (80, 24);
+
+/// replay the *keys* on the *app* without any terminal, see [`Options::keys_only`]
+///
+/// each key is replayed on a frame rendered off-screen, so that it sees exactly what it would
+/// in a terminal of [`HEADLESS_SIZE`].
+///
+/// > :bulb: **Note**  
+/// > an error stops the replay and is the result of the exploration
+fn replay_headless(
+    mut app: App,
+    config: &Config,
+    keys: &[KeyEvent],
+    strict: bool,
+) -> Result<Value> {
+    let mut terminal = Terminal::new(TestBackend::new(HEADLESS_SIZE.0, HEADLESS_SIZE.1))?;
+    app.screen_size = HEADLESS_SIZE;
+
+    for (i, key) in keys.iter().enumerate() {
+        app.fit_editor();
+        terminal.draw(|frame| ui::render_ui_safely(frame, &mut app, config, None))?;
+
+        app.status_message = None;
+        match handle_key_events(*key, &mut app, config)? {
+            TransitionResult::Quit => return nothing_peeked(strict),
+            TransitionResult::Continue => {}
+            TransitionResult::Mutate(cell, path) => app.apply_mutation(&cell, &path),
+            TransitionResult::Error(error) => {
+                return Err(ShellError::GenericError {
+                    error: "invalid key".into(),
+                    msg: format!(
+                        "key {} (`{}`) failed: {}",
+                        i,
+                        repr_keycode(&key.code),
+                        error
+                    ),
+                    span: None,
+                    help: None,
+                    inner: vec![],
+                }
+                .into())
+            }
+            TransitionResult::Return(value) => {
+                drop(app);
+                return Ok(Rc::unwrap_or_clone(value));
+            }
+        }
+    }
+
+    nothing_peeked(strict)
+}
+
+/// remember the position of the *app* for the next exploration of the same data, the *sessions*
+/// coming with the fingerprint of the data
+///
+/// > :bulb: **Note**  
+/// > the session is a convenience, so failing to save it is not an error
+fn save_session(sessions: Option<&mut (Sessions, String)>, app: &App) {
+    if let Some((sessions, id)) = sessions {
+        let _ = sessions.save(id, &app.root_position().members);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use nu_protocol::{record, Value};
+
+    use super::{explore, replay_headless, App, Config, Options};
+
+    #[test]
+    fn replay_keys_without_terminal() {
+        let config = Config::default();
+        let value = Value::test_list(vec![
+            Value::test_record(record! { "a" => Value::test_int(1) }),
+            Value::test_record(record! { "a" => Value::test_int(2) }),
+        ]);
+        let keys = |keys: &[KeyCode]| -> Vec<KeyEvent> {
+            keys.iter()
+                .map(|key| KeyEvent::new(*key, KeyModifiers::empty()))
+                .collect()
+        };
+
+        let result = replay_headless(
+            App::from_value(value.clone()),
+            &config,
+            &keys(&[
+                KeyCode::Char('j'),
+                KeyCode::Char('l'),
+                KeyCode::Char('p'),
+                KeyCode::Char('p'),
+            ]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(result, Value::test_int(2));
+
+        // NOTE: running out of keys is like quitting
+        let result = replay_headless(
+            App::from_value(value.clone()),
+            &config,
+            &keys(&[KeyCode::Char('j')]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(result, Value::test_nothing());
+        assert!(replay_headless(App::from_value(value), &config, &[], true).is_err());
+    }
+
+    #[test]
+    fn limit_what_is_explored() {
+        let keybindings = Config::default().keybindings;
+        let value = Value::test_list((0..5).map(Value::test_int).collect());
+        let explore_keys = |keys: &[KeyCode]| {
+            let options = Options {
+                limit: Some(2),
+                keys: keys
+                    .iter()
+                    .map(|key| KeyEvent::new(*key, KeyModifiers::empty()))
+                    .collect(),
+                keys_only: true,
+                ..Options::default()
+            };
+            explore(&None, value.clone(), None, options).unwrap()
+        };
+
+        // NOTE: only the first items are explored, going down wraps around before the others
+        let down = keybindings.navigation.down;
+        let (peek, under) = (keybindings.peek, keybindings.peeking.under);
+        assert_eq!(explore_keys(&[down, down, peek, under]), Value::test_int(0));
+
+        // NOTE: the whole data is given back
+        assert_eq!(explore_keys(&[peek, keybindings.peeking.all]), value);
+        assert_eq!(explore_keys(&[peek, keybindings.peeking.view]), value);
+    }
+}
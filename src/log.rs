@@ -0,0 +1,113 @@
+//! a tiny logger, appending lines to a file to help with bug reports
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::config::{LogConfig, LogLevel};
+
+/// append a *line* to the log file at *path*, prefixed with the number of seconds since the Unix
+/// epoch
+///
+/// > :bulb: **Note**  
+/// > the file is created if it does not exist yet
+pub(crate) fn append(path: &str, line: &str) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    writeln!(file, "{} {}", now, line)
+}
+
+/// a logger to the file of [`LogConfig`], if any, that stops at the first failure
+///
+/// the log must never get in the way of the exploration: when a line cannot be written, e.g.
+/// the file is not writable, the error is given once and all the next lines are dropped.
+#[derive(Debug, Default)]
+pub(crate) struct Logger {
+    /// the file to append to, `None` when logging is disabled
+    file: Option<String>,
+    level: LogLevel,
+}
+
+impl Logger {
+    pub(crate) fn new(config: &LogConfig) -> Self {
+        Self {
+            file: config.file.clone(),
+            level: config.level,
+        }
+    }
+
+    /// whether a line of the given *level* would be written
+    pub(crate) fn enabled(&self, level: LogLevel) -> bool {
+        self.file.is_some() && level <= self.level
+    }
+
+    /// append a *line* of the given *level*, if enabled
+    ///
+    /// > :bulb: **Note**  
+    /// > the error of the first failed write is given back and the logger is then disabled
+    pub(crate) fn log(&mut self, level: LogLevel, line: &str) -> Result<(), String> {
+        if !self.enabled(level) {
+            return Ok(());
+        }
+        let line = format!("{} {}", repr_level(level), line);
+        let file = self.file.as_ref().unwrap();
+        if let Err(e) = append(file, &line) {
+            let err = format!("could not write to the log file `{}`: {}", file, e);
+            self.file = None;
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+fn repr_level(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Error => "ERROR",
+        LogLevel::Info => "INFO",
+        LogLevel::Debug => "DEBUG",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Logger;
+    use crate::config::{LogConfig, LogLevel};
+
+    #[test]
+    fn log_levels_and_failures() {
+        let file = std::env::temp_dir().join(format!(
+            "nu_plugin_explore-logger-{}.log",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&file);
+
+        let mut logger = Logger::new(&LogConfig {
+            file: Some(file.to_string_lossy().to_string()),
+            level: LogLevel::Info,
+        });
+        logger.log(LogLevel::Error, "foo").unwrap();
+        logger.log(LogLevel::Debug, "bar").unwrap();
+        logger.log(LogLevel::Info, "baz").unwrap();
+
+        let lines: Vec<String> = std::fs::read_to_string(&file)
+            .unwrap()
+            .lines()
+            .map(|l| l.split_once(' ').unwrap().1.to_string())
+            .collect();
+        assert_eq!(lines, vec!["ERROR foo", "INFO baz"]);
+        std::fs::remove_file(&file).unwrap();
+
+        // NOTE: a directory cannot be written to, the error is only given once
+        let mut logger = Logger::new(&LogConfig {
+            file: Some(std::env::temp_dir().to_string_lossy().to_string()),
+            level: LogLevel::Debug,
+        });
+        assert!(logger.log(LogLevel::Error, "foo").is_err());
+        assert_eq!(logger.log(LogLevel::Error, "foo"), Ok(()));
+        assert!(!logger.enabled(LogLevel::Error));
+    }
+}
@@ -1,6 +1,11 @@
 use nu_plugin::{serve_plugin, EvaluatedCall, LabeledError, MsgPackSerializer, Plugin};
-use nu_plugin_explore::explore;
-use nu_protocol::{Category, PluginExample, PluginSignature, ShellError, Type, Value};
+use nu_plugin_explore::{
+    explore, is_output_format, open_file, parse_keys, to_format, Mode, Options, Watcher,
+    OUTPUT_FORMATS,
+};
+use nu_protocol::{
+    Category, PluginExample, PluginSignature, Record, ShellError, Spanned, SyntaxShape, Type, Value,
+};
 
 /// the main structure of the [Nushell](https://nushell.sh) plugin
 struct Explore;
@@ -10,12 +15,145 @@ impl Plugin for Explore {
         vec![PluginSignature::build("nu_plugin_explore")
             .usage("interactively explore Nushell structured data")
             .input_output_type(Type::Any, Type::Any)
+            .optional(
+                "file",
+                SyntaxShape::Filepath,
+                "a file to open and explore, instead of the input",
+            )
+            .switch(
+                "watch",
+                "reload the file whenever it changes on disk, requires a file",
+                Some('w'),
+            )
+            .switch(
+                "fresh",
+                "start from the top of the data, instead of where the previous exploration of the same data stopped",
+                None,
+            )
+            .switch(
+                "read-only",
+                "disable all the edition of the data, which can only be peeked",
+                None,
+            )
+            .switch(
+                "schema",
+                "explore the structure of the data, i.e. the types of its fields, instead of the data itself",
+                None,
+            )
+            .switch(
+                "strict",
+                "give an error when quitting without peeking anything, instead of `null`",
+                None,
+            )
+            .named(
+                "mode",
+                SyntaxShape::String,
+                "the mode to start in, e.g. insert or peeking, instead of normal",
+                Some('m'),
+            )
+            .named(
+                "limit",
+                SyntaxShape::Int,
+                "only render and explore the first items of a list, the whole list being given back when peeking all of it",
+                Some('l'),
+            )
+            .named(
+                "expect",
+                SyntaxShape::Any,
+                "the expected types of some columns, to mark the rows of the tables that do not fit",
+                None,
+            )
+            .named(
+                "validate",
+                SyntaxShape::Closure(Some(vec![SyntaxShape::Any])),
+                "a closure to mark the rows of the tables it fails on, not supported until plugins can evaluate closures",
+                None,
+            )
+            .named(
+                "source",
+                SyntaxShape::Filepath,
+                "the file the input has been parsed from, to show where its values come from",
+                None,
+            )
+            .named(
+                "output",
+                SyntaxShape::String,
+                "serialize the peeked value as a string, in one of [nuon, json, toml, yaml, csv, tsv]",
+                None,
+            )
+            .named(
+                "keys",
+                SyntaxShape::Any,
+                "keys to replay before the ones of the terminal, e.g. `\"jj l ctrl-d\"` or a list of key names",
+                None,
+            )
+            .named(
+                "keys-only",
+                SyntaxShape::Any,
+                "like `--keys` but without any terminal, the result of the keys being the result of `explore`",
+                None,
+            )
             .plugin_examples(vec![
                 PluginExample {
                     example: "open Cargo.toml | explore".into(),
                     description: "explore the Cargo.toml file of this project".into(),
                     result: None,
                 },
+                PluginExample {
+                    example: "explore Cargo.toml".into(),
+                    description: "open and explore the Cargo.toml file directly".into(),
+                    result: None,
+                },
+                PluginExample {
+                    example: "explore config.toml --watch".into(),
+                    description: "explore a file while editing it somewhere else".into(),
+                    result: None,
+                },
+                PluginExample {
+                    example: "open huge.json | explore --limit 1000".into(),
+                    description: "explore only the first 1000 items of a large list".into(),
+                    result: None,
+                },
+                PluginExample {
+                    example: "open config.toml | explore --fresh".into(),
+                    description: "explore a file from the top, ignoring where the last exploration stopped".into(),
+                    result: None,
+                },
+                PluginExample {
+                    example: "http get https://api.github.com/repos/nushell/nushell | explore --schema".into(),
+                    description: "learn the shape of the response of an unfamiliar API".into(),
+                    result: None,
+                },
+                PluginExample {
+                    example: "open config.json | explore --mode insert".into(),
+                    description: "start editing the first cell right away".into(),
+                    result: None,
+                },
+                PluginExample {
+                    example: r#"try { ls | explore --strict } catch { print "nothing was chosen" }"#.into(),
+                    description: "use the exploration as an interactive chooser in a script".into(),
+                    result: None,
+                },
+                PluginExample {
+                    example: "explore | save new-config.nuon".into(),
+                    description: "type some data from scratch, starting from an empty record, and peek all of it to save it".into(),
+                    result: None,
+                },
+                PluginExample {
+                    example: "open users.json | explore --expect {age: int, email: string}".into(),
+                    description: "review the quality of some data, jumping between the rows that do not fit with `]` and `[`".into(),
+                    result: None,
+                },
+                PluginExample {
+                    example: "open data.json | explore --output yaml | save peeked.yaml".into(),
+                    description: "get what has been peeked as text, e.g. to give it to another program".into(),
+                    result: None,
+                },
+                PluginExample {
+                    example: r#"open data.json | explore --keys-only "jj l pp""#.into(),
+                    description: "script an exploration, e.g. to test a config, and get what has been peeked".into(),
+                    result: None,
+                },
                 PluginExample {
                     example: r#"$nu | explore {show_cell_path: false, layout: "compact"}"#.into(),
                     description: "explore `$nu` and set some config options".into(),
@@ -33,19 +171,167 @@ impl Plugin for Explore {
         input: &Value,
     ) -> Result<Value, LabeledError> {
         match name {
-            "nu_plugin_explore" => match explore(config, input.clone()) {
-                Ok(value) => Ok(value),
-                Err(err) => {
-                    match err.downcast_ref::<ShellError>() {
-                        Some(shell_error) => Err(LabeledError::from(shell_error.clone())),
-                        None => Err(LabeledError {
-                            label: "unexpected internal error".into(),
-                            msg: "could not transform error into ShellError, there was another kind of crash...".into(),
+            "nu_plugin_explore" => {
+                let file: Option<Spanned<String>> = call.opt(0)?;
+
+                let watcher = match (call.has_flag("watch")?, &file) {
+                    (true, Some(file)) => Some(Watcher::new(&file.item, file.span)),
+                    (true, None) => {
+                        return Err(LabeledError {
+                            label: "nothing to watch".into(),
+                            msg: "`--watch` requires a file to explore".into(),
                             span: Some(call.head),
-                        }),
+                        })
+                    }
+                    (false, _) => None,
+                };
+
+                let input = match (input, file) {
+                    // NOTE: exploring nothing starts from an empty record, to type data from
+                    // scratch
+                    (Value::Nothing { .. }, None) => Value::record(Record::new(), call.head),
+                    (input, None) => input.clone(),
+                    (Value::Nothing { .. }, Some(file)) => open_file(&file.item, file.span)?,
+                    (_, Some(file)) => {
+                        return Err(LabeledError {
+                            label: "ambiguous input".into(),
+                            msg: "cannot explore both the piped input and a file, please give only one of them".into(),
+                            span: Some(file.span),
+                        })
+                    }
+                };
+
+                let limit = match call.get_flag::<Spanned<i64>>("limit")? {
+                    Some(limit) => Some(usize::try_from(limit.item).map_err(|_| LabeledError {
+                        label: "invalid limit".into(),
+                        msg: format!("expected a non-negative integer, found {}", limit.item),
+                        span: Some(limit.span),
+                    })?),
+                    None => None,
+                };
+
+                if limit.is_some() && !matches!(input, Value::List { .. }) {
+                    return Err(LabeledError {
+                        label: "invalid input".into(),
+                        msg: format!("`--limit` requires a list, found {}", input.get_type()),
+                        span: call.get_flag_value("limit").map(|limit| limit.span()),
+                    });
+                }
+
+                let mode = match call.get_flag::<Spanned<String>>("mode")? {
+                    Some(mode) => mode.item.parse::<Mode>().map_err(|err| LabeledError {
+                        label: "invalid mode".into(),
+                        msg: err,
+                        span: Some(mode.span),
+                    })?,
+                    None => Mode::Normal,
+                };
+
+                // NOTE: the plugins of Nushell 0.90 have no engine interface to evaluate a closure
+                // with, so `--validate` waits for a newer `nu-plugin` and `--expect` checks the
+                // rows against a record of types in the meantime
+                if let Some(closure) = call.get_flag::<Value>("validate")? {
+                    return Err(LabeledError {
+                        label: "closures are not supported".into(),
+                        msg: "plugins can not evaluate closures yet, give the expected types to `--expect` instead, e.g. `--expect {age: int}`".into(),
+                        span: Some(closure.span()),
+                    });
+                }
+                let expect = match call.get_flag::<Value>("expect")? {
+                    Some(Value::Record { val, .. }) => Some(val),
+                    Some(x) => {
+                        return Err(LabeledError {
+                            label: "invalid schema".into(),
+                            msg: format!(
+                                "expected a record of types, e.g. `{{age: int}}`, found {}",
+                                x.get_type()
+                            ),
+                            span: Some(x.span()),
+                        })
+                    }
+                    None => None,
+                };
+
+                let source = match call.get_flag::<Spanned<String>>("source")? {
+                    Some(path) => Some(std::fs::read_to_string(&path.item).map_err(|err| {
+                        LabeledError {
+                            label: "could not read the source".into(),
+                            msg: err.to_string(),
+                            span: Some(path.span),
+                        }
+                    })?),
+                    None => None,
+                };
+
+                let (keys, keys_only) = match (
+                    call.get_flag::<Value>("keys")?,
+                    call.get_flag::<Value>("keys-only")?,
+                ) {
+                    (None, None) => (vec![], false),
+                    (Some(keys), None) => (parse_keys(&keys)?, false),
+                    (None, Some(keys)) => (parse_keys(&keys)?, true),
+                    (Some(_), Some(keys)) => {
+                        return Err(LabeledError {
+                            label: "ambiguous keys".into(),
+                            msg: "cannot give both `--keys` and `--keys-only`, please give only one of them".into(),
+                            span: Some(keys.span()),
+                        })
+                    }
+                };
+
+                // NOTE: the format is checked before exploring, not to lose the exploration
+                let output = call.get_flag::<Spanned<String>>("output")?;
+                if let Some(output) = &output {
+                    if !is_output_format(&output.item) {
+                        return Err(LabeledError {
+                            label: "invalid output format".into(),
+                            msg: format!(
+                                "expected one of [{}], found `{}`",
+                                OUTPUT_FORMATS, output.item
+                            ),
+                            span: Some(output.span),
+                        });
+                    }
+                }
+
+                let options = Options {
+                    fresh: call.has_flag("fresh")?,
+                    schema: call.has_flag("schema")?,
+                    read_only: call.has_flag("read-only")?,
+                    strict: call.has_flag("strict")?,
+                    mode,
+                    limit,
+                    expect,
+                    source,
+                    keys,
+                    keys_only,
+                };
+
+                match explore(config, input, watcher, options) {
+                    // NOTE: the value is serialized once the terminal has been restored, and
+                    // quitting without peeking anything still gives `null`
+                    Ok(value) => match output {
+                        Some(output) if !value.is_nothing() => to_format(&value, &output.item)
+                            .map(|text| Value::string(text, call.head))
+                            .map_err(|msg| LabeledError {
+                                label: "could not serialize the output".into(),
+                                msg,
+                                span: Some(output.span),
+                            }),
+                        _ => Ok(value),
+                    },
+                    Err(err) => {
+                        match err.downcast_ref::<ShellError>() {
+                            Some(shell_error) => Err(LabeledError::from(shell_error.clone())),
+                            None => Err(LabeledError {
+                                label: "unexpected internal error".into(),
+                                msg: "could not transform error into ShellError, there was another kind of crash...".into(),
+                                span: Some(call.head),
+                            }),
+                        }
                     }
                 }
-            },
+            }
             _ => Err(LabeledError {
                 label: "Plugin call with wrong name signature".into(),
                 msg: "the signature used to call the plugin does not match any name in the plugin signature vector".into(),
@@ -1,7 +1,10 @@
 //! navigate in the data in all directions
-use nu_protocol::{ast::PathMember, Span, Value};
+use nu_protocol::{ast::PathMember, Record, Span, Value};
 
-use crate::app::{App, Mode};
+use crate::{
+    app::{App, Mode, StatusMessage},
+    nu::value::{column_index, column_member, follow, is_ragged_table, range_len, table_columns},
+};
 
 /// specify a vertical direction in which to go in the data
 pub enum Direction {
@@ -39,30 +42,33 @@ pub(super) fn go_up_or_down_in_data(app: &mut App, direction: Direction) {
         .pop()
         .unwrap_or_else(|| panic!("unexpected error: position is empty"));
 
-    let cell = app
-        .value
-        .clone()
-        .follow_cell_path(&app.position.members, false)
-        .unwrap_or_else(|_| {
-            panic!(
-                "unexpected error when following {:?} in {}",
-                app.position.members,
-                app.value.into_string(" ", &nu_protocol::Config::default())
-            )
-        });
+    let cell = follow(&app.value, &app.position.members).unwrap_or_else(|| {
+        panic!(
+            "unexpected error when following {:?} in {}",
+            app.position.members,
+            app.value.into_string(" ", &nu_protocol::Config::default())
+        )
+    });
 
-    match cell {
-        Value::List { vals, .. } => {
+    // NOTE: the length of a range is computed without enumerating it
+    let len = match &*cell {
+        Value::List { vals, .. } => Some(vals.len()),
+        Value::Range { val, internal_span } => range_len(val, *internal_span).ok(),
+        _ => None,
+    };
+
+    match (&*cell, len) {
+        (_, Some(len)) => {
             let new = match current {
                 PathMember::Int {
                     val,
                     span,
                     optional,
                 } => PathMember::Int {
-                    val: if vals.is_empty() {
+                    val: if len == 0 {
                         val
                     } else {
-                        let len = vals.len() as i32;
+                        let len = len as i32;
                         let new_index = (val as i32 + direction + len) % len;
 
                         new_index as usize
@@ -74,26 +80,16 @@ pub(super) fn go_up_or_down_in_data(app: &mut App, direction: Direction) {
             };
             app.position.members.push(new);
         }
-        Value::Record { val: rec, .. } => {
-            let new = match current {
-                PathMember::String {
-                    val,
-                    span,
-                    optional,
-                } => PathMember::String {
-                    val: if rec.cols.is_empty() {
-                        "".into()
-                    } else {
-                        let index = rec.cols.iter().position(|x| x == &val).unwrap() as i32;
-                        let len = rec.cols.len() as i32;
-                        let new_index = (index + direction + len) % len;
+        (Value::Record { val: rec, .. }, _) => {
+            let new = if rec.cols.is_empty() {
+                current
+            } else {
+                // NOTE: a column that does not exist anymore is the first one
+                let index = column_index(rec, &current).unwrap_or(0) as i32;
+                let len = rec.cols.len() as i32;
+                let new_index = (index + direction + len) % len;
 
-                        rec.cols[new_index as usize].clone()
-                    },
-                    span,
-                    optional,
-                },
-                _ => panic!("current should be an string path member"),
+                column_member(rec, new_index as usize)
             };
             app.position.members.push(new);
         }
@@ -101,38 +97,148 @@ pub(super) fn go_up_or_down_in_data(app: &mut App, direction: Direction) {
     }
 }
 
+/// the *cell path* member of the first column of a record, optional when the record is empty
+pub(crate) fn first_member(rec: &Record) -> PathMember {
+    if rec.cols.is_empty() {
+        PathMember::String {
+            val: "".into(),
+            span: Span::unknown(),
+            optional: true,
+        }
+    } else {
+        column_member(rec, 0)
+    }
+}
+
 /// go one level deeper in the data
 ///
 /// > :bulb: **Note**  
 /// > this function will
 /// > - push a new *cell path* member to the state if there is more depth ahead
 /// > - mark the state as *at the bottom* if the value at the new depth is of a simple type
+/// > - mark the state as *at the bottom* and the last *cell path* member as optional if the value
+/// >   at the new depth is a column missing from a row of a ragged table, any other missing value
+/// >   being a bug
+/// > - go into the elements of a range as in a list, or give an error if the range can't be
+/// >   explored, see [`range_to_list`]
 pub(super) fn go_deeper_in_data(app: &mut App) {
-    let cell = app
-        .value
-        .clone()
-        .follow_cell_path(&app.position.members, false)
-        .unwrap_or_else(|_| {
-            panic!(
-                "unexpected error when following {:?} in {}",
-                app.position.members,
-                app.value.into_string(" ", &nu_protocol::Config::default())
-            )
-        });
+    // NOTE: the scroll offset of each level is kept, to come back to it as it was left
+    app.remember_offset();
 
-    match cell {
+    // NOTE: with a column under the cursor, the row and the column are crossed in one step
+    if let Some(column) = app.column.take() {
+        if let Some(column) = view_columns(app).get(column) {
+            app.position.members.push(PathMember::String {
+                val: column.clone(),
+                span: Span::unknown(),
+                optional: false,
+            });
+            app.column_depths.push(app.position.members.len());
+        }
+    }
+
+    let cell = match follow(&app.value, &app.position.members) {
+        Some(cell) => cell,
+        None => {
+            if !is_missing_column(app) {
+                panic!(
+                    "unexpected error when following {:?} in {}",
+                    app.position.members,
+                    app.value.into_string(" ", &nu_protocol::Config::default())
+                );
+            }
+            if let Some(PathMember::String { optional, .. }) = app.position.members.last_mut() {
+                *optional = true;
+            }
+            app.hit_bottom();
+            app.restore_offset();
+            return;
+        }
+    };
+
+    match &*cell {
         Value::List { vals, .. } => app.position.members.push(PathMember::Int {
             val: 0,
             span: Span::unknown(),
             optional: vals.is_empty(),
         }),
-        Value::Record { val: rec, .. } => app.position.members.push(PathMember::String {
-            val: rec.cols.first().unwrap_or(&"".to_string()).into(),
-            span: Span::unknown(),
-            optional: rec.cols.is_empty(),
-        }),
+        Value::Record { val: rec, .. } => app.position.members.push(first_member(rec)),
+        Value::Range { val, internal_span } => match range_len(val, *internal_span) {
+            Ok(len) => app.position.members.push(PathMember::Int {
+                val: 0,
+                span: Span::unknown(),
+                optional: len == 0,
+            }),
+            Err(err) => app.status_message = Some(StatusMessage::Error(err)),
+        },
+        // NOTE: closures, blocks and cell paths can't be explored further, they are described in
+        // full at the bottom
+        Value::Closure { .. } | Value::Block { .. } | Value::CellPath { .. } => app.hit_bottom(),
         _ => app.hit_bottom(),
     }
+    app.restore_offset();
+}
+
+/// whether the cursor is on a column that is missing from a row of a table, i.e. a hole of a
+/// ragged table
+fn is_missing_column(app: &App) -> bool {
+    let members = &app.position.members;
+    if members.len() < 2 || !matches!(members.last(), Some(PathMember::String { .. })) {
+        return false;
+    }
+
+    let row = follow(&app.value, &members[..members.len() - 1]);
+    let table = follow(&app.value, &members[..members.len() - 2]);
+    matches!(row.as_deref(), Some(Value::Record { .. }))
+        && matches!(table.as_deref(), Some(Value::List { .. }))
+}
+
+/// keep going deeper while the current view has a single child that is a non-empty list or
+/// record, e.g. from the `data` of `{data: {items: [...]}}` to the first of its items
+///
+/// the skipped levels are shown in the status bar. going back climbs them one at a time, see
+/// [`go_back_in_data`].
+///
+/// > :bulb: **Note**  
+/// > the descent stops before a single simple value, i.e. it never hits the bottom
+///
+/// > see the tests for detailed examples
+pub(crate) fn auto_descend(app: &mut App) {
+    let start = app.position.members.len();
+
+    while !app.is_at_bottom() && app.column.is_none() {
+        let n = app.position.members.len();
+        if n == 0 {
+            break;
+        }
+        let single = match follow(&app.value, &app.position.members[..n - 1]).as_deref() {
+            Some(Value::List { vals, .. }) => vals.len() == 1,
+            Some(Value::Record { val: rec, .. }) => rec.len() == 1,
+            _ => false,
+        };
+        let container = match follow(&app.value, &app.position.members).as_deref() {
+            Some(Value::List { vals, .. }) => !vals.is_empty(),
+            Some(Value::Record { val: rec, .. }) => !rec.is_empty(),
+            _ => false,
+        };
+        if !(single && container) {
+            break;
+        }
+        go_deeper_in_data(app);
+    }
+
+    let n = app.position.members.len();
+    if start > 0 && n > start {
+        let skipped = app.position.members[start - 1..n - 1]
+            .iter()
+            .map(|m| match m {
+                PathMember::Int { val, .. } => val.to_string(),
+                PathMember::String { val, .. } => val.clone(),
+            })
+            .collect::<Vec<String>>()
+            .join(".");
+        app.status_message = Some(StatusMessage::Info(format!("skipped {}", skipped)));
+    }
 }
 
 /// pop one level of depth from the data
@@ -140,19 +246,222 @@ pub(super) fn go_deeper_in_data(app: &mut App) {
 /// > :bulb: **Note**  
 /// > - the state is always marked as *not at the bottom*
 /// > - the state *cell path* can have it's last member popped if possible
+/// > - the view is scrolled as it was when it was left, see [`App::restore_offset`]
 pub(super) fn go_back_in_data(app: &mut App) {
+    if app.column.take().is_some() {
+        return;
+    }
+
     if !app.is_at_bottom() & (app.position.members.len() > 1) {
-        app.position.members.pop();
+        app.remember_offset();
+        // NOTE: a cell entered from its column goes back to the column of its row, whether the
+        // cell is a simple value or the cursor is inside it
+        if !back_to_column(app) {
+            app.position.members.pop();
+            back_to_column(app);
+        }
+        app.restore_offset();
     }
     app.mode = Mode::Normal;
 }
 
+/// pop the last *cell path* member if it is a column entered with [`go_deeper_in_data`]
+///
+/// the column cursor is put back on that column and `true` is returned, otherwise nothing
+/// happens and `false` is returned.
+fn back_to_column(app: &mut App) -> bool {
+    let depth = app.position.members.len();
+    app.column_depths.retain(|&d| d <= depth);
+    if app.column_depths.last() != Some(&depth) {
+        return false;
+    }
+
+    app.column_depths.pop();
+    if let Some(PathMember::String { val, .. }) = app.position.members.pop() {
+        app.column = view_columns(app).iter().position(|c| c == &val);
+    }
+    true
+}
+
+/// move the column cursor of a table to the *next* or the previous column
+///
+/// the cursor wraps around the columns, with the whole row being selected in between, i.e.
+/// when no column is under the cursor.
+///
+/// > :bulb: **Note**  
+/// > nothing happens outside of a table, e.g. in a list of simple values or a record
+pub(super) fn go_to_next_column(app: &mut App, next: bool) {
+    if app.is_at_bottom() {
+        return;
+    }
+
+    let nb_columns = view_columns(app).len();
+    if nb_columns == 0 {
+        return;
+    }
+
+    app.column = match (app.column, next) {
+        (None, true) => Some(0),
+        (None, false) => Some(nb_columns - 1),
+        (Some(c), true) if c + 1 < nb_columns => Some(c + 1),
+        (Some(c), false) if c > 0 => Some(c - 1),
+        (Some(_), _) => None,
+    };
+}
+
+/// the columns of the current view if it is a table, nothing otherwise
+pub(crate) fn view_columns(app: &App) -> Vec<String> {
+    let n = app.position.members.len();
+    match follow(&app.value, &app.position.members[..n.saturating_sub(1)]).as_deref() {
+        Some(view @ Value::List { vals, .. }) if is_ragged_table(view) => {
+            table_columns(vals.iter().filter_map(|v| v.as_record().ok()))
+        }
+        _ => vec![],
+    }
+}
+
+/// jump directly to a row of the current level
+///
+/// the *target* is interpreted depending on the current level
+/// - in a list, it has to be a valid index
+/// - in a record, it is a column name, the first column starting with *target* being used when
+///   there is no exact match
+///
+/// > :bulb: **Note**  
+/// > on success, the view will be centered around the new selection on the next frame
+pub(super) fn go_to(app: &mut App, target: &str) -> Result<(), String> {
+    let target = target.trim();
+
+    let mut view_path = app.position.members.clone();
+    if view_path.pop().is_none() {
+        return Err(format!(
+            "cannot jump anywhere in a {}",
+            app.value.get_type()
+        ));
+    }
+
+    let view = follow(&app.value, &view_path).unwrap_or_else(|| {
+        panic!(
+            "unexpected error when following {:?} in {}",
+            view_path,
+            app.value.into_string(" ", &nu_protocol::Config::default())
+        )
+    });
+
+    // NOTE: app.position.members cannot be empty thanks to the guard above
+    match (&*view, app.position.members.last_mut().unwrap()) {
+        (Value::List { vals, .. }, PathMember::Int { val, .. }) => {
+            let index = target
+                .parse::<usize>()
+                .map_err(|_| format!("expected an index, found `{}`", target))?;
+            if index >= vals.len() {
+                return Err(format!(
+                    "index {} is out of range, the list has {} items",
+                    index,
+                    vals.len()
+                ));
+            }
+
+            *val = index;
+        }
+        (Value::Record { val: rec, .. }, member) => {
+            let index = rec
+                .cols
+                .iter()
+                .position(|col| col == target)
+                .or_else(|| rec.cols.iter().position(|col| col.starts_with(target)))
+                .ok_or_else(|| format!("no field matches `{}`", target))?;
+
+            *member = column_member(rec, index);
+        }
+        (x, _) => return Err(format!("cannot jump anywhere in a {}", x.get_type())),
+    }
+
+    app.recenter = true;
+
+    Ok(())
+}
+
+/// jump to the same place in the next or previous sibling of the nearest ancestor that is an
+/// item of a list, e.g. from `users.3.address.city` to `users.4.address.city`
+///
+/// the index of the ancestor wraps around the list, as in [`go_up_or_down_in_data`].
+///
+/// > :bulb: **Note**  
+/// > when the rest of the *cell path* does not exist in the sibling, the cursor stops at the
+/// > deepest part of it that does and a note is shown in the status bar
+///
+/// > see the tests for detailed examples
+pub(super) fn go_to_ancestor_sibling(app: &mut App, direction: Direction) -> Result<(), String> {
+    let members = &app.position.members;
+    let n = members.len();
+
+    // NOTE: the last member is the cursor itself, moving it is going up or down
+    let (i, index, len) = (0..n.saturating_sub(1))
+        .rev()
+        .find_map(
+            |i| match (&members[i], follow(&app.value, &members[..i]).as_deref()) {
+                (PathMember::Int { val, .. }, Some(Value::List { vals, .. })) => {
+                    Some((i, *val, vals.len()))
+                }
+                _ => None,
+            },
+        )
+        .ok_or("no list above the cursor")?;
+
+    let index = match direction {
+        Direction::Down => (index + 1) % len,
+        Direction::Up => (index + len - 1) % len,
+    };
+
+    let mut members = members.clone();
+    members[i] = PathMember::Int {
+        val: index,
+        span: Span::unknown(),
+        optional: false,
+    };
+    let depth = (i + 1..=n)
+        .rev()
+        .find(|&k| follow(&app.value, &members[..k]).is_some())
+        .unwrap_or(i + 1);
+    if depth < n {
+        app.status_message = Some(StatusMessage::Info(format!(
+            "`{}` does not exist in item {}",
+            members[depth..]
+                .iter()
+                .map(|m| match m {
+                    PathMember::Int { val, .. } => val.to_string(),
+                    PathMember::String { val, .. } => val.clone(),
+                })
+                .collect::<Vec<String>>()
+                .join("."),
+            index
+        )));
+        members.truncate(depth);
+        app.mode = Mode::Normal;
+        app.column = None;
+        app.column_depths.retain(|&d| d <= depth);
+    }
+
+    app.position.members = members;
+    app.recenter = true;
+
+    Ok(())
+}
+
 // TODO: add proper assert error messages
 #[cfg(test)]
 mod tests {
-    use super::{go_back_in_data, go_deeper_in_data, go_up_or_down_in_data, Direction};
-    use crate::app::App;
-    use nu_protocol::{ast::PathMember, record, Span, Value};
+    use super::{
+        auto_descend, go_back_in_data, go_deeper_in_data, go_to, go_to_ancestor_sibling,
+        go_to_next_column, go_up_or_down_in_data, Direction,
+    };
+    use crate::app::{App, StatusMessage};
+    use nu_protocol::{
+        ast::{CellPath, PathMember, RangeInclusion},
+        engine::Closure,
+        record, Range, Record, Span, Value,
+    };
 
     fn test_string_pathmember(val: impl Into<String>) -> PathMember {
         PathMember::String {
@@ -218,6 +527,93 @@ mod tests {
         }
     }
 
+    #[test]
+    fn go_up_and_down_in_record_with_duplicate_columns() {
+        let mut rec = Record::new();
+        for (col, val) in [("x", 1), ("x", 2), ("x", 3), ("y", 4)] {
+            rec.push(col, Value::test_int(val));
+        }
+        let mut app = App::from_value(Value::test_record(rec));
+        assert_eq!(app.position.members, vec![test_int_pathmember(0)]);
+
+        let sequence = vec![
+            (Direction::Down, test_int_pathmember(1)),
+            (Direction::Down, test_int_pathmember(2)),
+            (Direction::Down, test_string_pathmember("y")),
+            (Direction::Down, test_int_pathmember(0)),
+            (Direction::Up, test_string_pathmember("y")),
+            (Direction::Up, test_int_pathmember(2)),
+        ];
+        for (direction, member) in sequence {
+            go_up_or_down_in_data(&mut app, direction);
+            assert_eq!(app.position.members, vec![member]);
+        }
+        assert_eq!(app.cell_under(), Value::test_int(3));
+
+        go_to(&mut app, "y").unwrap();
+        assert_eq!(app.position.members, vec![test_string_pathmember("y")]);
+        go_to(&mut app, "x").unwrap();
+        assert_eq!(app.position.members, vec![test_int_pathmember(0)]);
+        assert_eq!(app.cell_under(), Value::test_int(1));
+    }
+
+    #[test]
+    fn go_to_index_in_list() {
+        let value = Value::test_list(vec![
+            Value::test_nothing(),
+            Value::test_nothing(),
+            Value::test_nothing(),
+        ]);
+        let mut app = App::from_value(value);
+
+        assert_eq!(go_to(&mut app, "2"), Ok(()));
+        assert_eq!(app.position.members, vec![test_int_pathmember(2)]);
+        assert!(app.recenter);
+
+        assert_eq!(go_to(&mut app, " 1 "), Ok(()));
+        assert_eq!(app.position.members, vec![test_int_pathmember(1)]);
+
+        assert_eq!(
+            go_to(&mut app, "3"),
+            Err("index 3 is out of range, the list has 3 items".into())
+        );
+        assert_eq!(
+            go_to(&mut app, "foo"),
+            Err("expected an index, found `foo`".into())
+        );
+        assert_eq!(app.position.members, vec![test_int_pathmember(1)]);
+    }
+
+    #[test]
+    fn go_to_key_in_record() {
+        let value = Value::test_record(record! {
+            "foo" => Value::test_nothing(),
+            "foobar" => Value::test_nothing(),
+            "bar" => Value::test_nothing(),
+        });
+        let mut app = App::from_value(value);
+
+        let cases = vec![
+            ("bar", "bar"),
+            ("foo", "foo"),
+            ("foob", "foobar"),
+            ("b", "bar"),
+            ("f", "foo"),
+        ];
+        for (target, expected) in cases {
+            assert_eq!(go_to(&mut app, target), Ok(()));
+            assert_eq!(
+                app.position.members,
+                vec![test_string_pathmember(expected)],
+                "expected `{}` to jump to `{}`",
+                target,
+                expected
+            );
+        }
+
+        assert_eq!(go_to(&mut app, "baz"), Err("no field matches `baz`".into()));
+    }
+
     #[test]
     fn go_deeper() {
         let value = Value::test_list(vec![Value::test_record(record! {
@@ -237,6 +633,47 @@ mod tests {
         assert_eq!(app.position.members, expected);
     }
 
+    #[test]
+    fn hit_bottom_on_missing_cell() {
+        let value = Value::test_list(vec![
+            Value::test_record(record! {
+                "a" => Value::test_int(1),
+            }),
+            Value::test_record(record! {
+                "a" => Value::test_int(2),
+                "b" => Value::test_int(3),
+            }),
+        ]);
+        let mut app = App::from_value(value);
+        app.position.members = vec![test_int_pathmember(0), test_string_pathmember("b")];
+
+        go_deeper_in_data(&mut app);
+        assert!(app.is_at_bottom());
+        assert_eq!(
+            app.position.members,
+            vec![
+                test_int_pathmember(0),
+                PathMember::String {
+                    val: "b".into(),
+                    span: Span::test_data(),
+                    optional: true,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected error when following")]
+    fn panic_on_missing_value_outside_of_a_table() {
+        let value = Value::test_record(record! {
+            "a" => Value::test_record(record! { "b" => Value::test_int(1) }),
+        });
+        let mut app = App::from_value(value);
+        app.position.members = vec![test_string_pathmember("a"), test_string_pathmember("c")];
+
+        go_deeper_in_data(&mut app);
+    }
+
     #[test]
     fn hit_bottom() {
         let value = Value::test_nothing();
@@ -277,4 +714,342 @@ mod tests {
         go_back_in_data(&mut app);
         assert_eq!(app.position.members, expected);
     }
+
+    #[test]
+    fn go_into_ranges() {
+        let range = |to: Value| {
+            Value::test_range(Range {
+                from: Value::test_int(1),
+                incr: Value::test_int(1),
+                to,
+                inclusion: RangeInclusion::Inclusive,
+            })
+        };
+        let value = Value::test_list(vec![
+            range(Value::test_int(3)),
+            range(Value::test_nothing()),
+        ]);
+        let mut app = App::from_value(value);
+
+        go_deeper_in_data(&mut app);
+        assert_eq!(
+            app.position.members,
+            vec![test_int_pathmember(0), test_int_pathmember(0)]
+        );
+
+        go_up_or_down_in_data(&mut app, Direction::Up);
+        assert_eq!(
+            app.position.members,
+            vec![test_int_pathmember(0), test_int_pathmember(2)]
+        );
+
+        go_deeper_in_data(&mut app);
+        assert!(app.is_at_bottom());
+
+        go_back_in_data(&mut app);
+        go_back_in_data(&mut app);
+        go_up_or_down_in_data(&mut app, Direction::Down);
+
+        // NOTE: an unbounded range can't be explored
+        go_deeper_in_data(&mut app);
+        assert!(!app.is_at_bottom());
+        assert_eq!(app.position.members, vec![test_int_pathmember(1)]);
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Error(
+                "cannot explore an unbounded range".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn closures_and_cell_paths_are_leaves() {
+        let value = Value::test_list(vec![
+            Value::closure(
+                Closure {
+                    block_id: 0,
+                    captures: vec![],
+                },
+                Span::test_data(),
+            ),
+            Value::cell_path(
+                CellPath {
+                    members: vec![test_string_pathmember("a")],
+                },
+                Span::test_data(),
+            ),
+        ]);
+        let mut app = App::from_value(value);
+
+        for _ in 0..2 {
+            go_deeper_in_data(&mut app);
+            assert!(app.is_at_bottom());
+            go_back_in_data(&mut app);
+            go_up_or_down_in_data(&mut app, Direction::Down);
+        }
+    }
+
+    #[test]
+    fn go_into_table_cells() {
+        let value = Value::test_list(vec![
+            Value::test_record(record! {
+                "a" => Value::test_int(1),
+                "b" => Value::test_list(vec![Value::test_nothing()]),
+            }),
+            Value::test_record(record! {
+                "a" => Value::test_int(2),
+            }),
+        ]);
+        let mut app = App::from_value(value);
+
+        let sequence = vec![
+            (true, Some(0)),
+            (true, Some(1)),
+            (true, None),
+            (false, Some(1)),
+        ];
+        for (next, expected) in sequence {
+            go_to_next_column(&mut app, next);
+            assert_eq!(app.column, expected, "moving next: {}", next);
+        }
+
+        go_deeper_in_data(&mut app);
+        assert_eq!(app.column, None);
+        assert_eq!(
+            app.position.members,
+            vec![
+                test_int_pathmember(0),
+                test_string_pathmember("b"),
+                test_int_pathmember(0)
+            ]
+        );
+
+        // going back from the cell goes back to its column, then to the whole row
+        go_back_in_data(&mut app);
+        assert_eq!(app.column, Some(1));
+        assert_eq!(app.position.members, vec![test_int_pathmember(0)]);
+
+        go_back_in_data(&mut app);
+        assert_eq!(app.column, None);
+        assert_eq!(app.position.members, vec![test_int_pathmember(0)]);
+
+        // a missing cell is the bottom of the data
+        go_up_or_down_in_data(&mut app, Direction::Down);
+        go_to_next_column(&mut app, false);
+        go_deeper_in_data(&mut app);
+        assert!(app.is_at_bottom());
+        assert_eq!(
+            app.position.members,
+            vec![
+                test_int_pathmember(1),
+                PathMember::String {
+                    val: "b".into(),
+                    span: Span::test_data(),
+                    optional: true,
+                }
+            ]
+        );
+
+        go_back_in_data(&mut app);
+        assert!(!app.is_at_bottom());
+        go_back_in_data(&mut app);
+        assert_eq!(app.column, Some(1));
+        assert_eq!(app.position.members, vec![test_int_pathmember(1)]);
+    }
+
+    #[test]
+    fn no_columns_outside_of_tables() {
+        let value = Value::test_record(record! {
+            "a" => Value::test_list(vec![Value::test_int(1)]),
+        });
+        let mut app = App::from_value(value);
+
+        go_to_next_column(&mut app, true);
+        assert_eq!(app.column, None);
+
+        go_deeper_in_data(&mut app);
+        go_to_next_column(&mut app, true);
+        assert_eq!(app.column, None);
+    }
+
+    #[test]
+    fn go_to_the_sibling_of_an_ancestor() {
+        let user = |city: Option<&str>| match city {
+            Some(city) => Value::test_record(record! {
+                "address" => Value::test_record(record! {
+                    "city" => Value::test_string(city),
+                }),
+            }),
+            None => Value::test_record(record! {
+                "address" => Value::test_record(record! {}),
+            }),
+        };
+        let value = Value::test_record(record! {
+            "users" => Value::test_list(vec![
+                user(Some("a")),
+                user(Some("b")),
+                user(None),
+            ]),
+        });
+        let path = |index: usize, suffix: &[&str]| {
+            let mut members = vec![test_string_pathmember("users"), test_int_pathmember(index)];
+            members.extend(suffix.iter().map(|&s| test_string_pathmember(s)));
+            members
+        };
+
+        let mut app = App::from_value(value);
+        app.position.members = path(0, &["address", "city"]);
+        app.hit_bottom();
+
+        go_to_ancestor_sibling(&mut app, Direction::Down).unwrap();
+        assert_eq!(app.position.members, path(1, &["address", "city"]));
+        assert!(app.is_at_bottom());
+        assert_eq!(app.status_message, None);
+
+        // NOTE: the third user has no city, the cursor stops at its address
+        go_to_ancestor_sibling(&mut app, Direction::Down).unwrap();
+        assert_eq!(app.position.members, path(2, &["address"]));
+        assert!(!app.is_at_bottom());
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info(
+                "`city` does not exist in item 2".into()
+            ))
+        );
+
+        // NOTE: the end of the list wraps around to the first user
+        go_to_ancestor_sibling(&mut app, Direction::Down).unwrap();
+        assert_eq!(app.position.members, path(0, &["address"]));
+        go_to_ancestor_sibling(&mut app, Direction::Up).unwrap();
+        assert_eq!(app.position.members, path(2, &["address"]));
+
+        // NOTE: the cursor itself is not an ancestor
+        app.position.members = path(1, &[]);
+        assert_eq!(
+            go_to_ancestor_sibling(&mut app, Direction::Down),
+            Err("no list above the cursor".into())
+        );
+        assert_eq!(app.position.members, path(1, &[]));
+
+        let mut app = App::from_value(Value::test_record(record! {
+            "a" => Value::test_record(record! { "b" => Value::test_int(1) }),
+        }));
+        app.position.members = vec![test_string_pathmember("a"), test_string_pathmember("b")];
+        assert_eq!(
+            go_to_ancestor_sibling(&mut app, Direction::Up),
+            Err("no list above the cursor".into())
+        );
+    }
+
+    #[test]
+    fn auto_descend_through_single_children() {
+        let table = Value::test_list(vec![
+            Value::test_record(record! { "x" => Value::test_int(1) }),
+            Value::test_record(record! { "x" => Value::test_int(2) }),
+        ]);
+        let mut value = table;
+        for key in ["e", "d", "c", "b", "a"] {
+            let mut rec = Record::new();
+            rec.push(key, value);
+            value = Value::test_record(rec);
+        }
+        let path = |keys: &[&str], index: Option<usize>| {
+            let mut members: Vec<PathMember> =
+                keys.iter().map(|&k| test_string_pathmember(k)).collect();
+            members.extend(index.map(test_int_pathmember));
+            members
+        };
+
+        let mut app = App::from_value(value);
+        assert_eq!(app.position.members, path(&["a"], None));
+
+        auto_descend(&mut app);
+        assert_eq!(
+            app.position.members,
+            path(&["a", "b", "c", "d", "e"], Some(0))
+        );
+        assert!(!app.is_at_bottom());
+        assert_eq!(
+            app.status_message,
+            Some(StatusMessage::Info("skipped a.b.c.d.e".into()))
+        );
+
+        // NOTE: the rows of the table have a single column but there are two of them
+        app.status_message = None;
+        auto_descend(&mut app);
+        assert_eq!(
+            app.position.members,
+            path(&["a", "b", "c", "d", "e"], Some(0))
+        );
+        assert_eq!(app.status_message, None);
+
+        // NOTE: going back climbs one level at a time
+        go_back_in_data(&mut app);
+        assert_eq!(app.position.members, path(&["a", "b", "c", "d", "e"], None));
+        go_back_in_data(&mut app);
+        assert_eq!(app.position.members, path(&["a", "b", "c", "d"], None));
+
+        // NOTE: the descent stops before a single simple value
+        let mut app = App::from_value(Value::test_record(record! {
+            "a" => Value::test_record(record! { "b" => Value::test_int(1) }),
+        }));
+        auto_descend(&mut app);
+        assert_eq!(app.position.members, path(&["a", "b"], None));
+        assert!(!app.is_at_bottom());
+    }
+
+    #[test]
+    fn restore_the_offset_of_each_level() {
+        let row = Value::test_record(record! {
+            "a" => Value::test_list((0..10).map(Value::test_int).collect()),
+        });
+        let mut app = App::from_value(Value::test_list(vec![row; 100]));
+        app.position.members = vec![PathMember::Int {
+            val: 50,
+            span: Span::test_data(),
+            optional: false,
+        }];
+        app.offset = 40;
+
+        go_deeper_in_data(&mut app);
+        assert_eq!(app.offset, 0, "a new level starts at the top");
+        go_deeper_in_data(&mut app);
+        app.offset = 5;
+
+        go_back_in_data(&mut app);
+        go_back_in_data(&mut app);
+        assert_eq!(app.offset, 40, "the list is scrolled as it was left");
+
+        go_deeper_in_data(&mut app);
+        go_deeper_in_data(&mut app);
+        assert_eq!(app.offset, 5, "the inner list too");
+
+        // NOTE: an edit forgets the offsets of what it has changed
+        go_back_in_data(&mut app);
+        let list = Value::test_list(vec![Value::test_int(0)]);
+        app.apply_mutation(
+            &list,
+            &CellPath {
+                members: app.position.members.clone(),
+            },
+        );
+        go_deeper_in_data(&mut app);
+        assert_eq!(app.offset, 0);
+
+        // NOTE: only the most recently left views are remembered
+        app.offsets.clear();
+        for i in 0..100 {
+            app.position.members = vec![PathMember::Int {
+                val: i,
+                span: Span::test_data(),
+                optional: false,
+            }];
+            go_deeper_in_data(&mut app);
+            app.offset = i + 1;
+            go_back_in_data(&mut app);
+        }
+        assert!(app.offsets.len() <= 64);
+        assert_eq!(app.offsets.last().map(|(_, offset, _)| *offset), Some(100));
+        assert!(!app.offsets.iter().any(|(_, offset, _)| *offset == 1));
+    }
 }
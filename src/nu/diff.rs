@@ -0,0 +1,235 @@
+//! structural diff between two Nushell values
+use nu_protocol::{ast::PathMember, Span, Value};
+
+use super::search::snippet;
+use crate::config::DiffConfig;
+
+/// the way an element differs between the two values of a [`diff`]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Change {
+    /// the element only exists in the second value
+    Added(String),
+    /// the element only exists in the first value
+    Removed(String),
+    /// the element exists in both values but is different, from the first to the second
+    Changed(String, String),
+}
+
+/// a single difference of a [`diff`]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Difference {
+    /// the cell path to the element, relative to the two values
+    pub path: Vec<PathMember>,
+    pub change: Change,
+}
+
+/// compare two values recursively, from *base* to *other*
+///
+/// records are compared key by key and lists index by index. the comparison stops
+/// - at [`DiffConfig::max_depth`] levels deep into the data, where the elements are compared as
+///   a whole
+/// - as soon as [`DiffConfig::max_differences`] differences have been found
+///
+/// > see the tests for detailed examples
+pub(crate) fn diff(base: &Value, other: &Value, config: &DiffConfig) -> Vec<Difference> {
+    let mut differences = vec![];
+    walk(base, other, config, &mut vec![], &mut differences);
+    differences
+}
+
+fn walk(
+    base: &Value,
+    other: &Value,
+    config: &DiffConfig,
+    path: &mut Vec<PathMember>,
+    differences: &mut Vec<Difference>,
+) {
+    if differences.len() >= config.max_differences {
+        return;
+    }
+
+    if path.len() >= config.max_depth {
+        if base != other {
+            push(
+                differences,
+                config,
+                path,
+                Change::Changed(snippet(base), snippet(other)),
+            );
+        }
+        return;
+    }
+
+    match (base, other) {
+        (Value::Record { val: a, .. }, Value::Record { val: b, .. }) => {
+            for (col, val) in a.iter() {
+                path.push(PathMember::String {
+                    val: col.clone(),
+                    span: Span::unknown(),
+                    optional: false,
+                });
+                match b.get(col) {
+                    Some(other) => walk(val, other, config, path, differences),
+                    None => {
+                        push(differences, config, path, Change::Removed(snippet(val)));
+                    }
+                }
+                path.pop();
+            }
+            for (col, val) in b.iter().filter(|(col, _)| a.get(col).is_none()) {
+                path.push(PathMember::String {
+                    val: col.clone(),
+                    span: Span::unknown(),
+                    optional: false,
+                });
+                push(differences, config, path, Change::Added(snippet(val)));
+                path.pop();
+            }
+        }
+        (Value::List { vals: a, .. }, Value::List { vals: b, .. }) => {
+            for i in 0..a.len().max(b.len()) {
+                path.push(PathMember::Int {
+                    val: i,
+                    span: Span::unknown(),
+                    optional: false,
+                });
+                match (a.get(i), b.get(i)) {
+                    (Some(x), Some(y)) => walk(x, y, config, path, differences),
+                    (Some(x), None) => {
+                        push(differences, config, path, Change::Removed(snippet(x)));
+                    }
+                    (None, Some(y)) => {
+                        push(differences, config, path, Change::Added(snippet(y)));
+                    }
+                    (None, None) => {}
+                }
+                path.pop();
+            }
+        }
+        (a, b) => {
+            if a != b {
+                push(
+                    differences,
+                    config,
+                    path,
+                    Change::Changed(snippet(a), snippet(b)),
+                );
+            }
+        }
+    }
+}
+
+fn push(
+    differences: &mut Vec<Difference>,
+    config: &DiffConfig,
+    path: &[PathMember],
+    change: Change,
+) {
+    if differences.len() < config.max_differences {
+        differences.push(Difference {
+            path: path.to_vec(),
+            change,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::{record, Value};
+
+    use super::{diff, Change, Difference};
+    use crate::{
+        config::{Config, DiffConfig},
+        nu::cell_path::{to_path_member_vec, PM},
+    };
+
+    fn difference(path: &[PM], change: Change) -> Difference {
+        Difference {
+            path: to_path_member_vec(path),
+            change,
+        }
+    }
+
+    #[test]
+    fn diff_values() {
+        let config = Config::default().diff;
+
+        let base = Value::test_record(record! {
+            "name" => Value::test_string("foo"),
+            "size" => Value::test_int(1),
+            "tags" => Value::test_list(vec![Value::test_string("a"), Value::test_string("b")]),
+            "old" => Value::test_bool(true),
+        });
+        let other = Value::test_record(record! {
+            "name" => Value::test_string("foo"),
+            "size" => Value::test_int(2),
+            "tags" => Value::test_list(vec![Value::test_string("a")]),
+            "new" => Value::test_nothing(),
+        });
+
+        assert_eq!(diff(&base, &base, &config), vec![]);
+        assert_eq!(
+            diff(&base, &other, &config),
+            vec![
+                difference(&[PM::S("size")], Change::Changed("1".into(), "2".into())),
+                difference(&[PM::S("tags"), PM::I(1)], Change::Removed("b".into())),
+                difference(&[PM::S("old")], Change::Removed("true".into())),
+                difference(&[PM::S("new")], Change::Added("".into())),
+            ]
+        );
+
+        // simple values and values of different types are compared as a whole
+        assert_eq!(
+            diff(&Value::test_int(1), &Value::test_string("1"), &config),
+            vec![difference(&[], Change::Changed("1".into(), "1".into()))]
+        );
+        assert_eq!(
+            diff(&Value::test_list(vec![]), &base, &config),
+            vec![difference(
+                &[],
+                Change::Changed("list of 0 items".into(), "record of 4 fields".into())
+            )]
+        );
+    }
+
+    #[test]
+    fn diff_with_limits() {
+        let base = Value::test_list(vec![
+            Value::test_record(record! { "a" => Value::test_int(1) }),
+            Value::test_int(2),
+            Value::test_int(3),
+        ]);
+        let other = Value::test_list(vec![
+            Value::test_record(record! { "a" => Value::test_int(0) }),
+            Value::test_int(0),
+            Value::test_int(0),
+        ]);
+
+        let config = DiffConfig {
+            max_depth: 1,
+            ..Config::default().diff
+        };
+        assert_eq!(
+            diff(&base, &other, &config)[0],
+            difference(
+                &[PM::I(0)],
+                Change::Changed("record of 1 fields".into(), "record of 1 fields".into())
+            )
+        );
+
+        let config = DiffConfig {
+            max_differences: 2,
+            ..Config::default().diff
+        };
+        assert_eq!(
+            diff(&base, &other, &config),
+            vec![
+                difference(
+                    &[PM::I(0), PM::S("a")],
+                    Change::Changed("1".into(), "0".into())
+                ),
+                difference(&[PM::I(1)], Change::Changed("2".into(), "0".into())),
+            ]
+        );
+    }
+}
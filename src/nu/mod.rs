@@ -1,4 +1,10 @@
 #[cfg(test)]
 pub(super) mod cell_path;
+pub(super) mod diff;
+pub(super) mod schema;
+pub(super) mod search;
+pub(super) mod source;
+pub(super) mod stats;
 pub(super) mod strings;
+pub(super) mod validate;
 pub(super) mod value;
@@ -0,0 +1,223 @@
+//! the *schema* of a value, i.e. its structure without the data
+use nu_protocol::{Record, Span, Type, Value};
+
+use crate::config::SchemaConfig;
+
+/// compute the type of a value, e.g. `list<record<name: string, size: filesize>>`
+///
+/// the type of the items of a list is the unification of the types of at most
+/// [`SchemaConfig::max_samples`] of its first items, see [`unify`].
+///
+/// > :bulb: **Note**  
+/// > the items of an empty list are of type `nothing`
+///
+/// > see the tests for detailed examples
+pub(crate) fn infer(value: &Value, config: &SchemaConfig) -> Type {
+    match value {
+        Value::Record { val, .. } => Type::Record(
+            val.iter()
+                .map(|(col, val)| (col.clone(), infer(val, config)))
+                .collect(),
+        ),
+        Value::List { vals, .. } => Type::List(Box::new(
+            vals.iter()
+                .take(config.max_samples)
+                .map(|val| infer(val, config))
+                .reduce(unify)
+                .unwrap_or(Type::Nothing),
+        )),
+        x => x.get_type(),
+    }
+}
+
+/// compute the most precise type that both types fit in
+///
+/// - `nothing` fits in any type, e.g. a field that is sometimes `null`
+/// - `int` and `float` both fit in `number`
+/// - records are unified field by field, the fields missing in one of them being kept
+/// - lists are unified item-wise
+/// - anything else only fits in `any`
+///
+/// > see the tests for detailed examples
+pub(crate) fn unify(a: Type, b: Type) -> Type {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (Type::Nothing, x) | (x, Type::Nothing) => x,
+        (Type::Int | Type::Float | Type::Number, Type::Int | Type::Float | Type::Number) => {
+            Type::Number
+        }
+        (Type::Record(a), Type::Record(mut b)) => {
+            let mut fields = vec![];
+            for (col, ty) in a {
+                match b.iter().position(|(c, _)| c == &col) {
+                    Some(i) => {
+                        let (_, other) = b.remove(i);
+                        fields.push((col, unify(ty, other)));
+                    }
+                    None => fields.push((col, ty)),
+                }
+            }
+            fields.extend(b);
+            Type::Record(fields)
+        }
+        (Type::List(a), Type::List(b)) => Type::List(Box::new(unify(*a, *b))),
+        _ => Type::Any,
+    }
+}
+
+/// turn a type into a value that can be explored as the data would be
+///
+/// - a record is a record of the schemas of its fields
+/// - a list is a list with a single item, the schema of its items
+/// - any other type is its name, e.g. `"filesize"`
+pub(crate) fn to_value(ty: &Type) -> Value {
+    match ty {
+        Type::Record(fields) => {
+            let mut record = Record::new();
+            for (col, ty) in fields {
+                record.push(col, to_value(ty));
+            }
+            Value::record(record, Span::unknown())
+        }
+        Type::List(ty) => Value::list(vec![to_value(ty)], Span::unknown()),
+        ty => Value::string(ty.to_string(), Span::unknown()),
+    }
+}
+
+/// compute the schema of a value, to be explored instead of the data, see [`infer`] and
+/// [`to_value`]
+pub(crate) fn schema(value: &Value, config: &SchemaConfig) -> Value {
+    to_value(&infer(value, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::{record, Type, Value};
+
+    use super::{infer, schema, unify};
+    use crate::config::{Config, SchemaConfig};
+
+    #[test]
+    fn unify_types() {
+        let record = |fields: &[(&str, Type)]| {
+            Type::Record(
+                fields
+                    .iter()
+                    .map(|(col, ty)| (col.to_string(), ty.clone()))
+                    .collect(),
+            )
+        };
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (Type::Int, Type::Int, Type::Int),
+            (Type::Int, Type::Float, Type::Number),
+            (Type::Number, Type::Int, Type::Number),
+            (Type::Nothing, Type::String, Type::String),
+            (Type::String, Type::Nothing, Type::String),
+            (Type::String, Type::Int, Type::Any),
+            (Type::List(Box::new(Type::Int)), Type::Int, Type::Any),
+            (
+                Type::List(Box::new(Type::Int)),
+                Type::List(Box::new(Type::Float)),
+                Type::List(Box::new(Type::Number)),
+            ),
+            (
+                record(&[("a", Type::Int), ("b", Type::String)]),
+                record(&[("c", Type::Bool), ("a", Type::Float)]),
+                record(&[("a", Type::Number), ("b", Type::String), ("c", Type::Bool)]),
+            ),
+        ];
+
+        for (a, b, expected) in cases {
+            assert_eq!(
+                unify(a.clone(), b.clone()),
+                expected,
+                "unifying {} and {}",
+                a,
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn infer_heterogeneous_lists() {
+        let config = Config::default().schema;
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (Value::test_list(vec![]), "list<nothing>"),
+            (Value::test_list(vec![Value::test_int(1), Value::test_int(2)]), "list<int>"),
+            (Value::test_list(vec![Value::test_int(1), Value::test_float(2.0)]), "list<number>"),
+            (Value::test_list(vec![Value::test_int(1), Value::test_string("2")]), "list<any>"),
+            (Value::test_list(vec![Value::test_nothing(), Value::test_string("2")]), "list<string>"),
+            (
+                Value::test_list(vec![
+                    Value::test_record(record! {
+                        "name" => Value::test_string("foo"),
+                        "size" => Value::test_filesize(1),
+                    }),
+                    Value::test_record(record! {
+                        "name" => Value::test_string("bar"),
+                        "tags" => Value::test_list(vec![Value::test_string("a")]),
+                    }),
+                    Value::test_record(record! {
+                        "name" => Value::test_nothing(),
+                        "tags" => Value::test_list(vec![]),
+                    }),
+                ]),
+                "list<record<name: string, size: filesize, tags: list<string>>>",
+            ),
+            (
+                Value::test_list(vec![
+                    Value::test_list(vec![Value::test_int(1)]),
+                    Value::test_list(vec![Value::test_record(record! {})]),
+                ]),
+                "list<list<any>>",
+            ),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(infer(&value, &config).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn infer_with_samples() {
+        let value = Value::test_list(vec![
+            Value::test_int(1),
+            Value::test_int(2),
+            Value::test_string("3"),
+        ]);
+
+        let config = SchemaConfig { max_samples: 2 };
+        assert_eq!(infer(&value, &config).to_string(), "list<int>");
+        let config = SchemaConfig { max_samples: 3 };
+        assert_eq!(infer(&value, &config).to_string(), "list<any>");
+    }
+
+    #[test]
+    fn schema_as_value() {
+        let value = Value::test_record(record! {
+            "name" => Value::test_string("foo"),
+            "files" => Value::test_list(vec![
+                Value::test_record(record! {
+                    "size" => Value::test_filesize(1),
+                }),
+            ]),
+            "tags" => Value::test_list(vec![Value::test_string("a")]),
+        });
+
+        let expected = Value::test_record(record! {
+            "name" => Value::test_string("string"),
+            "files" => Value::test_list(vec![
+                Value::test_record(record! {
+                    "size" => Value::test_string("filesize"),
+                }),
+            ]),
+            "tags" => Value::test_list(vec![Value::test_string("string")]),
+        });
+
+        assert_eq!(schema(&value, &Config::default().schema), expected);
+    }
+}
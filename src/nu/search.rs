@@ -0,0 +1,221 @@
+//! deep search inside a Nushell value
+use nu_protocol::{ast::PathMember, Span, Value};
+
+use crate::{ansi, config::SearchConfig, nu::value::column_member};
+
+/// the maximum number of characters in the snippet of a match
+const SNIPPET_LENGTH: usize = 40;
+
+/// a single result of a [`search`]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Match {
+    /// the full cell path to the matching element
+    pub path: Vec<PathMember>,
+    /// a short representation of the matching element
+    pub snippet: String,
+}
+
+/// search recursively for keys or simple values that contain the *query*
+///
+/// the search is case-insensitive and walks the data depth-first, in order, and stops
+/// - at [`SearchConfig::max_depth`] levels deep into the data
+/// - as soon as [`SearchConfig::max_matches`] matches have been found
+///
+/// > :bulb: **Note**  
+/// > binary values larger than [`SearchConfig::max_binary_size`] bytes are never matched
+///
+/// > see the tests for detailed examples
+pub(crate) fn search(value: &Value, query: &str, config: &SearchConfig) -> Vec<Match> {
+    let mut matches = vec![];
+    walk(
+        value,
+        &query.to_lowercase(),
+        config,
+        &mut vec![],
+        &mut matches,
+    );
+    matches
+}
+
+fn walk(
+    value: &Value,
+    query: &str,
+    config: &SearchConfig,
+    path: &mut Vec<PathMember>,
+    matches: &mut Vec<Match>,
+) {
+    if path.len() >= config.max_depth {
+        return;
+    }
+
+    let children: Vec<(PathMember, bool, &Value)> = match value {
+        Value::List { vals, .. } => vals
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let member = PathMember::Int {
+                    val: i,
+                    span: Span::unknown(),
+                    optional: false,
+                };
+                (member, false, v)
+            })
+            .collect(),
+        Value::Record { val: rec, .. } => rec
+            .iter()
+            .enumerate()
+            .map(|(i, (k, v))| (column_member(rec, i), k.to_lowercase().contains(query), v))
+            .collect(),
+        _ => return,
+    };
+
+    for (member, key_matches, child) in children {
+        if matches.len() >= config.max_matches {
+            return;
+        }
+
+        path.push(member);
+        if key_matches || matches_simple_value(child, query, config) {
+            matches.push(Match {
+                path: path.clone(),
+                snippet: snippet(child),
+            });
+        }
+        walk(child, query, config, path, matches);
+        path.pop();
+    }
+}
+
+/// whether a simple value, i.e. not a list nor a record, contains the *query*
+fn matches_simple_value(value: &Value, query: &str, config: &SearchConfig) -> bool {
+    match value {
+        Value::List { .. } | Value::Record { .. } => false,
+        Value::Binary { val, .. } => {
+            val.len() <= config.max_binary_size
+                && String::from_utf8_lossy(val).to_lowercase().contains(query)
+        }
+        x => x
+            .into_string(" ", &nu_protocol::Config::default())
+            .to_lowercase()
+            .contains(query),
+    }
+}
+
+/// a short representation of a value, to be shown next to the path of a match
+pub(super) fn snippet(value: &Value) -> String {
+    match value {
+        Value::List { vals, .. } => format!("list of {} items", vals.len()),
+        Value::Record { val: rec, .. } => format!("record of {} fields", rec.len()),
+        Value::Binary { val, .. } => format!("{} bytes of binary", val.len()),
+        x => {
+            let repr = ansi::strip(&x.into_string(" ", &nu_protocol::Config::default()));
+            if repr.chars().count() > SNIPPET_LENGTH {
+                format!(
+                    "{}...",
+                    repr.chars().take(SNIPPET_LENGTH - 3).collect::<String>()
+                )
+            } else {
+                repr
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::{ast::PathMember, record, Value};
+
+    use super::{search, Match};
+    use crate::{
+        config::{Config, SearchConfig},
+        nu::cell_path::{to_path_member_vec, PM},
+    };
+
+    fn paths(matches: Vec<Match>) -> Vec<String> {
+        matches
+            .iter()
+            .map(|m| {
+                m.path
+                    .iter()
+                    .map(|p| match p {
+                        PathMember::Int { val, .. } => val.to_string(),
+                        PathMember::String { val, .. } => val.clone(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(".")
+            })
+            .collect()
+    }
+
+    fn value() -> Value {
+        Value::test_record(record! {
+            "foo" => Value::test_list(vec![
+                Value::test_string("bar"),
+                Value::test_string("Foo Fighters"),
+            ]),
+            "bar" => Value::test_record(record! {
+                "baz" => Value::test_record(record! {
+                    "deep" => Value::test_string("some foo here"),
+                }),
+            }),
+            "bin" => Value::test_binary(b"foo".to_vec()),
+        })
+    }
+
+    #[test]
+    fn search_keys_and_values() {
+        let config = Config::default().search;
+
+        assert_eq!(
+            paths(search(&value(), "foo", &config)),
+            vec!["foo", "foo.1", "bar.baz.deep", "bin"]
+        );
+        assert_eq!(
+            paths(search(&value(), "BAR", &config)),
+            vec!["foo.0", "bar"]
+        );
+        assert_eq!(
+            paths(search(&value(), "nothing", &config)),
+            Vec::<String>::new()
+        );
+
+        let matches = search(&value(), "deep", &config);
+        assert_eq!(
+            matches,
+            vec![Match {
+                path: to_path_member_vec(&[PM::S("bar"), PM::S("baz"), PM::S("deep")]),
+                snippet: "some foo here".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn search_with_limits() {
+        let config = SearchConfig {
+            max_depth: 2,
+            ..Config::default().search
+        };
+        assert_eq!(
+            paths(search(&value(), "foo", &config)),
+            vec!["foo", "foo.1", "bin"]
+        );
+
+        let config = SearchConfig {
+            max_matches: 2,
+            ..Config::default().search
+        };
+        assert_eq!(
+            paths(search(&value(), "foo", &config)),
+            vec!["foo", "foo.1"]
+        );
+
+        let config = SearchConfig {
+            max_binary_size: 2,
+            ..Config::default().search
+        };
+        assert_eq!(
+            paths(search(&value(), "foo", &config)),
+            vec!["foo", "foo.1", "bar.baz.deep"]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! where a value comes from, i.e. the text its span points to in the source it was parsed from
+use nu_protocol::{Span, Value};
+
+/// the number of lines shown around the text of a span
+const CONTEXT_LINES: usize = 2;
+
+/// a line of the source around a span
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SourceLine {
+    /// the number of the line in the source, starting at 1
+    pub number: usize,
+    pub text: String,
+    /// whether the line is part of the span
+    pub in_span: bool,
+}
+
+/// the provenance of a value, see [`source_info`]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SourceInfo {
+    /// the type of the value, e.g. `record<a: int>`
+    pub ty: String,
+    /// the span of the value, if it is known
+    pub span: Option<Span>,
+    /// the lines of the source around the span, empty when the text is not available
+    pub lines: Vec<SourceLine>,
+    /// why the lines are not available, if they are not
+    pub note: Option<String>,
+}
+
+/// compute where a *value* comes from, with the lines of the *source* its span points to
+///
+/// - a value with an unknown span, e.g. a value built while exploring, has no source information
+/// - a span outside of the source can not be shown, e.g. because the source is not the text the
+///   value has been parsed from
+///
+/// > see the tests for detailed examples
+pub(crate) fn source_info(value: &Value, source: Option<&str>) -> SourceInfo {
+    let span = value.span();
+    let mut info = SourceInfo {
+        ty: value.get_type().to_string(),
+        span: None,
+        lines: vec![],
+        note: None,
+    };
+
+    if span == Span::unknown() {
+        info.note = Some("no source information".into());
+        return info;
+    }
+    info.span = Some(span);
+
+    let source = match source {
+        Some(source) => source,
+        None => {
+            info.note = Some("no source text, see `--source`".into());
+            return info;
+        }
+    };
+    if span.end > source.len() || span.start > span.end {
+        info.note = Some(format!(
+            "the span is outside of the source, which has {} bytes",
+            source.len()
+        ));
+        return info;
+    }
+
+    // NOTE: the offset of the start of each line, to find the lines of the span
+    let mut offset = 0;
+    let lines: Vec<(usize, &str)> = source
+        .split('\n')
+        .map(|line| {
+            let start = offset;
+            offset += line.len() + 1;
+            (start, line)
+        })
+        .collect();
+    let line_of = |pos: usize| {
+        lines
+            .iter()
+            .rposition(|(start, _)| *start <= pos)
+            .unwrap_or(0)
+    };
+    let first = line_of(span.start);
+    let last = line_of(span.end.saturating_sub(1).max(span.start));
+
+    info.lines = lines
+        .iter()
+        .enumerate()
+        .skip(first.saturating_sub(CONTEXT_LINES))
+        .take(last + CONTEXT_LINES + 1 - first.saturating_sub(CONTEXT_LINES))
+        .map(|(i, (_, text))| SourceLine {
+            number: i + 1,
+            text: text.to_string(),
+            in_span: first <= i && i <= last,
+        })
+        .collect();
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::{Span, Value};
+
+    use super::{source_info, SourceLine};
+
+    #[test]
+    fn unknown_sources() {
+        let info = source_info(&Value::test_int(1), Some("1"));
+        assert_eq!(info.ty, "int");
+        assert_eq!(info.span, None);
+        assert_eq!(info.note, Some("no source information".into()));
+
+        let value = Value::int(1, Span::new(0, 1));
+        let info = source_info(&value, None);
+        assert_eq!(info.span, Some(Span::new(0, 1)));
+        assert_eq!(info.note, Some("no source text, see `--source`".into()));
+
+        let info = source_info(&Value::int(1, Span::new(5, 10)), Some("1"));
+        assert_eq!(
+            info.note,
+            Some("the span is outside of the source, which has 1 bytes".into())
+        );
+        assert!(info.lines.is_empty());
+    }
+
+    #[test]
+    fn lines_around_the_span() {
+        let source = "a\nb\nc: 123\nd\ne\nf\ng";
+        let start = source.find("123").unwrap();
+        let value = Value::int(123, Span::new(start, start + 3));
+
+        let info = source_info(&value, Some(source));
+        assert_eq!(info.note, None);
+        let line = |number: usize, text: &str, in_span: bool| SourceLine {
+            number,
+            text: text.into(),
+            in_span,
+        };
+        assert_eq!(
+            info.lines,
+            vec![
+                line(1, "a", false),
+                line(2, "b", false),
+                line(3, "c: 123", true),
+                line(4, "d", false),
+                line(5, "e", false),
+            ]
+        );
+
+        // NOTE: a span over several lines shows all of them
+        let value = Value::string("b\nc", Span::new(2, 5));
+        let info = source_info(&value, Some(source));
+        assert_eq!(
+            info.lines.iter().map(|l| l.number).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            info.lines.iter().filter(|l| l.in_span).count(),
+            2,
+            "lines 2 and 3 are in the span"
+        );
+    }
+}
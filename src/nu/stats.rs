@@ -0,0 +1,218 @@
+//! quick statistics about a numeric column of a table
+use nu_protocol::{Span, Value};
+
+/// the kind of the numbers in a column, to show the statistics in their natural unit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Unit {
+    Int,
+    /// a mix of integers and floats is a column of floats
+    Float,
+    Filesize,
+    Duration,
+}
+
+/// the statistics of a numeric column, see [`column_stats`]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ColumnStats {
+    /// the name of the column
+    pub column: String,
+    pub unit: Unit,
+    /// the number of numeric cells
+    pub count: usize,
+    /// the number of cells that are missing, `null` or errors
+    pub skipped: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub sum: f64,
+}
+
+impl ColumnStats {
+    /// format a number of the column in its natural unit, e.g. `1.5 KiB` in a column of
+    /// filesizes
+    ///
+    /// > :bulb: **Note**  
+    /// > integers are only shown as such when *exact*, e.g. the sum or the minimum, and not the
+    /// > mean or the median
+    pub(crate) fn repr(&self, x: f64, exact: bool) -> String {
+        let value = match self.unit {
+            Unit::Int if exact => Value::int(x as i64, Span::unknown()),
+            Unit::Int | Unit::Float => Value::float(x, Span::unknown()),
+            Unit::Filesize => Value::filesize(x.round() as i64, Span::unknown()),
+            Unit::Duration => Value::duration(x.round() as i64, Span::unknown()),
+        };
+        value.into_string(" ", &nu_protocol::Config::default())
+    }
+}
+
+/// compute the statistics of a *column* in the rows of a table
+///
+/// the rows are walked once, without copying any of them, and only the numbers of the column
+/// are collected to compute the median.
+/// - missing cells, `null`s and errors are skipped and counted as such
+/// - any other non-numeric cell, or a mix of units, e.g. filesizes and durations, is an error
+///
+/// > see the tests for detailed examples
+pub(crate) fn column_stats(rows: &[Value], column: &str) -> Result<ColumnStats, String> {
+    let mut unit: Option<Unit> = None;
+    let mut numbers = vec![];
+    let mut skipped = 0;
+    let (mut min, mut max, mut sum) = (f64::INFINITY, f64::NEG_INFINITY, 0.0);
+
+    for row in rows {
+        let (x, u) = match row.as_record().ok().and_then(|r| r.get(column)) {
+            Some(Value::Int { val, .. }) => (*val as f64, Unit::Int),
+            Some(Value::Float { val, .. }) => (*val, Unit::Float),
+            Some(Value::Filesize { val, .. }) => (*val as f64, Unit::Filesize),
+            Some(Value::Duration { val, .. }) => (*val as f64, Unit::Duration),
+            None | Some(Value::Nothing { .. }) | Some(Value::Error { .. }) => {
+                skipped += 1;
+                continue;
+            }
+            Some(x) => {
+                return Err(format!(
+                    "column `{}` is not numeric, found {}",
+                    column,
+                    x.get_type()
+                ))
+            }
+        };
+
+        unit = match (unit, u) {
+            (None, u) => Some(u),
+            (Some(a), b) if a == b => Some(a),
+            (Some(Unit::Int | Unit::Float), Unit::Int | Unit::Float) => Some(Unit::Float),
+            _ => return Err(format!("column `{}` mixes different units", column)),
+        };
+
+        min = min.min(x);
+        max = max.max(x);
+        sum += x;
+        numbers.push(x);
+    }
+
+    let unit = match unit {
+        Some(unit) => unit,
+        None => return Err(format!("column `{}` has no numeric cell", column)),
+    };
+
+    let count = numbers.len();
+    numbers.sort_by(f64::total_cmp);
+    let median = if count % 2 == 0 {
+        (numbers[count / 2 - 1] + numbers[count / 2]) / 2.0
+    } else {
+        numbers[count / 2]
+    };
+
+    Ok(ColumnStats {
+        column: column.to_string(),
+        unit,
+        count,
+        skipped,
+        min,
+        max,
+        mean: sum / count as f64,
+        median,
+        sum,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::{record, Value};
+
+    use super::{column_stats, ColumnStats, Unit};
+
+    fn table(cells: Vec<Option<Value>>) -> Vec<Value> {
+        cells
+            .into_iter()
+            .map(|cell| match cell {
+                Some(cell) => Value::test_record(record! { "a" => cell }),
+                None => Value::test_record(record! { "b" => Value::test_int(0) }),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stats() {
+        let rows = table(vec![
+            Some(Value::test_int(3)),
+            Some(Value::test_nothing()),
+            Some(Value::test_int(1)),
+            None,
+            Some(Value::test_int(4)),
+            Some(Value::test_int(2)),
+        ]);
+        assert_eq!(
+            column_stats(&rows, "a"),
+            Ok(ColumnStats {
+                column: "a".into(),
+                unit: Unit::Int,
+                count: 4,
+                skipped: 2,
+                min: 1.0,
+                max: 4.0,
+                mean: 2.5,
+                median: 2.5,
+                sum: 10.0,
+            })
+        );
+
+        let rows = table(vec![
+            Some(Value::test_int(3)),
+            Some(Value::test_float(0.5)),
+            Some(Value::test_int(1)),
+        ]);
+        let stats = column_stats(&rows, "a").unwrap();
+        assert_eq!(stats.unit, Unit::Float);
+        assert_eq!(stats.median, 1.0);
+        assert_eq!(stats.min, 0.5);
+    }
+
+    #[test]
+    fn stats_in_natural_units() {
+        let rows = table(vec![
+            Some(Value::test_filesize(1024)),
+            Some(Value::test_filesize(2048)),
+        ]);
+        let stats = column_stats(&rows, "a").unwrap();
+        assert_eq!(stats.unit, Unit::Filesize);
+        assert_eq!(stats.repr(stats.sum, true), "3.0 KiB");
+
+        let rows = table(vec![
+            Some(Value::test_duration(1_000_000_000)),
+            Some(Value::test_duration(2_000_000_000)),
+        ]);
+        let stats = column_stats(&rows, "a").unwrap();
+        assert_eq!(stats.repr(stats.mean, false), "1sec 500ms");
+
+        let rows = table(vec![Some(Value::test_int(1)), Some(Value::test_int(2))]);
+        let stats = column_stats(&rows, "a").unwrap();
+        assert_eq!(stats.repr(stats.sum, true), "3");
+        assert_eq!(stats.repr(stats.mean, false), "1.5");
+    }
+
+    #[test]
+    fn stats_of_non_numeric_columns() {
+        #[rustfmt::skip]
+        let cases = vec![
+            (
+                table(vec![Some(Value::test_int(1)), Some(Value::test_string("foo"))]),
+                "column `a` is not numeric, found string",
+            ),
+            (
+                table(vec![Some(Value::test_int(1)), Some(Value::test_filesize(1))]),
+                "column `a` mixes different units",
+            ),
+            (
+                table(vec![Some(Value::test_nothing()), None]),
+                "column `a` has no numeric cell",
+            ),
+        ];
+
+        for (rows, expected) in cases {
+            assert_eq!(column_stats(&rows, "a"), Err(expected.into()));
+        }
+    }
+}
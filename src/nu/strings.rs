@@ -32,6 +32,90 @@ impl SpecialString {
     }
 }
 
+/// a transformation of the case or of the surrounding whitespace of a string, see
+/// [`crate::command::Command::Transform`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Transform {
+    Upper,
+    Lower,
+    Trim,
+    /// the first letter of each word in uppercase and the others in lowercase
+    Titlecase,
+}
+
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let repr = match self {
+            Self::Upper => "upper",
+            Self::Lower => "lower",
+            Self::Trim => "trim",
+            Self::Titlecase => "titlecase",
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+impl Transform {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name {
+            "upper" => Some(Self::Upper),
+            "lower" => Some(Self::Lower),
+            "trim" => Some(Self::Trim),
+            "titlecase" => Some(Self::Titlecase),
+            _ => None,
+        }
+    }
+
+    /// > see the tests for detailed examples
+    pub(crate) fn apply(&self, input: &str) -> String {
+        match self {
+            Self::Upper => input.to_uppercase(),
+            Self::Lower => input.to_lowercase(),
+            Self::Trim => input.trim().to_string(),
+            Self::Titlecase => {
+                let mut output = String::with_capacity(input.len());
+                let mut start_of_word = true;
+                for c in input.chars() {
+                    if start_of_word {
+                        output.extend(c.to_uppercase());
+                    } else {
+                        output.extend(c.to_lowercase());
+                    }
+                    start_of_word = c.is_whitespace();
+                }
+                output
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod transform_tests {
+    use super::Transform;
+
+    #[test]
+    fn transform_strings() {
+        #[rustfmt::skip]
+        let cases = vec![
+            (Transform::Upper, "foo Bar", "FOO BAR"),
+            (Transform::Lower, "foo Bar", "foo bar"),
+            (Transform::Trim, "  foo bar \n", "foo bar"),
+            (Transform::Titlecase, "hello WORLD  of\tdata", "Hello World  Of\tData"),
+            (Transform::Titlecase, "", ""),
+        ];
+
+        for (transform, input, expected) in cases {
+            assert_eq!(
+                transform.apply(input),
+                expected,
+                "{} of {:?}",
+                transform,
+                input
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod special_strings_tests {
     use super::SpecialString;
@@ -0,0 +1,146 @@
+//! check the rows of a table against a simple schema, i.e. the expected type of some columns
+use nu_protocol::{Record, Value};
+
+/// whether a value of type *actual* fits in the *expected* type of a schema
+///
+/// - `any` accepts anything
+/// - `number` accepts `int` and `float`
+/// - `list` and `record` accept any list and any record, e.g. `list<int>`
+/// - a trailing `?` also accepts `nothing`, e.g. `int?`
+///
+/// > see the tests for detailed examples
+fn fits(actual: &str, expected: &str) -> bool {
+    if let Some(expected) = expected.strip_suffix('?') {
+        return actual == "nothing" || fits(actual, expected);
+    }
+
+    match expected {
+        "any" => true,
+        "number" => matches!(actual, "int" | "float"),
+        "list" | "record" => actual == expected || actual.starts_with(&format!("{}<", expected)),
+        _ => actual == expected,
+    }
+}
+
+/// check a *row* against a *schema*, i.e. a record from the columns to their expected type,
+/// e.g. `{age: int, email: string}`, see [`fits`]
+///
+/// this gives the reason of the first failure, e.g. ``"`age` is string, expected int"``.
+///
+/// > see the tests for detailed examples
+pub(crate) fn validate(row: &Value, schema: &Record) -> Result<(), String> {
+    let row = match row {
+        Value::Record { val, .. } => val,
+        Value::Error { error, .. } => return Err(error.to_string()),
+        x => return Err(format!("expected a record, found {}", x.get_type())),
+    };
+
+    for (column, expected) in schema.iter() {
+        let expected = match expected {
+            Value::String { val, .. } => val,
+            x => return Err(format!("invalid type in the schema: {}", x.get_type())),
+        };
+        match row.get(column) {
+            Some(Value::Error { error, .. }) => return Err(format!("`{}`: {}", column, error)),
+            Some(val) => {
+                let actual = val.get_type().to_string();
+                if !fits(&actual, expected) {
+                    return Err(format!("`{}` is {}, expected {}", column, actual, expected));
+                }
+            }
+            None if expected.ends_with('?') => {}
+            None => return Err(format!("missing column `{}`", column)),
+        }
+    }
+
+    Ok(())
+}
+
+/// check all the *rows* against a *schema*, see [`validate`], and give the index and the
+/// reason of the rows that fail
+pub(crate) fn failures(rows: &[Value], schema: &Record) -> Vec<(usize, String)> {
+    rows.iter()
+        .enumerate()
+        .filter_map(|(i, row)| validate(row, schema).err().map(|err| (i, err)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::{record, Value};
+
+    use super::{failures, fits, validate};
+
+    #[test]
+    fn fit_types() {
+        #[rustfmt::skip]
+        let cases = vec![
+            ("int", "int", true),
+            ("string", "int", false),
+            ("float", "number", true),
+            ("string", "any", true),
+            ("list<int>", "list", true),
+            ("list<int>", "list<int>", true),
+            ("list<int>", "record", false),
+            ("nothing", "int?", true),
+            ("int", "int?", true),
+            ("string", "int?", false),
+        ];
+
+        for (actual, expected, fit) in cases {
+            assert_eq!(fits(actual, expected), fit, "{} in {}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn validate_rows() {
+        let schema = record! {
+            "age" => Value::test_string("int"),
+            "email" => Value::test_string("string?"),
+        };
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (
+                Value::test_record(record! { "age" => Value::test_int(1) }),
+                Ok(()),
+            ),
+            (
+                Value::test_record(record! {
+                    "age" => Value::test_string("1"),
+                    "email" => Value::test_string("a@b"),
+                }),
+                Err("`age` is string, expected int".into()),
+            ),
+            (
+                Value::test_record(record! { "email" => Value::test_string("a@b") }),
+                Err("missing column `age`".into()),
+            ),
+            (
+                Value::test_record(record! {
+                    "age" => Value::test_int(1),
+                    "email" => Value::test_int(2),
+                }),
+                Err("`email` is int, expected string?".into()),
+            ),
+            (
+                Value::test_int(1),
+                Err("expected a record, found int".into()),
+            ),
+        ];
+
+        for (row, expected) in cases {
+            assert_eq!(validate(&row, &schema), expected);
+        }
+
+        let rows = vec![
+            Value::test_record(record! { "age" => Value::test_int(1) }),
+            Value::test_record(record! { "age" => Value::test_nothing() }),
+            Value::test_record(record! { "age" => Value::test_int(2) }),
+        ];
+        assert_eq!(
+            failures(&rows, &schema),
+            vec![(1, "`age` is nothing, expected int".to_string())]
+        );
+    }
+}
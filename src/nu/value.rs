@@ -1,20 +1,274 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
 use nu_protocol::{
     ast::{CellPath, PathMember},
-    record, Record, Span, Type, Value,
+    record, Range, Record, Span, Type, Value,
 };
 
+/// follow a cell path into a value, without cloning it
+///
+/// this is the same as [`Value::follow_cell_path`], restricted to the lists, records and ranges
+/// that can be explored, but gives a reference to the cell instead of a copy of the whole value.
+///
+/// > :bulb: **Note**  
+/// > as in Nushell, a missing cell behind an *optional* member gives `Nothing` and any other
+/// > missing cell gives `None`
+pub(crate) fn follow<'a>(value: &'a Value, members: &[PathMember]) -> Option<Cow<'a, Value>> {
+    let mut current = value;
+    for (i, member) in members.iter().enumerate() {
+        // NOTE: the elements of a range are computed, so they can't be borrowed
+        if let Value::Range { val, internal_span } = current {
+            let next = match member {
+                PathMember::Int { val: index, .. } => {
+                    range_element(val, *internal_span, *index).ok()?
+                }
+                PathMember::String { .. } => None,
+            };
+            return match (next, member) {
+                (Some(next), _) => {
+                    follow(&next, &members[i + 1..]).map(|cell| Cow::Owned(cell.into_owned()))
+                }
+                (
+                    None,
+                    PathMember::Int { optional: true, .. }
+                    | PathMember::String { optional: true, .. },
+                ) => Some(Cow::Owned(Value::nothing(Span::unknown()))),
+                (None, _) => None,
+            };
+        }
+
+        let next = match (current, member) {
+            (Value::List { vals, .. }, PathMember::Int { val, .. }) => vals.get(*val),
+            (Value::Record { val: rec, .. }, PathMember::String { val, .. }) => rec.get(val),
+            // NOTE: a duplicate column of a record is given by its index, see [`column_member`]
+            (Value::Record { val: rec, .. }, member @ PathMember::Int { val, .. }) => {
+                if column_index(rec, member).is_some_and(|i| column_member(rec, i) == *member) {
+                    rec.values().nth(*val)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        match (next, member) {
+            (Some(next), _) => current = next,
+            (
+                None,
+                PathMember::Int { optional: true, .. } | PathMember::String { optional: true, .. },
+            ) => return Some(Cow::Owned(Value::nothing(Span::unknown()))),
+            (None, _) => return None,
+        }
+    }
+
+    Some(Cow::Borrowed(current))
+}
+
+/// the *cell path* member of the *index*-th column of a record
+///
+/// this is the name of the column, unless other columns of the record have the same name, e.g.
+/// after some joins, in which case this is the index of the column because a name can not tell
+/// duplicate columns apart.
+///
+/// > see the tests for detailed examples
+pub(crate) fn column_member(rec: &Record, index: usize) -> PathMember {
+    let col = &rec.cols[index];
+    if rec.cols.iter().filter(|c| *c == col).count() > 1 {
+        PathMember::Int {
+            val: index,
+            span: Span::unknown(),
+            optional: false,
+        }
+    } else {
+        PathMember::String {
+            val: col.clone(),
+            span: Span::unknown(),
+            optional: false,
+        }
+    }
+}
+
+/// the index of the column of a record that a *cell path* *member* points to, see
+/// [`column_member`]
+pub(crate) fn column_index(rec: &Record, member: &PathMember) -> Option<usize> {
+    match member {
+        PathMember::String { val, .. } => rec.cols.iter().position(|c| c == val),
+        PathMember::Int { val, .. } if *val < rec.cols.len() => Some(*val),
+        PathMember::Int { .. } => None,
+    }
+}
+
+/// whether the *cell path* *members* in a *value* can be used in Nushell, i.e. whether none
+/// of them is the index of a duplicate column, see [`column_member`]
+pub(crate) fn is_nushell_cell_path(value: &Value, members: &[PathMember]) -> bool {
+    !members.iter().enumerate().any(|(i, member)| {
+        matches!(member, PathMember::Int { .. })
+            && matches!(
+                follow(value, &members[..i]).as_deref(),
+                Some(Value::Record { .. })
+            )
+    })
+}
+
+/// the columns of a table, in order of first appearance in the rows
+///
+/// > :bulb: **Note**  
+/// > in a ragged table, some rows do not have all the columns
+pub(crate) fn table_columns<'a>(rows: impl IntoIterator<Item = &'a Record>) -> Vec<String> {
+    let mut columns: Vec<String> = vec![];
+    for row in rows {
+        for col in row.columns() {
+            if !columns.contains(col) {
+                columns.push(col.clone());
+            }
+        }
+    }
+    columns
+}
+
+/// the maximum number of elements of a range that can be explored
+pub(crate) const MAX_RANGE_LENGTH: usize = 100_000;
+
+/// enumerate the elements of a range, to explore it as a list
+///
+/// > :bulb: **Note**  
+/// > unbounded ranges, e.g. `1..`, and ranges of more than [`MAX_RANGE_LENGTH`] elements can't
+/// > be explored and give an error
+///
+/// > see the tests for detailed examples
+pub(crate) fn range_to_list(range: &Range, span: Span) -> Result<Value, String> {
+    if matches!(range.to, Value::Nothing { .. }) {
+        return Err("cannot explore an unbounded range".into());
+    }
+
+    let vals: Vec<Value> = range
+        .clone()
+        .into_range_iter(None)
+        .map_err(|err| err.to_string())?
+        .take(MAX_RANGE_LENGTH + 1)
+        .collect();
+    if vals.len() > MAX_RANGE_LENGTH {
+        return Err(format!(
+            "cannot explore a range of more than {} elements",
+            MAX_RANGE_LENGTH
+        ));
+    }
+    if let Some(Value::Error { error, .. }) = vals.last() {
+        return Err(error.to_string());
+    }
+
+    Ok(Value::list(vals, span))
+}
+
+/// the bounds and the step of a range of integers
+fn int_range(range: &Range) -> Option<(i128, i128, i128)> {
+    match (&range.from, &range.incr, &range.to) {
+        (
+            Value::Int { val: from, .. },
+            Value::Int { val: incr, .. },
+            Value::Int { val: to, .. },
+        ) => Some((*from as i128, *incr as i128, *to as i128)),
+        _ => None,
+    }
+}
+
+/// the number of elements of a range, without enumerating the range when its bounds and its
+/// step are integers, see [`range_to_list`]
+///
+/// this gives an error when the range can not be explored.
+///
+/// > see the tests for detailed examples
+pub(crate) fn range_len(range: &Range, span: Span) -> Result<usize, String> {
+    let (from, incr, to) = match int_range(range) {
+        Some(bounds) => bounds,
+        // NOTE: the other ranges, e.g. of floats, are enumerated as in Nushell
+        None => {
+            return match range_to_list(range, span)? {
+                Value::List { vals, .. } => Ok(vals.len()),
+                _ => Ok(0),
+            }
+        }
+    };
+
+    let too_long = || {
+        format!(
+            "cannot explore a range of more than {} elements",
+            MAX_RANGE_LENGTH
+        )
+    };
+    // NOTE: the number of elements given by the iterator of Nushell, which never stops when
+    // the step goes away from the end of the range
+    let exclusive = !range.is_end_inclusive() as i128;
+    let len = if exclusive == 1 && from == to {
+        0
+    } else if from <= to && incr > 0 {
+        (to - from - exclusive) / incr + 1
+    } else if from > to && incr < 0 {
+        (from - to - exclusive) / -incr + 1
+    } else {
+        return Err(too_long());
+    };
+    if len > MAX_RANGE_LENGTH as i128 {
+        return Err(too_long());
+    }
+    // NOTE: the iterator computes the element after the last one, which might overflow
+    if len > 0 && i64::try_from(from + len * incr).is_err() {
+        return Err("the range overflows".into());
+    }
+
+    Ok(len as usize)
+}
+
+/// the *index*-th element of a range, without enumerating the range when its bounds and its
+/// step are integers, see [`range_len`]
+///
+/// this gives an error when the range can not be explored and `None` when the *index* is
+/// outside of the range.
+pub(crate) fn range_element(
+    range: &Range,
+    span: Span,
+    index: usize,
+) -> Result<Option<Value>, String> {
+    let (from, incr, _) = match int_range(range) {
+        Some(bounds) => bounds,
+        None => {
+            return match range_to_list(range, span)? {
+                Value::List { vals, .. } => Ok(vals.get(index).cloned()),
+                _ => Ok(None),
+            }
+        }
+    };
+
+    if index < range_len(range, span)? {
+        Ok(Some(Value::int(
+            (from + index as i128 * incr) as i64,
+            range.from.span(),
+        )))
+    } else {
+        Ok(None)
+    }
+}
+
+/// the value to show in place of a value: the elements of a range that can be explored, see
+/// [`range_to_list`], or the value itself
+pub(crate) fn explorable(value: Cow<'_, Value>) -> Cow<'_, Value> {
+    let list = match value.as_ref() {
+        Value::Range { val, internal_span } => range_to_list(val, *internal_span).ok(),
+        _ => None,
+    };
+
+    match list {
+        Some(list) => Cow::Owned(list),
+        None => value,
+    }
+}
+
 pub(crate) fn mutate_value_cell(value: &Value, cell_path: &CellPath, cell: &Value) -> Value {
     if cell_path.members.is_empty() {
         return cell.clone();
     }
 
-    if value
-        .clone()
-        .follow_cell_path(&cell_path.members, false)
-        .is_err()
-    {
+    if follow(value, &cell_path.members).is_none() {
         return value.clone();
     }
 
@@ -37,14 +291,9 @@ pub(crate) fn mutate_value_cell(value: &Value, cell_path: &CellPath, cell: &Valu
             Value::list(vals, Span::unknown())
         }
         Value::Record { val: rec, .. } => {
-            let col = match first {
-                PathMember::String { val, .. } => val.clone(),
-                _ => panic!("first cell path element should be an string"),
-            };
+            let id = column_index(rec, first).unwrap_or(0);
             cell_path.members.remove(0);
 
-            let id = rec.cols.iter().position(|x| *x == col).unwrap_or(0);
-
             let cols = rec.columns().cloned().collect();
             let vals = rec
                 .values()
@@ -64,11 +313,28 @@ pub(crate) fn mutate_value_cell(value: &Value, cell_path: &CellPath, cell: &Valu
                 Span::unknown(),
             )
         }
+        // NOTE: a range is edited as the list of its elements
+        Value::Range { val, internal_span } => match range_to_list(val, *internal_span) {
+            Ok(list) => mutate_value_cell(&list, &cell_path, cell),
+            Err(_) => value.clone(),
+        },
         _ => cell.clone(),
     }
 }
 
 pub(crate) fn is_table(value: &Value) -> bool {
+    check_table(value, false)
+}
+
+/// a *ragged* table is a table in which some rows might lack some of the columns
+///
+/// > :bulb: **Note**  
+/// > every table is also a ragged table
+pub(crate) fn is_ragged_table(value: &Value) -> bool {
+    check_table(value, true)
+}
+
+fn check_table(value: &Value, allow_holes: bool) -> bool {
     match value {
         Value::List { vals, .. } => {
             if vals.is_empty() {
@@ -87,20 +353,32 @@ pub(crate) fn is_table(value: &Value) -> bool {
             }
 
             // check the number of columns for each row
-            let n = rows[0].keys().len();
-            for row in rows.iter().skip(1) {
-                if row.keys().len() != n {
-                    return false;
+            if !allow_holes {
+                let n = rows[0].keys().len();
+                for row in rows.iter().skip(1) {
+                    if row.keys().len() != n {
+                        return false;
+                    }
+                }
+            }
+
+            let mut keys: Vec<&String> = vec![];
+            for row in rows.iter() {
+                for key in row.keys() {
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
                 }
             }
 
             // check the actual types for each column
             // - if a row has a null, it doesn't count as "not a table"
             // - if two rows are numeric, then the check can continue
-            for (key, val) in rows[0].iter() {
-                let mut ty = val;
+            // - if a row does not have the column, it's a hole
+            for key in keys {
+                let mut ty = &Type::Nothing;
 
-                for row in rows.iter().skip(1) {
+                for row in rows.iter() {
                     match row.get(key) {
                         Some(v) => match ty {
                             Type::Nothing => ty = v,
@@ -118,7 +396,11 @@ pub(crate) fn is_table(value: &Value) -> bool {
                                 }
                             }
                         },
-                        None => return false,
+                        None => {
+                            if !allow_holes {
+                                return false;
+                            }
+                        }
                     }
                 }
             }
@@ -252,14 +534,54 @@ pub(crate) fn transpose(value: &Value) -> Value {
     }
 }
 
+/// pivot a table into a record of its columns, e.g. `{name: [...], size: [...]}`
+///
+/// the cells missing from the rows of a ragged table are `null` in the columns, so that all the
+/// columns have the length of the table.
+///
+/// > :bulb: **Note**  
+/// > this gives `None` if the value is not a list of records
+///
+/// > see the tests for detailed examples
+pub(crate) fn to_columns(value: &Value) -> Option<Value> {
+    let rows = match value {
+        Value::List { vals, .. } => vals
+            .iter()
+            .map(|val| val.as_record().ok())
+            .collect::<Option<Vec<&Record>>>()?,
+        _ => return None,
+    };
+
+    let mut columns = Record::new();
+    for col in table_columns(rows.iter().copied()) {
+        let cells = rows
+            .iter()
+            .map(|row| {
+                row.get(&col)
+                    .cloned()
+                    .unwrap_or(Value::nothing(Span::unknown()))
+            })
+            .collect();
+        columns.push(col, Value::list(cells, Span::unknown()));
+    }
+
+    Some(Value::record(columns, Span::unknown()))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{is_table, mutate_value_cell};
+    use super::{
+        column_index, column_member, follow, is_nushell_cell_path, is_ragged_table, is_table,
+        mutate_value_cell, range_element, range_len, range_to_list, to_columns, MAX_RANGE_LENGTH,
+    };
     use crate::nu::{
         cell_path::{to_path_member_vec, PM},
         value::transpose,
     };
-    use nu_protocol::{ast::CellPath, record, Config, Value};
+    use nu_protocol::{
+        ast::{CellPath, PathMember, RangeInclusion},
+        record, Config, Range, Record, Span, Value,
+    };
 
     fn default_value_repr(value: &Value) -> String {
         value.into_string(" ", &Config::default())
@@ -398,6 +720,242 @@ mod tests {
         }
     }
 
+    fn range(from: i64, to: Option<i64>) -> Range {
+        Range {
+            from: Value::test_int(from),
+            incr: Value::test_int(1),
+            to: to.map_or(Value::test_nothing(), Value::test_int),
+            inclusion: RangeInclusion::Inclusive,
+        }
+    }
+
+    #[test]
+    fn ranges_as_lists() {
+        assert_eq!(
+            range_to_list(&range(1, Some(3)), Span::test_data()),
+            Ok(Value::test_list(vec![
+                Value::test_int(1),
+                Value::test_int(2),
+                Value::test_int(3)
+            ]))
+        );
+        assert_eq!(
+            range_to_list(&range(1, None), Span::test_data()),
+            Err("cannot explore an unbounded range".into())
+        );
+        assert_eq!(
+            range_to_list(&range(0, Some(MAX_RANGE_LENGTH as i64)), Span::test_data()),
+            Err(format!(
+                "cannot explore a range of more than {} elements",
+                MAX_RANGE_LENGTH
+            ))
+        );
+
+        let value = Value::test_record(record! {
+            "r" => Value::test_range(range(5, Some(10))),
+        });
+        assert_eq!(
+            follow(&value, &to_path_member_vec(&[PM::S("r"), PM::I(2)])).map(|v| v.into_owned()),
+            Some(Value::test_int(7))
+        );
+        assert_eq!(
+            follow(&value, &to_path_member_vec(&[PM::S("r"), PM::I(6)])),
+            None
+        );
+
+        let mutated = mutate_value_cell(
+            &value,
+            &CellPath {
+                members: to_path_member_vec(&[PM::S("r"), PM::I(1)]),
+            },
+            &Value::test_int(0),
+        );
+        assert_eq!(
+            mutated.get_data_by_key("r"),
+            Some(Value::test_list(
+                [5, 0, 7, 8, 9, 10].map(Value::test_int).to_vec()
+            ))
+        );
+    }
+
+    #[test]
+    fn elements_of_ranges() {
+        // NOTE: the elements are computed exactly as the whole range would be enumerated
+        for (from, incr, to) in [
+            (1, 1, 5),
+            (1, 2, 6),
+            (1, 2, 7),
+            (5, -1, 1),
+            (7, -3, -2),
+            (3, 1, 3),
+            (3, -1, 3),
+            (3, 0, 3),
+            (1, -1, 5),
+            (5, 1, 1),
+            (0, 1, MAX_RANGE_LENGTH as i64),
+            (i64::MAX - 3, 1, i64::MAX),
+            (i64::MIN + 3, -2, i64::MIN),
+        ] {
+            for inclusion in [RangeInclusion::Inclusive, RangeInclusion::RightExclusive] {
+                let range = Range {
+                    from: Value::test_int(from),
+                    incr: Value::test_int(incr),
+                    to: Value::test_int(to),
+                    inclusion,
+                };
+                let name = format!("{}..{}..{} ({:?})", from, incr, to, inclusion);
+
+                match range_to_list(&range, Span::test_data()) {
+                    Ok(Value::List { vals, .. }) => {
+                        assert_eq!(
+                            range_len(&range, Span::test_data()),
+                            Ok(vals.len()),
+                            "length of {}",
+                            name
+                        );
+                        for index in 0..vals.len() + 2 {
+                            assert_eq!(
+                                range_element(&range, Span::test_data(), index),
+                                Ok(vals.get(index).cloned()),
+                                "element {} of {}",
+                                index,
+                                name
+                            );
+                        }
+                    }
+                    Ok(x) => panic!("expected a list, found {:?}", x),
+                    Err(_) => assert!(
+                        range_len(&range, Span::test_data()).is_err(),
+                        "{} can not be explored",
+                        name
+                    ),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn follow_cell_paths() {
+        let value = Value::test_record(record! {
+            "a" => Value::test_list(vec![
+                Value::test_int(1),
+                Value::test_record(record! {
+                    "b" => Value::test_string("foo"),
+                }),
+            ]),
+        });
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (vec![], Some(value.clone())),
+            (vec![PM::S("a"), PM::I(0)], Some(Value::test_int(1))),
+            (vec![PM::S("a"), PM::I(1), PM::S("b")], Some(Value::test_string("foo"))),
+            (vec![PM::S("a"), PM::I(2)], None),
+            (vec![PM::S("c")], None),
+            (vec![PM::I(0)], None),
+        ];
+
+        for (members, expected) in cases {
+            let cell_path = to_path_member_vec(&members);
+            assert_eq!(
+                follow(&value, &cell_path).map(|v| v.into_owned()),
+                expected,
+                "could not follow {} in {}",
+                PM::as_cell_path(&members),
+                default_value_repr(&value)
+            );
+        }
+
+        let mut cell_path = to_path_member_vec(&[PM::S("a"), PM::I(2)]);
+        if let Some(PathMember::Int { optional, .. }) = cell_path.last_mut() {
+            *optional = true;
+        }
+        assert_eq!(
+            follow(&value, &cell_path).map(|v| v.into_owned()),
+            Some(Value::nothing(nu_protocol::Span::unknown()))
+        );
+    }
+
+    #[test]
+    fn duplicate_columns() {
+        let mut rec = Record::new();
+        for (col, val) in [("x", 1), ("x", 2), ("x", 3), ("y", 4)] {
+            rec.push(col, Value::test_int(val));
+        }
+
+        let members: Vec<PathMember> = (0..4).map(|i| column_member(&rec, i)).collect();
+        assert_eq!(
+            members,
+            to_path_member_vec(&[PM::I(0), PM::I(1), PM::I(2), PM::S("y")])
+        );
+        for (i, member) in members.iter().enumerate() {
+            assert_eq!(column_index(&rec, member), Some(i));
+        }
+
+        let value = Value::test_record(rec);
+        #[rustfmt::skip]
+        let cases = vec![
+            (vec![PM::I(0)], Some(Value::test_int(1))),
+            (vec![PM::I(1)], Some(Value::test_int(2))),
+            (vec![PM::I(2)], Some(Value::test_int(3))),
+            // NOTE: the name of a duplicate column gives the first one, as in Nushell
+            (vec![PM::S("x")], Some(Value::test_int(1))),
+            // NOTE: a unique column is only given by its name
+            (vec![PM::I(3)], None),
+            (vec![PM::S("y")], Some(Value::test_int(4))),
+        ];
+        for (members, expected) in cases {
+            let members = to_path_member_vec(&members);
+            assert_eq!(
+                follow(&value, &members).map(|v| v.into_owned()),
+                expected,
+                "following {:?}",
+                members
+            );
+        }
+
+        // NOTE: Nushell can not address a duplicate column by its index
+        assert!(!is_nushell_cell_path(
+            &value,
+            &to_path_member_vec(&[PM::I(1)])
+        ));
+        assert!(is_nushell_cell_path(
+            &value,
+            &to_path_member_vec(&[PM::S("y")])
+        ));
+        let list = Value::test_list(vec![value.clone()]);
+        assert!(is_nushell_cell_path(
+            &list,
+            &to_path_member_vec(&[PM::I(0)])
+        ));
+        assert!(!is_nushell_cell_path(
+            &list,
+            &to_path_member_vec(&[PM::I(0), PM::I(2)])
+        ));
+
+        let mutated = mutate_value_cell(
+            &value,
+            &CellPath {
+                members: to_path_member_vec(&[PM::I(2)]),
+            },
+            &Value::test_int(0),
+        );
+        assert_eq!(
+            mutated
+                .as_record()
+                .unwrap()
+                .values()
+                .cloned()
+                .collect::<Vec<Value>>(),
+            vec![
+                Value::test_int(1),
+                Value::test_int(2),
+                Value::test_int(0),
+                Value::test_int(4)
+            ]
+        );
+    }
+
     #[test]
     fn is_a_table() {
         let table = Value::test_list(vec![
@@ -595,4 +1153,123 @@ mod tests {
             Value::test_list(vec![Value::test_int(1), Value::test_int(2)])
         );
     }
+
+    #[test]
+    fn is_a_ragged_table() {
+        let ragged_table = Value::test_list(vec![
+            Value::test_record(record! {
+                "a" => Value::test_string("a"),
+            }),
+            Value::test_record(record! {
+                "a" => Value::test_string("b"),
+                "b" => Value::test_int(1),
+            }),
+            Value::test_record(record! {
+                "b" => Value::test_int(2),
+            }),
+        ]);
+        assert!(
+            is_ragged_table(&ragged_table),
+            "{} should be a ragged table",
+            default_value_repr(&ragged_table)
+        );
+        assert!(
+            !is_table(&ragged_table),
+            "{} should not be a table",
+            default_value_repr(&ragged_table)
+        );
+
+        let table = Value::test_list(vec![
+            Value::test_record(record! {
+                "a" => Value::test_string("foo"),
+                "b" => Value::test_int(1),
+            }),
+            Value::test_record(record! {
+                "a" => Value::test_string("bar"),
+                "b" => Value::test_int(2),
+            }),
+        ]);
+        assert!(
+            is_ragged_table(&table),
+            "{} should be a ragged table",
+            default_value_repr(&table)
+        );
+
+        let not_a_table_incompatible_types = Value::test_list(vec![
+            Value::test_record(record! {
+                "a" => Value::test_string("a"),
+            }),
+            Value::test_record(record! {
+                "a" => Value::test_int(1),
+                "b" => Value::test_int(1),
+            }),
+        ]);
+        assert!(
+            !is_ragged_table(&not_a_table_incompatible_types),
+            "{} should not be a ragged table",
+            default_value_repr(&not_a_table_incompatible_types)
+        );
+
+        assert!(!is_ragged_table(&Value::test_list(vec![Value::test_int(
+            0
+        )])));
+    }
+
+    #[test]
+    fn pivot_to_columns() {
+        let table = Value::test_list(vec![
+            Value::test_record(record! {
+                "name" => Value::test_string("a"),
+                "size" => Value::test_int(1),
+            }),
+            Value::test_record(record! {
+                "name" => Value::test_string("b"),
+                "size" => Value::test_int(2),
+            }),
+        ]);
+        let columns = to_columns(&table).unwrap();
+        assert_eq!(
+            columns,
+            Value::test_record(record! {
+                "name" => Value::test_list(vec![Value::test_string("a"), Value::test_string("b")]),
+                "size" => Value::test_list(vec![Value::test_int(1), Value::test_int(2)]),
+            })
+        );
+
+        // NOTE: the pivot of a table with columns of equal length is not lossy
+        let rec = columns.as_record().unwrap();
+        let n = rec.values().next().unwrap().as_list().unwrap().len();
+        let rows: Vec<Value> = (0..n)
+            .map(|i| {
+                let mut row = Record::new();
+                for (col, cells) in rec.iter() {
+                    row.push(col, cells.as_list().unwrap()[i].clone());
+                }
+                Value::test_record(row)
+            })
+            .collect();
+        assert_eq!(Value::test_list(rows), table);
+
+        let ragged = Value::test_list(vec![
+            Value::test_record(record! { "a" => Value::test_int(1) }),
+            Value::test_record(record! { "b" => Value::test_int(2) }),
+        ]);
+        assert_eq!(
+            to_columns(&ragged),
+            Some(Value::test_record(record! {
+                "a" => Value::test_list(vec![Value::test_int(1), Value::test_nothing()]),
+                "b" => Value::test_list(vec![Value::test_nothing(), Value::test_int(2)]),
+            }))
+        );
+
+        assert_eq!(
+            to_columns(&Value::test_list(vec![])),
+            Some(Value::test_record(record! {}))
+        );
+        assert_eq!(
+            to_columns(&Value::test_list(vec![Value::test_int(1)])),
+            None
+        );
+        assert_eq!(to_columns(&Value::test_int(1)), None);
+    }
 }
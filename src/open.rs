@@ -0,0 +1,100 @@
+//! open the URLs and the paths of the data with the opener of the system, e.g. `xdg-open`
+use std::process::{Command, Stdio};
+
+use url::Url;
+
+/// the schemes of the URLs that can be opened
+const SCHEMES: [&str; 4] = ["http", "https", "file", "mailto"];
+
+/// the target to open for a string, if it is clearly a URL or a path
+///
+/// the detection is conservative, only the URLs with one of the [`SCHEMES`] and the absolute
+/// paths to existing files are opened, anything else is left alone.
+///
+/// > see the tests for detailed examples
+pub(crate) fn openable(s: &str) -> Option<String> {
+    if s.contains(char::is_whitespace) {
+        return None;
+    }
+
+    if is_url(s) {
+        return Some(s.to_string());
+    }
+
+    let path = std::path::Path::new(s);
+    if path.is_absolute() && path.exists() {
+        return Some(s.to_string());
+    }
+
+    None
+}
+
+/// whether a string is a URL with one of the [`SCHEMES`], without looking at the filesystem
+pub(crate) fn is_url(s: &str) -> bool {
+    !s.contains(char::is_whitespace)
+        && Url::parse(s).is_ok_and(|url| SCHEMES.contains(&url.scheme()))
+}
+
+/// the opener of the system and its arguments before the target
+fn opener() -> (&'static str, &'static [&'static str]) {
+    if cfg!(target_os = "macos") {
+        ("open", &[])
+    } else if cfg!(windows) {
+        // NOTE: the empty argument is the title of the window, otherwise the target is used
+        ("cmd", &["/C", "start", ""])
+    } else {
+        ("xdg-open", &[])
+    }
+}
+
+/// open a *target* with the opener of the system, without waiting for it
+///
+/// the opener is detached from the terminal, i.e. it does not read from nor write to it, so that
+/// the TUI is not disturbed.
+pub(crate) fn open(target: &str) -> Result<(), String> {
+    let (program, args) = opener();
+    let mut child = Command::new(program)
+        .args(args)
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("could not run `{}`: {}", program, e))?;
+
+    // NOTE: the opener is waited for in the background, to not leave a zombie process behind
+    std::thread::spawn(move || child.wait());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::openable;
+
+    #[test]
+    fn openable_strings() {
+        let dir = std::env::temp_dir();
+        let dir = dir.to_str().unwrap();
+
+        #[rustfmt::skip]
+        let cases = vec![
+            ("https://www.nushell.sh/book/", true),
+            ("http://localhost:8080", true),
+            ("file:///etc/hosts", true),
+            ("mailto:someone@example.com", true),
+            (dir, true),
+            ("ftp://example.com/file", false),
+            ("javascript:alert(1)", false),
+            ("/this/path/does/not/exist", false),
+            ("relative/path", false),
+            ("https://example.com and more", false),
+            ("foo", false),
+            ("", false),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(openable(input).is_some(), expected, "opening {:?}", input);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! color the strings that are paths to existing files, as `ls` would with `$env.LS_COLORS`
+use std::{collections::HashMap, fs::Metadata};
+
+use ratatui::style::Style;
+
+use crate::ansi;
+
+/// the colors used when `$env.LS_COLORS` is not set, i.e. bold blue directories, bold cyan
+/// symbolic links and bold green executables
+const DEFAULT_LS_COLORS: &str = "di=01;34:ln=01;36:ex=01;32";
+
+/// the longest string that is considered a path, to skip the long texts cheaply
+const MAX_PATH_LENGTH: usize = 4096;
+
+/// the style of the paths to existing files, cached per string
+///
+/// > :bulb: **Note**  
+/// > the filesystem is only queried the first time a string is seen, so that nothing slows down
+/// > the rendering of the next frames
+pub(crate) struct PathStyles {
+    /// the styles of the kinds of files, e.g. `di` for directories, as in `$env.LS_COLORS`
+    colors: HashMap<String, Style>,
+    /// the style of all the strings seen so far, `None` when it is not a path to color
+    cache: HashMap<String, Option<Style>>,
+}
+
+impl PathStyles {
+    /// build the styles from `$env.LS_COLORS`, or from the default ones
+    pub(crate) fn from_env() -> Self {
+        let ls_colors = std::env::var("LS_COLORS").unwrap_or(DEFAULT_LS_COLORS.into());
+        Self::from_ls_colors(&ls_colors)
+    }
+
+    /// build the styles from a `LS_COLORS` string, e.g. `di=01;34:ln=01;36`
+    ///
+    /// only the kinds of files are used, the `*.ext` patterns are ignored.
+    pub(crate) fn from_ls_colors(ls_colors: &str) -> Self {
+        let colors = ls_colors
+            .split(':')
+            .filter_map(|entry| entry.split_once('='))
+            .filter(|(kind, _)| !kind.starts_with('*'))
+            .map(|(kind, sgr)| (kind.to_string(), ansi::apply_sgr(Style::default(), sgr)))
+            .collect();
+
+        Self {
+            colors,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// the style of a string if it is the path to an existing file
+    ///
+    /// > see the tests for detailed examples
+    pub(crate) fn style(&mut self, s: &str) -> Option<Style> {
+        if let Some(style) = self.cache.get(s) {
+            return *style;
+        }
+
+        let style = if looks_like_local_path(s) {
+            std::fs::symlink_metadata(s)
+                .ok()
+                .and_then(|metadata| self.colors.get(kind(&metadata)).copied())
+        } else {
+            None
+        };
+        self.cache.insert(s.to_string(), style);
+        style
+    }
+}
+
+/// whether a string could be a path on the local filesystem
+///
+/// network paths, e.g. `//host/share` or `sftp://host/file`, are never considered, to never
+/// block on a slow filesystem.
+fn looks_like_local_path(s: &str) -> bool {
+    if s.is_empty() || s.len() > MAX_PATH_LENGTH || s.contains(char::is_control) {
+        return false;
+    }
+    if s.starts_with("//") || s.starts_with(r"\\") || s.contains("://") {
+        return false;
+    }
+
+    s.contains(['/', '\\']) || s.starts_with('.')
+}
+
+/// the kind of a file, as in `$env.LS_COLORS`
+fn kind(metadata: &Metadata) -> &'static str {
+    if metadata.is_symlink() {
+        "ln"
+    } else if metadata.is_dir() {
+        "di"
+    } else if is_executable(metadata) {
+        "ex"
+    } else {
+        "fi"
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &Metadata) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::{Color, Modifier, Style};
+
+    use super::{looks_like_local_path, PathStyles};
+
+    #[test]
+    fn local_paths() {
+        #[rustfmt::skip]
+        let cases = vec![
+            ("src/lib.rs", true),
+            ("/tmp", true),
+            ("./foo", true),
+            (".", true),
+            ("foo", false),
+            ("", false),
+            ("//server/share", false),
+            (r"\\server\share", false),
+            ("https://example.com/foo", false),
+            ("foo\nbar/baz", false),
+        ];
+
+        for (s, expected) in cases {
+            assert_eq!(looks_like_local_path(s), expected, "checking {:?}", s);
+        }
+    }
+
+    #[test]
+    fn path_styles() {
+        // NOTE: the source files may or may not be executable depending on the checkout
+        let mut styles = PathStyles::from_ls_colors("di=01;34:fi=33:ex=33:*.rs=31");
+
+        let directory = Style::default()
+            .fg(Color::Indexed(4))
+            .add_modifier(Modifier::BOLD);
+        let file = Style::default().fg(Color::Indexed(3));
+
+        assert_eq!(styles.style("src"), None);
+        assert_eq!(styles.style("./src"), Some(directory));
+        assert_eq!(styles.style("src/lib.rs"), Some(file));
+        assert_eq!(styles.style("src/does/not/exist"), None);
+
+        // NOTE: the cache is used the second time
+        assert!(styles.cache.contains_key("src/lib.rs"));
+        assert_eq!(styles.style("src/lib.rs"), Some(file));
+    }
+}
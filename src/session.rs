@@ -0,0 +1,287 @@
+//! remember where the exploration of some data stopped, to start from there the next time
+//!
+//! the last *cell path* is stored per *fingerprint* of the input, in a small JSON file under the
+//! data directory of the user, e.g. `~/.local/share/nu_plugin_explore/sessions.json` on Linux.
+use std::path::PathBuf;
+
+use nu_protocol::{ast::PathMember, Span, Value};
+
+use crate::nu::value::follow;
+
+/// the maximum number of sessions kept in the file, the oldest ones are forgotten first
+const MAX_SESSIONS: usize = 64;
+
+/// compute the *fingerprint* of a value, from its type and its top-level shape
+///
+/// the fingerprint only depends on the columns of a record, or the length and the columns of
+/// the first item of a list, so that the same file gives the same fingerprint as long as the
+/// edits in between do not change its shape.
+///
+/// > :bulb: **Note**  
+/// > the fingerprints are stored on disk, so they are computed with FNV-1a, which gives the same
+/// > hash on all the platforms and with all the versions of Rust, unlike the hasher of `std`
+///
+/// > see the tests for detailed examples
+pub(crate) fn fingerprint(value: &Value) -> String {
+    let mut hasher = Fnv1a::new();
+    hasher.write_str(&value.get_type().to_string());
+    match value {
+        Value::Record { val, .. } => val.cols.iter().for_each(|c| hasher.write_str(c)),
+        Value::List { vals, .. } => {
+            hasher.write(&(vals.len() as u64).to_le_bytes());
+            if let Some(Value::Record { val, .. }) = vals.first() {
+                val.cols.iter().for_each(|c| hasher.write_str(c));
+            }
+        }
+        _ => {}
+    }
+    format!("{:016x}", hasher.0)
+}
+
+/// the 64-bit FNV-1a hash, see <http://www.isthe.com/chongo/tech/comp/fnv/>
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// hash a string, followed by a `0xff` byte so that `["ab", "c"]` and `["a", "bc"]` differ
+    fn write_str(&mut self, s: &str) {
+        self.write(s.as_bytes());
+        self.write(&[0xff]);
+    }
+}
+
+/// the last *cell paths* of the previous explorations, by *fingerprint*
+pub(crate) struct Sessions {
+    /// the file the sessions are stored in, if any
+    path: Option<PathBuf>,
+    sessions: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Sessions {
+    /// load the sessions from the file in the data directory of the user
+    pub(crate) fn open() -> Self {
+        let path = dirs_next::data_dir().map(|dir| dir.join("nu_plugin_explore/sessions.json"));
+        Self::from_file(path)
+    }
+
+    /// load the sessions from a file
+    ///
+    /// > :bulb: **Note**  
+    /// > a missing or corrupted file is ignored, i.e. there are no sessions to restore, and it
+    /// > will be overwritten on [`Sessions::save`]
+    fn from_file(path: Option<PathBuf>) -> Self {
+        let sessions = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        Self { path, sessions }
+    }
+
+    /// the last *cell path* in some data with the given *fingerprint*, if it still exists in
+    /// the *value*
+    pub(crate) fn restore(&self, fingerprint: &str, value: &Value) -> Option<Vec<PathMember>> {
+        let members = self
+            .sessions
+            .get(fingerprint)?
+            .as_array()?
+            .iter()
+            .map(|member| match member {
+                serde_json::Value::String(val) => Some(PathMember::String {
+                    val: val.clone(),
+                    span: Span::unknown(),
+                    optional: false,
+                }),
+                serde_json::Value::Number(val) => Some(PathMember::Int {
+                    val: val.as_u64()? as usize,
+                    span: Span::unknown(),
+                    optional: false,
+                }),
+                _ => None,
+            })
+            .collect::<Option<Vec<PathMember>>>()?;
+
+        if members.is_empty() {
+            return None;
+        }
+        follow(value, &members)?;
+
+        Some(members)
+    }
+
+    /// remember the last *cell path* in some data with the given *fingerprint* and write all
+    /// the sessions to the file
+    pub(crate) fn save(&mut self, fingerprint: &str, members: &[PathMember]) -> Result<(), String> {
+        let members = members
+            .iter()
+            .map(|member| match member {
+                PathMember::String { val, .. } => serde_json::Value::from(val.clone()),
+                PathMember::Int { val, .. } => serde_json::Value::from(*val),
+            })
+            .collect();
+
+        // NOTE: the sessions are kept from the oldest to the most recent one
+        self.sessions.shift_remove(fingerprint);
+        self.sessions
+            .insert(fingerprint.to_string(), serde_json::Value::Array(members));
+        while self.sessions.len() > MAX_SESSIONS {
+            let oldest = self.sessions.keys().next().cloned().unwrap();
+            self.sessions.shift_remove(&oldest);
+        }
+
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Err("no data directory to store the session".into()),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let contents = serde_json::to_string(&self.sessions).map_err(|e| e.to_string())?;
+        std::fs::write(path, contents).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::{record, Value};
+
+    use super::{fingerprint, Fnv1a, Sessions, MAX_SESSIONS};
+    use crate::nu::cell_path::{to_path_member_vec, PM};
+
+    #[test]
+    fn fingerprints() {
+        let record = |a: i64| {
+            Value::test_record(record! {
+                "a" => Value::test_int(a),
+                "b" => Value::test_string("foo"),
+            })
+        };
+
+        // NOTE: the values do not matter, only the shape
+        assert_eq!(fingerprint(&record(1)), fingerprint(&record(2)));
+        assert_ne!(
+            fingerprint(&record(1)),
+            fingerprint(&Value::test_record(record! {
+                "a" => Value::test_int(1),
+            }))
+        );
+
+        let list = |n: usize| Value::test_list(vec![record(1); n]);
+        assert_eq!(fingerprint(&list(2)), fingerprint(&list(2)));
+        assert_ne!(fingerprint(&list(2)), fingerprint(&list(3)));
+        assert_ne!(fingerprint(&list(1)), fingerprint(&record(1)));
+        assert_ne!(
+            fingerprint(&Value::test_int(1)),
+            fingerprint(&Value::test_string("1"))
+        );
+
+        // NOTE: the fingerprints are stored on disk, they must never change
+        let hash = |s: &[u8]| {
+            let mut hasher = Fnv1a::new();
+            hasher.write(s);
+            hasher.0
+        };
+        assert_eq!(hash(b""), 0xcbf29ce484222325);
+        assert_eq!(hash(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(hash(b"foobar"), 0x85944171f73967e8);
+        assert_ne!(
+            fingerprint(&Value::test_record(record! {
+                "ab" => Value::test_int(1),
+                "c" => Value::test_int(1),
+            })),
+            fingerprint(&Value::test_record(record! {
+                "a" => Value::test_int(1),
+                "bc" => Value::test_int(1),
+            })),
+        );
+    }
+
+    #[test]
+    fn save_and_restore() {
+        let file = std::env::temp_dir().join(format!(
+            "nu_plugin_explore-sessions-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&file);
+
+        let value = Value::test_record(record! {
+            "a" => Value::test_list(vec![Value::test_int(1), Value::test_int(2)]),
+        });
+        let id = fingerprint(&value);
+        let members = to_path_member_vec(&[PM::S("a"), PM::I(1)]);
+
+        let mut sessions = Sessions::from_file(Some(file.clone()));
+        assert_eq!(sessions.restore(&id, &value), None);
+        sessions.save(&id, &members).unwrap();
+
+        let sessions = Sessions::from_file(Some(file.clone()));
+        assert_eq!(sessions.restore(&id, &value), Some(members));
+        assert_eq!(sessions.restore("unknown", &value), None);
+
+        // NOTE: the cell path does not exist anymore
+        let value = Value::test_record(record! {
+            "a" => Value::test_list(vec![Value::test_int(1)]),
+        });
+        assert_eq!(sessions.restore(&id, &value), None);
+
+        // NOTE: a corrupted file is ignored and overwritten
+        std::fs::write(&file, "{ not json").unwrap();
+        let mut sessions = Sessions::from_file(Some(file.clone()));
+        assert_eq!(sessions.restore(&id, &value), None);
+        sessions
+            .save(&id, &to_path_member_vec(&[PM::S("a")]))
+            .unwrap();
+        assert_eq!(
+            Sessions::from_file(Some(file.clone())).restore(&id, &value),
+            Some(to_path_member_vec(&[PM::S("a")]))
+        );
+
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn forget_the_oldest_sessions() {
+        let file = std::env::temp_dir().join(format!(
+            "nu_plugin_explore-old-sessions-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&file);
+
+        let members = to_path_member_vec(&[PM::I(0)]);
+        let mut sessions = Sessions::from_file(Some(file.clone()));
+        for i in 0..MAX_SESSIONS {
+            sessions.save(&i.to_string(), &members).unwrap();
+        }
+        // NOTE: saving a session again makes it the most recent one
+        sessions.save("0", &members).unwrap();
+        sessions.save("a", &members).unwrap();
+        sessions.save("b", &members).unwrap();
+
+        assert_eq!(sessions.sessions.len(), MAX_SESSIONS);
+        for id in ["0", "a", "b", "3"] {
+            assert!(sessions.sessions.contains_key(id), "{} is kept", id);
+        }
+        for id in ["1", "2"] {
+            assert!(!sessions.sessions.contains_key(id), "{} is forgotten", id);
+        }
+        assert_eq!(
+            sessions.sessions.keys().next_back().map(String::as_str),
+            Some("b")
+        );
+
+        std::fs::remove_file(&file).unwrap();
+    }
+}
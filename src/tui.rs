@@ -1,10 +1,11 @@
 use anyhow::Result;
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use nu_protocol::ShellError;
 use ratatui::backend::Backend;
 use ratatui::prelude::Rect;
 use ratatui::Terminal;
-use std::io;
+use std::io::IsTerminal;
 use std::panic;
 
 use crate::app::App;
@@ -12,6 +13,43 @@ use crate::config::Config;
 use crate::event::EventHandler;
 use crate::ui;
 
+/// The output on which the terminal user interface is drawn.
+#[cfg(unix)]
+pub type TerminalOutput = std::fs::File;
+/// The output on which the terminal user interface is drawn.
+#[cfg(not(unix))]
+pub type TerminalOutput = std::io::Stderr;
+
+/// Opens the interactive terminal on which to draw.
+///
+/// On Unix, this is `/dev/tty` rather than *stderr*, so that the interface still works when
+/// *stderr* is redirected. On Windows, this is the console attached to *stderr*.
+///
+/// It fails when there is no interactive terminal, e.g. in a CI script, instead of waiting forever
+/// for key events that will never come.
+pub fn open_terminal() -> Result<TerminalOutput> {
+    #[cfg(unix)]
+    let output = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok();
+    #[cfg(not(unix))]
+    let output = Some(std::io::stderr());
+
+    match output {
+        Some(output) if output.is_terminal() => Ok(output),
+        _ => Err(ShellError::GenericError {
+            error: "explore requires an interactive terminal".into(),
+            msg: "could not find a terminal to draw on".into(),
+            span: None,
+            help: Some("`explore` cannot run in a script or when its input is captured".into()),
+            inner: vec![],
+        }
+        .into()),
+    }
+}
+
 /// Representation of a terminal user interface.
 ///
 /// It is responsible for setting up the terminal,
@@ -35,12 +73,17 @@ impl<B: Backend> Tui<B> {
     /// It enables the raw mode and sets terminal properties.
     pub fn init(&mut self) -> Result<()> {
         terminal::enable_raw_mode()?;
-        crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+        let mut output = open_terminal()?;
+        crossterm::execute!(output, EnterAlternateScreen, EnableMouseCapture)?;
 
         // Define a custom panic hook to reset the terminal properties.
         // This way, you won't have your terminal messed up if an unexpected error happens.
         let panic_hook = panic::take_hook();
         panic::set_hook(Box::new(move |panic| {
+            // NOTE: the panics of the renderer are recovered from, see `ui::render_ui_safely`
+            if ui::is_rendering() {
+                return;
+            }
             Self::reset().expect("failed to reset the terminal");
             panic_hook(panic);
         }));
@@ -56,7 +99,7 @@ impl<B: Backend> Tui<B> {
     /// [`rendering`]: crate::ui:render
     pub fn draw(&mut self, app: &mut App, config: &Config, error: Option<&str>) -> Result<()> {
         self.terminal
-            .draw(|frame| ui::render_ui(frame, app, config, error))?;
+            .draw(|frame| ui::render_ui_safely(frame, app, config, error))?;
         Ok(())
     }
 
@@ -66,7 +109,8 @@ impl<B: Backend> Tui<B> {
     /// the terminal properties if unexpected errors occur.
     fn reset() -> Result<()> {
         terminal::disable_raw_mode()?;
-        crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+        let mut output = open_terminal()?;
+        crossterm::execute!(output, LeaveAlternateScreen, DisableMouseCapture)?;
         Ok(())
     }
 
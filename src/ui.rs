@@ -1,24 +1,182 @@
 //! the module responsible for rendering the TUI
-use crate::nu::{strings::SpecialString, value::is_table};
+use crate::ansi;
+use crate::nu::{
+    diff::Change,
+    source::SourceInfo,
+    stats::ColumnStats,
+    strings::SpecialString,
+    value::{column_member, explorable, follow, is_ragged_table, table_columns},
+};
+use crate::open::is_url;
 
-use super::config::{repr_keycode, Layout};
-use super::{App, Config, Mode};
+use super::config::{
+    repr_keycode, ColumnWidth, Layout, LogLevel, Substitutions, WidthSpec, MIN_COLUMN_WIDTH,
+};
+use super::{app::StatusMessage, App, Config, Mode};
 use crossterm::event::KeyCode;
 use nu_protocol::ast::PathMember;
 use nu_protocol::{Record, Type, Value};
+use std::{
+    borrow::Cow,
+    ops::Range,
+    panic::{self, AssertUnwindSafe},
+    rc::Rc,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
 use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
     prelude::{Alignment, Constraint, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
-        Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table, TableState, Wrap,
+        Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState,
+        Wrap,
     },
-    Frame,
+    Frame, Terminal,
 };
 
+thread_local! {
+    /// whether a frame is being rendered by [`render_ui_safely`]
+    static RENDERING: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+#[cfg(test)]
+thread_local! {
+    /// make the renderer panic, to test [`render_ui_safely`]
+    static PANIC_WHILE_RENDERING: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// whether a frame is being rendered by [`render_ui_safely`], i.e. a panic will be recovered from
+pub(super) fn is_rendering() -> bool {
+    RENDERING.with(|r| r.get())
+}
+
+/// render the whole ui, recovering from a panic of the renderer, without any color in
+/// monochrome
+///
+/// a panic would otherwise unwind through the TUI and leave the terminal in raw mode. instead,
+/// an error screen with the panic message and the current *cell path* is rendered in place of
+/// the frame, so that the user can navigate away or quit, and the panic is logged, see
+/// [`crate::config::LogConfig`].
+pub(super) fn render_ui_safely(
+    frame: &mut Frame,
+    app: &mut App,
+    config: &Config,
+    error: Option<&str>,
+) {
+    RENDERING.with(|r| r.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| render_ui(frame, app, config, error)));
+    RENDERING.with(|r| r.set(false));
+
+    if let Err(payload) = result {
+        // NOTE: the panic might have happened while rendering the pane that is not focused
+        if app.split.as_ref().is_some_and(|split| split.is_swapped()) {
+            app.swap_panes();
+        }
+
+        let message = match (
+            payload.downcast_ref::<&str>(),
+            payload.downcast_ref::<String>(),
+        ) {
+            (Some(msg), _) => msg.to_string(),
+            (_, Some(msg)) => msg.clone(),
+            _ => "unknown error".into(),
+        };
+        let cell_path = repr_cell_path(&app.position.members);
+
+        let line = format!("panic at {}: {}", cell_path, message);
+        if let Err(err) = app.logger.log(LogLevel::Error, &line) {
+            app.status_message = Some(StatusMessage::Error(err));
+        }
+        render_panic(frame, &message, &cell_path);
+    }
+
+    if config.display.monochrome {
+        strip_colors(frame.buffer_mut());
+    }
+}
+
+/// drop all the colors of a rendered *buffer*, keeping only the modifiers, see
+/// [`crate::config::DisplayConfig::monochrome`]
+fn strip_colors(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
+}
+
+/// the style of the element under the cursor, in the data and in the popups
+///
+/// in monochrome, the selection is reversed on top of the
+/// [`crate::config::ColorConfig::selected_modifier`], as its colors are dropped.
+fn selected_style(config: &Config) -> Style {
+    let style = Style::default()
+        .fg(config.colors.selected.foreground)
+        .bg(config.colors.selected.background)
+        .add_modifier(config.colors.selected_modifier);
+    if config.display.monochrome {
+        style.add_modifier(Modifier::REVERSED)
+    } else {
+        style
+    }
+}
+
+/// render an error screen in place of the whole frame, after a panic of the renderer
+fn render_panic(frame: &mut Frame, message: &str, cell_path: &str) {
+    let lines = vec![
+        Line::from(Span::styled(
+            "the data could not be rendered, this is a bug",
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(format!("error: {}", message)),
+        Line::from(format!("cell path: {}", cell_path)),
+        Line::from(""),
+        Line::from("navigate away from this cell or quit"),
+    ];
+
+    frame.render_widget(Clear, frame.size());
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" rendering error ")
+                .style(Style::default().fg(Color::Red)),
+        ),
+        frame.size(),
+    );
+}
+
 /// render the whole ui
-pub(super) fn render_ui(frame: &mut Frame, app: &App, config: &Config, error: Option<&str>) {
-    render_data(frame, app, config);
+pub(super) fn render_ui(frame: &mut Frame, app: &mut App, config: &Config, error: Option<&str>) {
+    let data_frame_height = if config.show_cell_path {
+        frame.size().height - 2
+    } else {
+        frame.size().height - 1
+    };
+    let area = Rect::new(0, 0, frame.size().width, data_frame_height);
+    match app.split.as_ref().map(|split| split.right) {
+        None => render_data(frame, app, config, area),
+        Some(right) => {
+            let half = area.width / 2;
+            let left_area = Rect::new(0, 0, half, area.height);
+            let right_area = Rect::new(half, 0, area.width - half, area.height);
+            let (focused, other) = if right {
+                (right_area, left_area)
+            } else {
+                (left_area, right_area)
+            };
+
+            render_data(frame, app, config, focused);
+            app.swap_panes();
+            render_data(frame, app, config, other);
+            app.swap_panes();
+        }
+    }
+    app.recenter = false;
     if config.show_cell_path {
         render_cell_path(frame, app);
     }
@@ -28,11 +186,73 @@ pub(super) fn render_ui(frame: &mut Frame, app: &App, config: &Config, error: Op
         None => {
             render_status_bar(frame, app, config);
 
-            if app.mode == Mode::Insert {
+            if matches!(app.mode, Mode::Insert | Mode::Goto | Mode::Command) {
                 app.editor.render(frame, config);
+            } else if app.mode == Mode::Search {
+                if app.matches.is_empty() {
+                    app.editor.render(frame, config);
+                } else {
+                    render_matches(frame, app, config);
+                }
+            }
+
+            if !app.differences.is_empty() {
+                render_differences(frame, app, config);
+            }
+            if let Some(stats) = &app.stats {
+                render_stats(frame, stats, config);
+            }
+            if let Some(info) = &app.info {
+                render_info(frame, info, config);
+            }
+        }
+    }
+}
+
+/// render the whole ui, in NORMAL mode, off-screen and give back its text, without any style
+///
+/// the screen is rendered with the size of the terminal, see [`App::screen_size`], so that the
+/// text is exactly what was on the screen, the lines being trimmed at the end. the status bar is
+/// only kept with [`crate::config::Config::peek_status_bar`].
+pub(super) fn render_to_string(app: &mut App, config: &Config) -> String {
+    let (width, height) = app.screen_size;
+    let mode = std::mem::replace(&mut app.mode, Mode::Normal);
+
+    let mut terminal = Terminal::new(TestBackend::new(width, height))
+        .expect("an off-screen terminal can always be created");
+    terminal
+        .draw(|frame| render_ui(frame, app, config, None))
+        .expect("an off-screen terminal can always be drawn on");
+    app.mode = mode;
+
+    let buffer = terminal.backend().buffer();
+    let height = if config.peek_status_bar {
+        height
+    } else {
+        height.saturating_sub(1)
+    };
+
+    let mut lines = vec![];
+    for y in 0..height {
+        let mut line = String::new();
+        // NOTE: the cells covered by a wide character are skipped, as the terminal would
+        let mut skip = 0;
+        for x in 0..width {
+            if skip > 0 {
+                skip -= 1;
+                continue;
             }
+            let symbol = buffer.get(x, y).symbol();
+            skip = symbol.width().saturating_sub(1);
+            line.push_str(symbol);
         }
+        lines.push(line.trim_end().to_string());
     }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+
+    lines.join("\n")
 }
 
 pub(super) fn render_error(frame: &mut Frame, error: &str) {
@@ -44,7 +264,7 @@ pub(super) fn render_error(frame: &mut Frame, error: &str) {
             Style::default().fg(Color::Red),
         )),
         Line::from(Span::styled(
-            "Press any key to continue exploring the data.",
+            "Press any key to continue exploring the data, or ctrl-q to force quit.",
             Style::default().fg(Color::Blue),
         )),
     ];
@@ -55,12 +275,26 @@ pub(super) fn render_error(frame: &mut Frame, error: &str) {
     );
 }
 
+/// the placeholder for cells that are missing, e.g. in the rows of a ragged table
+const MISSING_CELL: &str = "❎";
+
+/// the separator between the pinned column of a table and the other ones, see
+/// [`crate::app::TableSettings::pinned`]
+const PINNED_SEPARATOR: &str = "┃";
+
+/// the gutter indicator of the rows that fail the validation, see
+/// [`crate::app::App::validate_view`]
+const FAILURE_SYMBOL: &str = "✗ ";
+
 /// a common representation for an explore row
 #[derive(Clone, Debug, PartialEq)]
-struct DataRowRepr {
+pub(crate) struct DataRowRepr {
     name: Option<String>,
     shape: String,
+    /// the data, without any ANSI escape sequence
     data: String,
+    /// the data split by the styles of its ANSI escape sequences, if it has any
+    styled: Option<Vec<(String, Style)>>,
 }
 
 impl DataRowRepr {
@@ -70,6 +304,7 @@ impl DataRowRepr {
             name: None,
             shape: shape.into(),
             data: data.into(),
+            styled: None,
         }
     }
 
@@ -79,6 +314,7 @@ impl DataRowRepr {
             name: Some(name.into()),
             shape: shape.into(),
             data: data.into(),
+            styled: None,
         }
     }
 }
@@ -97,6 +333,7 @@ fn repr_list(vals: &[Value]) -> DataRowRepr {
         name: None,
         shape: "list".into(),
         data,
+        styled: None,
     }
 }
 
@@ -114,13 +351,269 @@ fn repr_record(cols: &[String]) -> DataRowRepr {
         name: None,
         shape: "record".into(),
         data,
+        styled: None,
+    }
+}
+
+/// the symbol at the end of a truncated preview
+const TRUNCATED: &str = "…";
+
+/// truncate a string to *length* graphemes, ending it with [`TRUNCATED`] when it is too long
+///
+/// > :bulb: **Note**  
+/// > a *length* of `0` means that the string is never truncated
+///
+/// > see the tests for detailed examples
+fn truncate(s: &str, length: usize) -> String {
+    if length == 0 {
+        return s.to_string();
+    }
+
+    let mut graphemes = s.grapheme_indices(true);
+    match graphemes.nth(length) {
+        Some(_) => {
+            // NOTE: the (length - 1)-th grapheme exists because the length-th one does
+            let (end, _) = s.grapheme_indices(true).nth(length - 1).unwrap();
+            format!("{}{}", &s[..end], TRUNCATED)
+        }
+        None => s.to_string(),
+    }
+}
+
+/// the maximum width of the keys of a record in the compact layout, longer keys are truncated
+/// in the middle
+const MAX_KEY_WIDTH: usize = 24;
+
+/// truncate a string in the middle to fit in *width* terminal columns, e.g. `veryLong…Name`
+///
+/// > see the tests for detailed examples
+fn truncate_middle(s: &str, width: usize) -> String {
+    if s.width() <= width {
+        return s.to_string();
+    }
+    if width == 0 {
+        return "".into();
+    }
+
+    // NOTE: the start gets the extra column when the available width is odd
+    let available = width - TRUNCATED.width();
+    let (start_width, end_width) = (available - available / 2, available / 2);
+
+    fn take<'a>(graphemes: impl Iterator<Item = &'a str>, max_width: usize) -> Vec<&'a str> {
+        let mut taken = vec![];
+        let mut taken_width = 0;
+        for g in graphemes {
+            if taken_width + g.width() > max_width {
+                break;
+            }
+            taken_width += g.width();
+            taken.push(g);
+        }
+        taken
+    }
+
+    let start = take(s.graphemes(true), start_width);
+    let mut end = take(s.graphemes(true).rev(), end_width);
+    end.reverse();
+
+    format!("{}{}{}", start.concat(), TRUNCATED, end.concat())
+}
+
+/// align a key on *width* terminal columns, padding it with spaces or truncating it in the
+/// middle, see [`truncate_middle`]
+fn align_key(key: &str, width: usize) -> String {
+    let key = truncate_middle(key, width);
+    let padding = width.saturating_sub(key.width());
+    format!("{}{}", key, " ".repeat(padding))
+}
+
+/// the rows that are visible in a view of *height* rows out of *nb_rows*, starting at *offset*,
+/// once scrolled to show the *selected* row, as the list of the TUI does
+fn visible_rows(offset: usize, selected: usize, nb_rows: usize, height: usize) -> Range<usize> {
+    let offset = if selected < offset {
+        selected
+    } else if height > 0 && selected >= offset + height {
+        selected + 1 - height
+    } else {
+        offset
+    };
+    offset.min(nb_rows)..(offset + height).min(nb_rows)
+}
+
+/// truncate styled segments of text to *length* graphemes in total, like [`truncate`]
+///
+/// > see the tests for detailed examples
+fn truncate_styled(segments: &[(String, Style)], length: usize) -> Vec<(String, Style)> {
+    let total: usize = segments
+        .iter()
+        .map(|(t, _)| t.graphemes(true).count())
+        .sum();
+    if length == 0 || total <= length {
+        return segments.to_vec();
+    }
+
+    let mut remaining = length - 1;
+    let mut truncated = vec![];
+    for (text, style) in segments {
+        if remaining == 0 {
+            break;
+        }
+        let kept: String = text.graphemes(true).take(remaining).collect();
+        remaining -= kept.graphemes(true).count();
+        truncated.push((kept, *style));
+    }
+    truncated.push((TRUNCATED.to_string(), Style::default()));
+
+    truncated
+}
+
+/// the spans to render the *data* of a row with the given *style*
+///
+/// the *styled* segments of the data are only used when *render_ansi* is set, otherwise the
+/// data is rendered without its ANSI colors.
+fn data_spans(
+    data: String,
+    styled: Option<Vec<(String, Style)>>,
+    style: Style,
+    render_ansi: bool,
+) -> Vec<Span<'static>> {
+    match styled {
+        Some(segments) if render_ansi => segments
+            .into_iter()
+            .map(|(text, s)| Span::styled(text, style.patch(s)))
+            .collect(),
+        _ => vec![Span::styled(data, style)],
+    }
+}
+
+/// the style of a simple value when [`crate::config::DisplayConfig::colorize_values`] is set
+///
+/// booleans are green or red, the paths to existing files are styled as in `$env.LS_COLORS` and
+/// the URLs are underlined, all the other values are left as is.
+fn value_style(value: &Value, app: &mut App, config: &Config) -> Style {
+    if !config.display.colorize_values {
+        return Style::default();
+    }
+
+    match value {
+        Value::Bool { val: true, .. } => Style::default().fg(Color::Green),
+        Value::Bool { val: false, .. } => Style::default().fg(Color::Red),
+        Value::String { val, .. } => match app.path_styles.style(val) {
+            Some(style) => style,
+            None if is_url(val) => Style::default().add_modifier(Modifier::UNDERLINED),
+            None => Style::default(),
+        },
+        _ => Style::default(),
+    }
+}
+
+/// the maximum number of elements compared on each side of the selected element to find the
+/// runs of identical values, see [`runs_around`]
+const MAX_RUN_SCAN: usize = 16_384;
+
+/// group the consecutive identical simple values of a list into runs, around the *selected*
+/// element
+///
+/// at most *nb_runs* runs are computed on each side of the run of the selected element and at
+/// most [`MAX_RUN_SCAN`] elements are compared in each direction, so that the cost does not
+/// depend on the length of the list: a longer run is cut into pieces.
+///
+/// returns the runs, in order, and the index of the run of the selected element.
+///
+/// > see the tests for detailed examples
+fn runs_around(vals: &[Value], selected: usize, nb_runs: usize) -> (Vec<Range<usize>>, usize) {
+    if vals.is_empty() {
+        return (vec![], 0);
+    }
+
+    let same = |i: usize, j: usize| {
+        !matches!(vals[i], Value::List { .. } | Value::Record { .. }) && vals[i] == vals[j]
+    };
+    let first = selected.saturating_sub(MAX_RUN_SCAN);
+    let last = (selected + MAX_RUN_SCAN).min(vals.len() - 1);
+
+    let run_ending_at = |end: usize| {
+        let mut start = end;
+        while start > first && same(start - 1, start) {
+            start -= 1;
+        }
+        start
+    };
+    let run_starting_at = |start: usize| {
+        let mut end = start;
+        while end < last && same(end, end + 1) {
+            end += 1;
+        }
+        end
+    };
+
+    let mut before = vec![];
+    let mut start = run_ending_at(selected);
+    let selected_start = start;
+    while before.len() < nb_runs && start > first {
+        let end = start;
+        start = run_ending_at(end - 1);
+        before.push(start..end);
+    }
+    before.reverse();
+
+    let selected_run = before.len();
+    let mut runs = before;
+    let mut start = selected_start;
+    while runs.len() <= selected_run + nb_runs && start <= last {
+        let end = run_starting_at(start) + 1;
+        runs.push(start..end);
+        start = end;
+    }
+
+    (runs, selected_run)
+}
+
+/// compute the representation of a *run* of identical values of a list, as given by
+/// [`runs_around`]
+///
+/// a run of a single element is represented as the element itself, otherwise as its range of
+/// indices and the repeated value, e.g. `0‥99  (100 × 1)`, followed by the index of the
+/// *selected* element when it is in the run.
+fn repr_run(
+    vals: &[Value],
+    run: &Range<usize>,
+    selected: usize,
+    preview_length: usize,
+    depth: usize,
+    raw: bool,
+) -> DataRowRepr {
+    let repr = repr_value(&vals[run.start], preview_length, depth, raw);
+    if run.len() == 1 {
+        return repr;
+    }
+
+    let mut data = format!(
+        "{}‥{}  ({} × {})",
+        run.start,
+        run.end - 1,
+        run.len(),
+        repr.data
+    );
+    if run.contains(&selected) {
+        data.push_str(&format!("  [{}]", selected));
+    }
+
+    DataRowRepr {
+        name: None,
+        shape: repr.shape,
+        data,
+        styled: None,
     }
 }
 
-/// compute the preview representation of a simple value
+/// compute the preview representation of a simple value, truncated to *preview_length*
+/// graphemes
+///
+/// the value is formatted as in Nushell, or given *raw* (see [`repr_raw`]).
 ///
 /// > see the tests for detailed examples
-fn repr_simple_value(value: &Value) -> DataRowRepr {
+fn repr_simple_value(value: &Value, preview_length: usize, raw: bool) -> DataRowRepr {
     let shape = match value {
         Value::String { val, .. } => match SpecialString::parse(val) {
             Some(x) => x.to_string(),
@@ -128,29 +621,177 @@ fn repr_simple_value(value: &Value) -> DataRowRepr {
         },
         x => x.get_type().to_string(),
     };
+    let (data, styled) = match value {
+        x if raw => (truncate(&repr_raw(x), preview_length), None),
+        Value::String { val, .. } if val.contains('\x1b') => {
+            let segments = truncate_styled(&ansi::parse(val), preview_length);
+            let data = segments.iter().map(|(text, _)| text.as_str()).collect();
+            (data, Some(segments))
+        }
+        Value::String { val, .. } => (truncate(val, preview_length), None),
+        Value::Closure { val, .. } => (
+            truncate(
+                &format!(
+                    "closure of block {} capturing {} variables",
+                    val.block_id,
+                    val.captures.len()
+                ),
+                preview_length,
+            ),
+            None,
+        ),
+        Value::Block { val, .. } => (truncate(&format!("block {}", val), preview_length), None),
+        Value::CellPath { val, .. } => (
+            truncate(&repr_cell_path(&val.members), preview_length),
+            None,
+        ),
+        // FIXME: use a real config
+        x => (
+            truncate(
+                &x.into_string(" ", &nu_protocol::Config::default()),
+                preview_length,
+            ),
+            None,
+        ),
+    };
+
     DataRowRepr {
         name: None,
         shape,
+        data,
+        styled,
+    }
+}
+
+/// represent a simple value exactly, without any of the formatting of Nushell
+///
+/// - integers and floats have all their digits, floats always with a decimal part
+/// - dates are in RFC 3339 format
+/// - file sizes are a number of bytes and durations a number of nanoseconds
+/// - the control characters of strings are escaped, e.g. `\n` or `\t`
+///
+/// > see the tests for detailed examples
+fn repr_raw(value: &Value) -> String {
+    match value {
+        Value::Float { val, .. } => format!("{:?}", val),
+        Value::Date { val, .. } => val.to_rfc3339(),
+        Value::Filesize { val, .. } => val.to_string(),
+        Value::Duration { val, .. } => format!("{}ns", val),
+        Value::String { val, .. } => val
+            .chars()
+            .map(|c| {
+                if c.is_control() {
+                    c.escape_default().to_string()
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect(),
         // FIXME: use a real config
-        data: value.into_string(" ", &nu_protocol::Config::default()),
+        x => x.into_string(" ", &nu_protocol::Config::default()),
+    }
+}
+
+/// compute the preview representation of a missing value
+fn repr_missing() -> DataRowRepr {
+    DataRowRepr {
+        name: None,
+        shape: "missing value".into(),
+        data: MISSING_CELL.into(),
+        styled: None,
     }
 }
 
 /// compute the preview representation of a value
 ///
+/// lists and records are summarized, e.g. `[3 items]`, with a *depth* of `0` and previewed
+/// down to *depth* levels otherwise, see [`repr_nested`].
+///
 /// > see the tests for detailed examples
-fn repr_value(value: &Value) -> DataRowRepr {
+fn repr_value(value: &Value, preview_length: usize, depth: usize, raw: bool) -> DataRowRepr {
     match value {
-        Value::List { vals, .. } => repr_list(vals),
-        Value::Record { val: rec, .. } => repr_record(&rec.cols),
-        x => repr_simple_value(x),
+        Value::List { vals, .. } if depth == 0 || vals.is_empty() => repr_list(vals),
+        Value::Record { val: rec, .. } if depth == 0 || rec.is_empty() => repr_record(&rec.cols),
+        Value::List { .. } | Value::Record { .. } => DataRowRepr {
+            name: None,
+            shape: if matches!(value, Value::List { .. }) {
+                "list"
+            } else {
+                "record"
+            }
+            .into(),
+            data: repr_nested(value, preview_length, depth, raw),
+            styled: None,
+        },
+        x => repr_simple_value(x, preview_length, raw),
+    }
+}
+
+/// compute the nested preview of a list or a record, e.g. `{a: 1, b: [x, y], c: {…}}` with a
+/// *depth* of `2`, truncated to *preview_length* graphemes
+///
+/// the lists and records deeper than *depth* are replaced by `[…]` and `{…}`.
+///
+/// > :bulb: **Note**  
+/// > the formatting stops as soon as the preview is long enough to be truncated, so that the
+/// > preview of a huge value only costs its first few elements, whatever the *depth*
+///
+/// > see the tests for detailed examples
+fn repr_nested(value: &Value, preview_length: usize, depth: usize, raw: bool) -> String {
+    // NOTE: a grapheme is at least one byte and a char at most four, so more than four bytes
+    // per grapheme is always long enough to be truncated
+    let budget = match preview_length {
+        0 => usize::MAX,
+        n => n.saturating_mul(4),
+    };
+
+    fn write(value: &Value, depth: usize, budget: usize, raw: bool, out: &mut String) {
+        match value {
+            Value::List { vals, .. } if vals.is_empty() => out.push_str("[]"),
+            Value::Record { val: rec, .. } if rec.is_empty() => out.push_str("{}"),
+            Value::List { .. } if depth == 0 => out.push_str("[…]"),
+            Value::Record { .. } if depth == 0 => out.push_str("{…}"),
+            Value::List { vals, .. } => {
+                out.push('[');
+                for (i, val) in vals.iter().enumerate() {
+                    if out.len() > budget {
+                        return;
+                    }
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    write(val, depth - 1, budget, raw, out);
+                }
+                out.push(']');
+            }
+            Value::Record { val: rec, .. } => {
+                out.push('{');
+                for (i, (col, val)) in rec.iter().enumerate() {
+                    if out.len() > budget {
+                        return;
+                    }
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    out.push_str(&ansi::strip(col));
+                    out.push_str(": ");
+                    write(val, depth - 1, budget, raw, out);
+                }
+                out.push('}');
+            }
+            x => out.push_str(&repr_simple_value(x, budget, raw).data),
+        }
     }
+
+    let mut out = String::new();
+    write(value, depth, budget, raw, &mut out);
+    truncate(&out, preview_length)
 }
 
 /// compute the row / item representation of a complete Nushell Value
 ///
 /// > see the tests for detailed examples
-fn repr_data(data: &Value) -> Vec<DataRowRepr> {
+fn repr_data(data: &Value, preview_length: usize, depth: usize, raw: bool) -> Vec<DataRowRepr> {
     match data {
         Value::List { vals, .. } => {
             if vals.is_empty() {
@@ -158,9 +799,12 @@ fn repr_data(data: &Value) -> Vec<DataRowRepr> {
                     name: None,
                     shape: "list".into(),
                     data: "[]".into(),
+                    styled: None,
                 }]
             } else {
-                vals.iter().map(repr_value).collect::<Vec<DataRowRepr>>()
+                vals.iter()
+                    .map(|v| repr_value(v, preview_length, depth, raw))
+                    .collect::<Vec<DataRowRepr>>()
             }
         }
         Value::Record { val: rec, .. } => {
@@ -169,100 +813,709 @@ fn repr_data(data: &Value) -> Vec<DataRowRepr> {
                     name: None,
                     shape: "record".into(),
                     data: "{}".into(),
+                    styled: None,
                 }]
             } else {
                 rec.iter()
                     .map(|(col, val)| {
-                        let mut repr = repr_value(val);
-                        repr.name = Some(col.to_string());
+                        let mut repr = repr_value(val, preview_length, depth, raw);
+                        repr.name = Some(ansi::strip(col));
                         repr
                     })
                     .collect::<Vec<DataRowRepr>>()
             }
         }
-        value => vec![repr_simple_value(value)],
+        value => vec![repr_simple_value(value, preview_length, raw)],
     }
 }
 
-/// compute the representation of a complete Nushell table
+/// the text shown instead of a simple *value* by the *substitutions*, the ones of its *column*
+/// first, if it has one, see [`Substitutions`]
 ///
 /// > see the tests for detailed examples
-fn repr_table(table: &[Record]) -> (Vec<String>, Vec<String>, Vec<Vec<String>>) {
-    let mut shapes = vec![Type::Nothing; table[0].len()];
-
-    let mut rows = vec![vec![]; table.len()];
+fn substitution(
+    substitutions: &Substitutions,
+    column: Option<&str>,
+    value: &Value,
+) -> Option<String> {
+    if substitutions.is_empty() || matches!(value, Value::List { .. } | Value::Record { .. }) {
+        return None;
+    }
 
-    for (i, row) in table.iter().enumerate() {
-        for (j, col) in table[0].cols.iter().enumerate() {
-            // NOTE: because `table` is a valid table, this should always be a `Some`
-            let val = row.get(col).unwrap();
+    // FIXME: use a real config
+    let data = value.into_string(" ", &nu_protocol::Config::default());
+    let ty = value.get_type().to_string();
+    let rendering = [column, Some(ty.as_str())]
+        .into_iter()
+        .flatten()
+        .find_map(|name| substitutions.get(name)?.get(&data))
+        .cloned();
+    rendering
+}
 
-            let cell_type = val.get_type();
-            if !matches!(cell_type, Type::Nothing) {
-                if shapes[j].is_numeric() && cell_type.is_numeric() && (shapes[j] != cell_type) {
-                    shapes[j] = Type::Number;
-                } else {
-                    shapes[j] = cell_type;
-                }
-            }
+/// render the *rows* of a *value*, see [`repr_data`], with the *substitutions*, the *column* being
+/// the one of the value itself when it is a simple value, see [`substitution`]
+fn apply_substitutions(
+    rows: &mut [DataRowRepr],
+    value: &Value,
+    column: Option<&str>,
+    substitutions: &Substitutions,
+) {
+    if substitutions.is_empty() {
+        return;
+    }
 
-            rows[i].push(repr_value(val).data);
+    let renderings: Vec<Option<String>> = match value {
+        Value::List { vals, .. } => vals
+            .iter()
+            .map(|v| substitution(substitutions, None, v))
+            .collect(),
+        Value::Record { val, .. } => val
+            .iter()
+            .map(|(col, v)| substitution(substitutions, Some(col), v))
+            .collect(),
+        v => vec![substitution(substitutions, column, v)],
+    };
+    for (row, rendering) in rows.iter_mut().zip(renderings) {
+        if let Some(rendering) = rendering {
+            row.data = rendering;
+            row.styled = None;
         }
     }
-
-    (
-        table[0].cols.clone(),
-        shapes.iter().map(|s| s.to_string()).collect(),
-        rows,
-    )
 }
 
-/// render the whole data
-///
-/// the layout can be changed from [`crate::config::Config::layout`].
-///
-/// the data will be rendered on top of the bar, and on top of the cell path in case
-/// [`crate::config::Config::show_cell_path`] is set to `true`.
-fn render_data(frame: &mut Frame, app: &App, config: &Config) {
-    let data_frame_height = if config.show_cell_path {
-        frame.size().height - 2
-    } else {
-        frame.size().height - 1
-    };
-    let rect_without_bottom_bar = Rect::new(0, 0, frame.size().width, data_frame_height);
+/// the representation of a table: its columns, their shapes, its cells and whether each cell is
+/// missing, see [`repr_table`]
+type TableRepr = (Vec<String>, Vec<String>, Vec<Vec<String>>, Vec<Vec<bool>>);
 
-    let mut data_path = app.position.members.clone();
-    let current = if !app.is_at_bottom() {
-        data_path.pop()
-    } else {
-        None
-    };
+/// the representation of the last view that has been rendered
+///
+/// formatting all the rows or cells of a large view is the most expensive part of a frame, so
+/// they are only formatted again when the view is not the same anymore, i.e. when another view
+/// is rendered, when the data has been edited or reloaded or when the raw representation is
+/// toggled or the depth of the previews changed.
+pub(crate) struct ReprCache<T> {
+    /// the whole data, kept to know if it is still the same value
+    value: Rc<Value>,
+    /// the *cell path* to the view in the data
+    path: Vec<PathMember>,
+    raw: bool,
+    depth: usize,
+    repr: Rc<T>,
+}
 
-    let value = app
-        .value
-        .clone()
-        .follow_cell_path(&data_path, false)
-        .unwrap_or_else(|_| {
-            panic!(
-                "unexpected error when following {:?} in {}",
-                app.position.members,
-                app.value.into_string(" ", &nu_protocol::Config::default())
-            )
-        });
+impl<T> ReprCache<T> {
+    fn new(app: &App, path: &[PathMember], repr: T) -> Self {
+        Self {
+            value: app.value.clone(),
+            path: path.to_vec(),
+            raw: app.raw,
+            depth: app.preview_depth,
+            repr: Rc::new(repr),
+        }
+    }
 
-    let normal_name_style = Style::default()
+    /// the cached representation, if it is still the one of the view at *path* in the data of
+    /// the *app*
+    fn get(cache: &Option<Self>, app: &App, path: &[PathMember]) -> Option<Rc<T>> {
+        cache
+            .as_ref()
+            .filter(|cache| {
+                Rc::ptr_eq(&cache.value, &app.value)
+                    && cache.path == path
+                    && cache.raw == app.raw
+                    && cache.depth == app.preview_depth
+            })
+            .map(|cache| cache.repr.clone())
+    }
+}
+
+/// the representation of the last table that has been rendered, the styles of its cells and the
+/// natural widths of its columns, see [`column_widths`]
+pub(crate) type TableCache = ReprCache<(TableRepr, Vec<Vec<Style>>, Vec<u16>)>;
+
+/// the rows of the last list or record that has been rendered and the styles of their values
+pub(crate) type RowsCache = ReprCache<(Vec<DataRowRepr>, Vec<Style>)>;
+
+/// the representation of the table at *path* in the data, the styles of its cells and the
+/// widths of its columns, from the cache of the *app* when it is still valid
+fn cached_repr_table(
+    app: &mut App,
+    path: &[PathMember],
+    table: &[&Record],
+    config: &Config,
+) -> Rc<(TableRepr, Vec<Vec<Style>>, Vec<u16>)> {
+    if let Some(repr) = TableCache::get(&app.table_cache, app, path) {
+        return repr;
+    }
+
+    let mut repr = repr_table(
+        table,
+        config.display.preview_length,
+        app.preview_depth,
+        app.raw,
+    );
+    if !config.display.substitutions.is_empty() {
+        let columns = table_columns(table.iter().copied());
+        for (row, cells) in table.iter().zip(repr.2.iter_mut()) {
+            for (column, cell) in columns.iter().zip(cells.iter_mut()) {
+                let rendering = row
+                    .get(column)
+                    .and_then(|v| substitution(&config.display.substitutions, Some(column), v));
+                if let Some(rendering) = rendering {
+                    *cell = rendering;
+                }
+            }
+        }
+    }
+    let styles = table
+        .iter()
+        .map(|rec| {
+            repr.0
+                .iter()
+                .map(|col| match rec.get(col) {
+                    Some(val) => value_style(val, app, config),
+                    None => Style::default(),
+                })
+                .collect()
+        })
+        .collect();
+    let widths = column_widths(&repr.0, &repr.1, &repr.2);
+    let cache = TableCache::new(app, path, (repr, styles, widths));
+    let repr = cache.repr.clone();
+    app.table_cache = Some(cache);
+    repr
+}
+
+/// the rows of the list or record at *path* in the data and the styles of their values, from
+/// the cache of the *app* when it is still valid
+fn cached_repr_data(
+    app: &mut App,
+    path: &[PathMember],
+    value: &Value,
+    config: &Config,
+) -> Rc<(Vec<DataRowRepr>, Vec<Style>)> {
+    if let Some(repr) = RowsCache::get(&app.rows_cache, app, path) {
+        return repr;
+    }
+
+    let mut rows = repr_data(
+        value,
+        config.display.preview_length,
+        app.preview_depth,
+        app.raw,
+    );
+    let column = match path.last() {
+        Some(PathMember::String { val, .. }) => Some(val.as_str()),
+        _ => None,
+    };
+    apply_substitutions(&mut rows, value, column, &config.display.substitutions);
+    let styles = match value {
+        Value::List { vals, .. } => vals.iter().map(|v| value_style(v, app, config)).collect(),
+        Value::Record { val: rec, .. } => {
+            rec.values().map(|v| value_style(v, app, config)).collect()
+        }
+        _ => vec![],
+    };
+    let cache = RowsCache::new(app, path, (rows, styles));
+    let repr = cache.repr.clone();
+    app.rows_cache = Some(cache);
+    repr
+}
+
+/// compute the representation of a complete Nushell table
+///
+/// the columns are all the columns of the table, in order of first appearance. if a row does not
+/// have one of the columns, the cell will be a [`MISSING_CELL`] and will be flagged as missing,
+/// to be told apart from a string that looks the same.
+///
+/// > see the tests for detailed examples
+fn repr_table(table: &[&Record], preview_length: usize, depth: usize, raw: bool) -> TableRepr {
+    let columns = table_columns(table.iter().copied());
+
+    let mut shapes = vec![Type::Nothing; columns.len()];
+
+    let mut rows = vec![vec![]; table.len()];
+    let mut missing = vec![vec![false; columns.len()]; table.len()];
+
+    for (i, row) in table.iter().enumerate() {
+        for (j, col) in columns.iter().enumerate() {
+            let val = match row.get(col) {
+                Some(val) => val,
+                None => {
+                    rows[i].push(MISSING_CELL.into());
+                    missing[i][j] = true;
+                    continue;
+                }
+            };
+
+            let cell_type = val.get_type();
+            if !matches!(cell_type, Type::Nothing) {
+                if shapes[j].is_numeric() && cell_type.is_numeric() && (shapes[j] != cell_type) {
+                    shapes[j] = Type::Number;
+                } else {
+                    shapes[j] = cell_type;
+                }
+            }
+
+            rows[i].push(repr_value(val, preview_length, depth, raw).data);
+        }
+    }
+
+    (
+        columns.iter().map(|c| ansi::strip(c)).collect(),
+        shapes.iter().map(|s| s.to_string()).collect(),
+        rows,
+        missing,
+    )
+}
+
+/// compute the width of each column of a table, i.e. the display width of its widest cell,
+/// header included
+///
+/// > :bulb: **Note**  
+/// > the width is the number of terminal columns, e.g. `日本語` is 6 columns wide
+///
+/// > see the tests for detailed examples
+fn column_widths(columns: &[String], shapes: &[String], cells: &[Vec<String>]) -> Vec<u16> {
+    columns
+        .iter()
+        .zip(shapes)
+        .enumerate()
+        .map(|(j, (column, shape))| {
+            // NOTE: the header is rendered as `column (shape)`
+            let header = column.width() + shape.width() + 3;
+            cells
+                .iter()
+                .filter_map(|row| row.get(j))
+                .map(|cell| cell.width())
+                .fold(header, usize::max) as u16
+        })
+        .collect()
+}
+
+/// apply the *strategy* to the *natural* widths of the columns of a table, see
+/// [`column_widths`], in a table *available* terminal columns wide
+///
+/// - `auto` keeps the natural widths
+/// - `equal` gives all the columns the same width, to fit all of them if possible
+/// - the custom widths are applied in column order, the other columns sharing what is left
+///   equally
+///
+/// > :bulb: **Note**  
+/// > the columns are never narrower than [`MIN_COLUMN_WIDTH`], the table being scrolled
+/// > horizontally when they do not fit, see [`column_window`]
+///
+/// > see the tests for detailed examples
+fn strategy_widths(strategy: &ColumnWidth, natural: &[u16], available: u16) -> Vec<u16> {
+    let n = natural.len() as u16;
+    // NOTE: each column is separated from the next one by a space
+    let share = |width: u16, n: u16| (width / n.max(1)).saturating_sub(1).max(MIN_COLUMN_WIDTH);
+
+    match strategy {
+        ColumnWidth::Auto => natural.to_vec(),
+        ColumnWidth::Equal => vec![share(available, n); natural.len()],
+        ColumnWidth::Custom(specs) => {
+            let mut widths: Vec<Option<u16>> = (0..natural.len())
+                .map(|j| match specs.get(j) {
+                    Some(WidthSpec::Fixed(w)) => Some(*w),
+                    Some(WidthSpec::Percent(p)) => Some(
+                        ((available as u32 * *p as u32 / 100) as u16)
+                            .saturating_sub(1)
+                            .max(MIN_COLUMN_WIDTH),
+                    ),
+                    None => None,
+                })
+                .collect();
+
+            let used: u16 = widths.iter().flatten().map(|w| w.saturating_add(1)).sum();
+            let rest = widths.iter().filter(|w| w.is_none()).count() as u16;
+            let width = share(available.saturating_sub(used), rest);
+            for w in widths.iter_mut().filter(|w| w.is_none()) {
+                *w = Some(width);
+            }
+
+            widths.into_iter().flatten().collect()
+        }
+    }
+}
+
+/// the columns of a table to show in *width* terminal columns, as indices in *widths*, along
+/// with the new horizontal scroll offset
+///
+/// - the *pinned* column, if any, always comes first, followed by a separator, i.e. `None`
+/// - the other columns are shown in order from the *offset*-th one, the *offset* being moved
+///   just enough for the *selected* column to be visible
+/// - at least one column is shown, even if it does not fit
+///
+/// > :bulb: **Note**  
+/// > each column is separated from the next one by a space
+///
+/// > see the tests for detailed examples
+fn column_window(
+    widths: &[u16],
+    width: u16,
+    pinned: Option<usize>,
+    selected: Option<usize>,
+    offset: usize,
+) -> (Vec<Option<usize>>, usize) {
+    let cost = |j: &usize| widths[*j] as usize + 1;
+
+    let mut budget = width as usize;
+    let mut window = vec![];
+    if let Some(pinned) = pinned {
+        window.push(Some(pinned));
+        window.push(None);
+        budget = budget.saturating_sub(cost(&pinned) + PINNED_SEPARATOR.width() + 1);
+    }
+
+    let rest: Vec<usize> = (0..widths.len()).filter(|&j| Some(j) != pinned).collect();
+    if rest.is_empty() {
+        window.pop();
+        return (window, 0);
+    }
+    let fits = |range: &[usize]| range.iter().map(cost).sum::<usize>() <= budget;
+
+    let mut offset = offset.min(rest.len() - 1);
+    if let Some(s) = selected.and_then(|s| rest.iter().position(|&j| j == s)) {
+        offset = offset.min(s);
+        while offset < s && !fits(&rest[offset..=s]) {
+            offset += 1;
+        }
+    }
+    // NOTE: no room is left empty on the right when scrolling back is possible
+    while offset > 0 && fits(&rest[offset - 1..]) {
+        offset -= 1;
+    }
+
+    let mut end = offset + 1;
+    while end < rest.len() && fits(&rest[offset..=end]) {
+        end += 1;
+    }
+    window.extend(rest[offset..end].iter().map(|&j| Some(j)));
+
+    (window, offset)
+}
+
+/// compute the background of the row at *index* in the data, alternating between
+/// [`crate::config::ColorConfig::row`] and [`crate::config::ColorConfig::row_alt`]
+///
+/// > :bulb: **Note**  
+/// > the *index* is the one of the row in the whole data, not in the view, so that the stripes do
+/// > not move when scrolling
+fn row_background(config: &Config, index: usize) -> Color {
+    if index % 2 == 1 {
+        config.colors.row_alt
+    } else {
+        config.colors.row
+    }
+}
+
+/// give the background of the row to a *style* that does not have a background of its own
+///
+/// > see the tests for detailed examples
+fn stripe(style: Style, background: Color) -> Style {
+    match style.bg {
+        None | Some(Color::Reset) => style.bg(background),
+        Some(_) => style,
+    }
+}
+
+/// whether the row at *index*, with an optional *name* in a record, is one of the *marks* of
+/// the current view
+///
+/// > see the tests for detailed examples
+fn is_marked(marks: &[PathMember], index: usize, name: Option<&str>) -> bool {
+    marks.iter().any(|m| match m {
+        PathMember::Int { val, .. } => name.is_none() && *val == index,
+        PathMember::String { val, .. } => name == Some(val.as_str()),
+    })
+}
+
+/// the name of the *index*-th row of a view to look for in the marks, see [`is_marked`]
+///
+/// > :bulb: **Note**  
+/// > a duplicate column of a record is marked by its index, see
+/// > [`crate::nu::value::column_member`]
+fn mark_name<'a>(value: &Value, index: usize, name: Option<&'a str>) -> Option<&'a str> {
+    match value {
+        Value::Record { val: rec, .. } if index < rec.cols.len() => {
+            match column_member(rec, index) {
+                PathMember::Int { .. } => None,
+                PathMember::String { .. } => name,
+            }
+        }
+        _ => name,
+    }
+}
+
+/// compute the scroll offset of a view of *height* rows out of *nb_rows*
+///
+/// the last offset of the [`App`] is kept, unless the view needs to be centered around the
+/// *selected* row.
+///
+/// > see the tests for detailed examples
+fn view_offset(app: &App, selected: usize, nb_rows: usize, height: usize) -> usize {
+    if app.recenter {
+        selected
+            .saturating_sub(height / 2)
+            .min(nb_rows.saturating_sub(height))
+    } else {
+        app.offset
+    }
+}
+
+/// represent the *context* of a view, i.e. its key or its index in its parent, from the *cell
+/// path* to the view
+///
+/// > see the tests for detailed examples
+fn repr_context(members: &[PathMember]) -> String {
+    match members.last() {
+        Some(PathMember::Int { val, .. }) => format!(" item {} ", val),
+        Some(PathMember::String { val, .. }) => format!(" {} ", val),
+        None => " $ ".into(),
+    }
+}
+
+/// the block around the data, with the context of the view in its top line when
+/// [`crate::config::Config::show_context`] is set
+///
+/// > :bulb: **Note**  
+/// > without a top border, the context takes a line of its own, which has to be removed from
+/// > the height of the view when scrolling
+fn data_block<'a>(config: &Config, members: &[PathMember], borders: Borders) -> Block<'a> {
+    let block = Block::default().borders(borders);
+    if config.show_context {
+        block.title(Span::styled(
+            repr_context(members),
+            Style::default()
+                .fg(config.colors.normal.name.foreground)
+                .add_modifier(Modifier::BOLD),
+        ))
+    } else {
+        block
+    }
+}
+
+/// the number of siblings shown on each side of the current one in the context of a value at
+/// the bottom, see [`bottom_context`]
+const CONTEXT_SIBLINGS: usize = 3;
+
+/// the local context of a value at the bottom, i.e. a label describing its *parent* and the
+/// names of its siblings around the *current* member, the current one being flagged
+///
+/// only the keys or the length of the parent are used, its values are never formatted. the
+/// siblings too far from the current one are replaced by a `…`.
+///
+/// > see the tests for detailed examples
+fn bottom_context(parent: &Value, current: &PathMember) -> Option<(String, Vec<(String, bool)>)> {
+    let (label, len, position) = match (parent, current) {
+        (Value::Record { val, .. }, PathMember::String { val: key, .. }) => (
+            format!("record of {} fields", val.len()),
+            val.len(),
+            val.columns().position(|column| column == key)?,
+        ),
+        (Value::List { vals, .. }, PathMember::Int { val, .. }) => {
+            (format!("list of {} items", vals.len()), vals.len(), *val)
+        }
+        _ => return None,
+    };
+
+    let start = position.saturating_sub(CONTEXT_SIBLINGS);
+    let end = (position + CONTEXT_SIBLINGS + 1).min(len);
+    let names: Vec<String> = match parent {
+        Value::Record { val, .. } => val
+            .columns()
+            .skip(start)
+            .take(end - start)
+            .cloned()
+            .collect(),
+        _ => (start..end).map(|i| i.to_string()).collect(),
+    };
+
+    let mut siblings = vec![];
+    if start > 0 {
+        siblings.push(("…".to_string(), false));
+    }
+    siblings.extend(
+        names
+            .into_iter()
+            .zip(start..end)
+            .map(|(name, i)| (name, i == position)),
+    );
+    if end < len {
+        siblings.push(("…".to_string(), false));
+    }
+
+    Some((label, siblings))
+}
+
+/// render the whole data
+///
+/// the layout can be changed from [`crate::config::Config::layout`].
+///
+/// the data will be rendered in *area*, i.e. on top of the bar, and on top of the cell path in
+/// case [`crate::config::Config::show_cell_path`] is set to `true`, or in one of the panes of a
+/// split screen.
+fn render_data(frame: &mut Frame, app: &mut App, config: &Config, area: Rect) {
+    #[cfg(test)]
+    if PANIC_WHILE_RENDERING.with(|p| p.get()) {
+        panic!("a deliberate panic of the renderer");
+    }
+
+    let data_frame_height = area.height;
+    let rect_without_bottom_bar = area;
+
+    let mut data_path = app.position.members.clone();
+    let current = if !app.is_at_bottom() {
+        data_path.pop()
+    } else {
+        None
+    };
+
+    // NOTE: the value is shared, to be borrowed while the state of the app is updated
+    let root = app.value.clone();
+    let value = follow(&root, &data_path).unwrap_or_else(|| {
+        panic!(
+            "unexpected error when following {:?} in {}",
+            app.position.members,
+            app.value.into_string(" ", &nu_protocol::Config::default())
+        )
+    });
+    // NOTE: a range is shown as the list of its elements, unless it's the cell at the bottom
+    let value = if app.is_at_bottom() {
+        value
+    } else {
+        explorable(value)
+    };
+    let value = value.as_ref();
+
+    // NOTE: a missing cell can only be reached at the bottom, through an optional member
+    let is_missing = app.is_at_bottom()
+        && matches!(value, Value::Nothing { .. })
+        && matches!(
+            data_path.last(),
+            Some(
+                PathMember::Int { optional: true, .. } | PathMember::String { optional: true, .. }
+            )
+        );
+
+    let normal_name_style = Style::default()
         .fg(config.colors.normal.name.foreground)
         .bg(config.colors.normal.name.background);
     let normal_data_style = Style::default()
         .fg(config.colors.normal.data.foreground)
         .bg(config.colors.normal.data.background);
-    let normal_shape_style = Style::default()
+    let mut normal_shape_style = Style::default()
         .fg(config.colors.normal.shape.foreground)
         .bg(config.colors.normal.shape.background);
-    let highlight_style = Style::default()
-        .fg(config.colors.selected.foreground)
-        .bg(config.colors.selected.background)
-        .add_modifier(config.colors.selected_modifier);
+    let highlight_style = selected_style(config);
+    let mut marked_style = Style::default()
+        .fg(config.colors.marked.foreground)
+        .bg(config.colors.marked.background);
+    if config.display.monochrome {
+        normal_shape_style = normal_shape_style.add_modifier(Modifier::DIM);
+        marked_style = marked_style.add_modifier(Modifier::ITALIC);
+    }
+    let mark = |style: Style, marked: bool| {
+        if marked {
+            style.patch(marked_style)
+        } else {
+            style
+        }
+    };
+
+    let marks = if app.is_at_bottom() {
+        vec![]
+    } else {
+        app.marks_in_view()
+    };
+    // NOTE: the rows of the selected range look like marked ones
+    let range = app.range_in_view();
+    let in_range = |i: usize| range.is_some_and(|(start, end)| start <= i && i <= end);
+    let failures: Vec<usize> = match app.failures_in_view() {
+        Some(failures) if !app.is_at_bottom() => failures.iter().map(|(i, _)| *i).collect(),
+        _ => vec![],
+    };
+    let failure_style = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+    // NOTE: the matches of the last search are underlined, until the matches are cleared
+    let matches = if app.is_at_bottom() {
+        vec![]
+    } else {
+        app.matches_in_view()
+    };
+    let underline = |style: Style, matched: bool| {
+        if matched {
+            style.add_modifier(Modifier::UNDERLINED)
+        } else {
+            style
+        }
+    };
+
+    // NOTE: an empty view, e.g. when exploring nothing, is where new data starts to be typed
+    let empty = match value {
+        Value::Record { val, .. } if val.is_empty() => Some("record"),
+        Value::List { vals, .. } if vals.is_empty() => Some("list"),
+        _ => None,
+    };
+    if let (Some(empty), false) = (empty, app.is_at_bottom()) {
+        let hint = if config.read_only {
+            format!("empty {}", empty)
+        } else {
+            format!(
+                "empty {} — press {} to start editing",
+                empty,
+                repr_keycode(&config.keybindings.insert)
+            )
+        };
+        let borders = match config.layout {
+            Layout::Compact => Borders::NONE,
+            Layout::Table => Borders::ALL,
+        };
+        frame.render_widget(
+            Paragraph::new(Span::styled(hint, normal_shape_style))
+                .alignment(Alignment::Center)
+                .block(data_block(config, &data_path, borders)),
+            rect_without_bottom_bar,
+        );
+        return;
+    }
+
+    // NOTE: a simple value at the bottom is shown below the context of its parent, to not be
+    // alone on the screen
+    let context = match (data_path.split_last(), value) {
+        (_, Value::List { .. } | Value::Record { .. }) => None,
+        (Some((current, parent)), _)
+            if app.is_at_bottom() && config.display.bottom_context && area.height > 3 =>
+        {
+            follow(&root, parent)
+                .and_then(|parent| bottom_context(explorable(parent).as_ref(), current))
+        }
+        _ => None,
+    };
+    let (data_frame_height, rect_without_bottom_bar) = match context {
+        Some((label, siblings)) => {
+            let dim = Style::default().add_modifier(Modifier::DIM);
+            let mut spans = vec![Span::styled(format!("{}:", label), dim)];
+            for (name, is_current) in siblings {
+                spans.push(" ".into());
+                spans.push(if is_current {
+                    Span::styled(name, normal_name_style.add_modifier(Modifier::BOLD))
+                } else {
+                    Span::styled(name, dim)
+                });
+            }
+            frame.render_widget(
+                Paragraph::new(Line::from(spans)),
+                Rect::new(area.x, area.y, area.width, 1),
+            );
+            (
+                data_frame_height - 1,
+                Rect::new(area.x, area.y + 1, area.width, area.height - 1),
+            )
+        }
+        None => (data_frame_height, rect_without_bottom_bar),
+    };
 
     let selected = match current {
         Some(PathMember::Int { val, .. }) => val,
@@ -272,113 +1525,344 @@ fn render_data(frame: &mut Frame, app: &App, config: &Config) {
         None => 0,
     };
 
-    if is_table(&value) {
-        let (columns, shapes, cells) = match value {
+    // NOTE: a table is only checked and formatted again when it has changed since the last
+    // frame
+    let table = match TableCache::get(&app.table_cache, app, &data_path) {
+        Some(repr) => Some(repr),
+        None if is_ragged_table(value) => match value {
             Value::List { vals, .. } => {
+                // NOTE: the rows are only borrowed
                 let recs = vals
                     .iter()
-                    .map(|v| v.as_record().unwrap().clone())
-                    .collect::<Vec<Record>>();
-                repr_table(&recs)
+                    .map(|v| v.as_record().unwrap())
+                    .collect::<Vec<&Record>>();
+                Some(cached_repr_table(app, &data_path, &recs, config))
             }
             _ => panic!("value is a table but is not a list"),
-        };
+        },
+        None => None,
+    };
+    if let Some(repr) = table {
+        let (repr, styles, natural_widths) = (&repr.0, &repr.1, &repr.2);
+        let (columns, shapes, cells, missing) = (&repr.0, &repr.1, &repr.2, &repr.3);
+
+        // NOTE: the columns that do not fit are scrolled horizontally, with the pinned column
+        // at the left edge, the borders and the highlight symbol taking some room
+        let settings = app.table_settings(&data_path);
+        let pinned = settings
+            .pinned
+            .as_ref()
+            .and_then(|p| columns.iter().position(|c| c == p));
+        let available = area
+            .width
+            .saturating_sub(2 + config.colors.selected_symbol.width() as u16);
+        let all_widths = strategy_widths(&app.column_width, natural_widths, available);
+        let (window, column_offset) = column_window(
+            &all_widths,
+            available,
+            pinned,
+            app.column,
+            settings.column_offset,
+        );
+        if column_offset != settings.column_offset {
+            app.table_settings_mut(&data_path).column_offset = column_offset;
+        }
+        let separator = || Cell::from(PINNED_SEPARATOR).style(normal_shape_style);
 
-        let header = columns
+        let header = window
             .iter()
-            .zip(shapes)
-            .map(|(c, s)| {
-                let spans = vec![
-                    Span::styled(c, normal_name_style),
-                    " (".into(),
-                    Span::styled(s, normal_shape_style),
-                    ")".into(),
-                ];
-
-                Cell::from(Line::from(spans))
+            .map(|&j| match j {
+                Some(j) => {
+                    let spans = vec![
+                        Span::styled(&columns[j], normal_name_style),
+                        " (".into(),
+                        Span::styled(&shapes[j], normal_shape_style),
+                        ")".into(),
+                    ];
+
+                    Cell::from(Line::from(spans))
+                        .style(Style::default().add_modifier(config.colors.header_modifier))
+                }
+                None => separator(),
             })
             .collect::<Vec<Cell>>();
 
-        let widths = header
+        let widths = window
             .iter()
-            // FIXME: use an appropriate constraint here
-            .map(|_| Constraint::Min(25))
+            .map(|&j| match j {
+                // NOTE: the pinned column alone might be wider than the whole frame
+                Some(j) if app.column_width == ColumnWidth::Auto => {
+                    Constraint::Min(all_widths[j].min(available))
+                }
+                Some(j) => Constraint::Length(all_widths[j].min(available)),
+                None => Constraint::Length(PINNED_SEPARATOR.width() as u16),
+            })
             .collect::<Vec<Constraint>>();
 
         let header = Row::new(header).height(1);
 
-        let rows: Vec<Row> = cells
+        // NOTE: the borders and the header take three lines
+        let height = (data_frame_height as usize).saturating_sub(3);
+        let offset = view_offset(app, selected, cells.len(), height);
+        // NOTE: only the rows that are visible are turned into widgets, to not depend on the
+        // size of the table
+        let visible = visible_rows(offset, selected, cells.len(), height);
+
+        let rows: Vec<Row> = cells[visible.clone()]
             .iter()
-            .map(|r| Row::new(r.iter().cloned().map(Cell::from).collect::<Vec<Cell>>()))
+            .zip(visible.clone())
+            .map(|(r, i)| {
+                let marked = is_marked(&marks, i, None) || in_range(i);
+                let failed = failures.contains(&i);
+
+                Row::new(
+                    window
+                        .iter()
+                        .enumerate()
+                        .map(|(k, &j)| {
+                            let j = match j {
+                                Some(j) => j,
+                                None => return separator(),
+                            };
+                            let c = &r[j];
+                            if i == selected && app.column == Some(j) {
+                                Cell::from(c.as_str()).style(highlight_style)
+                            } else if missing[i][j] {
+                                Cell::from(c.as_str())
+                                    .style(Style::default().add_modifier(Modifier::DIM))
+                            } else if failed && k == 0 {
+                                let mut spans = vec![Span::styled(FAILURE_SYMBOL, failure_style)];
+                                if marked {
+                                    spans.push(config.colors.marked_symbol.clone().into());
+                                }
+                                spans.push(c.as_str().into());
+                                Cell::from(Line::from(spans)).style(styles[i][j])
+                            } else if marked && k == 0 {
+                                Cell::from(format!("{}{}", config.colors.marked_symbol, c))
+                                    .style(styles[i][j])
+                            } else {
+                                Cell::from(c.as_str()).style(styles[i][j])
+                            }
+                        })
+                        .collect::<Vec<Cell>>(),
+                )
+                .style(stripe(
+                    underline(mark(Style::default(), marked), is_marked(&matches, i, None)),
+                    row_background(config, i),
+                ))
+            })
             .collect();
 
+        // NOTE: only the cell under the cursor is highlighted when a column is selected
+        let row_highlight_style = match app.column {
+            Some(_) => Style::default(),
+            None => highlight_style,
+        };
+
         let table = Table::new(rows, widths)
             .header(header)
-            .block(Block::default().borders(Borders::ALL))
-            .highlight_style(highlight_style)
+            .block(data_block(config, &data_path, Borders::ALL))
+            .highlight_style(row_highlight_style)
             .highlight_symbol(config.colors.selected_symbol.clone());
 
-        frame.render_stateful_widget(
-            table,
-            rect_without_bottom_bar,
-            &mut TableState::default().with_selected(Some(selected)),
-        );
+        let mut state = TableState::default().with_selected(Some(selected - visible.start));
+        frame.render_stateful_widget(table, rect_without_bottom_bar, &mut state);
+        app.offset = visible.start + state.offset();
 
         return;
     }
 
+    // NOTE: the rows and the styles of the values are only computed again when the view has
+    // changed, before the state of the app is borrowed to build the rows
+    let cached = cached_repr_data(app, &data_path, value, config);
+    let styles = &cached.1;
+    let style_of = |i: usize| styles.get(i).copied().unwrap_or_default();
+
+    // NOTE: the runs of identical values are only computed around the selected element, to not
+    // depend on the length of the list
+    let runs = match value {
+        Value::List { vals, .. } if config.display.collapse_runs && !is_missing => {
+            Some(runs_around(vals, selected, data_frame_height as usize))
+        }
+        _ => None,
+    };
+    // NOTE: with collapsed runs, the rows of the view are the runs, identified by their first
+    // element
+    let element = |i: usize| match &runs {
+        Some((runs, _)) => runs[i].start,
+        None => i,
+    };
+    let rows: Cow<[DataRowRepr]> = match (&runs, value) {
+        _ if is_missing => Cow::Owned(vec![repr_missing()]),
+        (Some((runs, _)), Value::List { vals, .. }) => Cow::Owned(
+            runs.iter()
+                .map(|run| {
+                    let length = config.display.preview_length;
+                    repr_run(vals, run, selected, length, app.preview_depth, app.raw)
+                })
+                .collect(),
+        ),
+        _ => Cow::Borrowed(&cached.0),
+    };
+    let (selected, offset) = match &runs {
+        Some((runs, selected_run)) => {
+            let height = data_frame_height as usize;
+            let offset = selected_run
+                .saturating_sub(height / 2)
+                .min(runs.len().saturating_sub(height));
+            (*selected_run, Some(offset))
+        }
+        None => (selected, None),
+    };
+
     match config.layout {
         Layout::Compact => {
-            let items: Vec<ListItem> = repr_data(&value)
+            // NOTE: the context takes the first line, above the items
+            let height = if config.show_context {
+                (data_frame_height as usize).saturating_sub(1)
+            } else {
+                data_frame_height as usize
+            };
+            let offset = offset.unwrap_or(view_offset(app, selected, rows.len(), height));
+            // NOTE: only the rows that are visible are turned into widgets, to not depend on the
+            // size of the view
+            let window = visible_rows(offset, selected, rows.len(), height);
+
+            // NOTE: the keys are only aligned on the rows that are visible, to not depend on the
+            // size of the record
+            let key_width = rows[window.clone()]
+                .iter()
+                .filter_map(|row| row.name.as_ref())
+                .map(|name| name.width())
+                .max()
+                .unwrap_or(0)
+                .min(MAX_KEY_WIDTH);
+
+            let items: Vec<ListItem> = rows[window.clone()]
                 .iter()
                 .cloned()
-                .map(|row| {
+                .zip(window.clone())
+                .map(|(row, i)| {
+                    let background = row_background(config, i);
+                    let name = mark_name(value, i, row.name.as_deref());
+                    let marked = is_marked(&marks, element(i), name) || in_range(element(i));
+                    let matched = is_marked(&matches, element(i), name);
+
                     let mut spans = vec![];
+                    if failures.contains(&element(i)) {
+                        spans.push(Span::styled(
+                            FAILURE_SYMBOL,
+                            stripe(failure_style, background),
+                        ));
+                    }
+                    if marked {
+                        spans.push(Span::styled(
+                            config.colors.marked_symbol.clone(),
+                            stripe(marked_style, background),
+                        ));
+                    }
                     if let Some(name) = row.name {
-                        spans.push(Span::styled(name, normal_name_style));
+                        spans.push(Span::styled(
+                            align_key(&name, key_width),
+                            stripe(mark(normal_name_style, marked), background),
+                        ));
                         spans.push(": ".into());
                     }
                     spans.push("(".into());
-                    spans.push(Span::styled(row.shape, normal_shape_style));
+                    spans.push(Span::styled(
+                        row.shape,
+                        stripe(mark(normal_shape_style, marked), background),
+                    ));
                     spans.push(") ".into());
-                    spans.push(Span::styled(row.data, normal_data_style));
-
-                    ListItem::new(Line::from(spans))
+                    spans.extend(data_spans(
+                        row.data,
+                        row.styled,
+                        stripe(
+                            underline(
+                                mark(normal_data_style.patch(style_of(element(i))), marked),
+                                matched,
+                            ),
+                            background,
+                        ),
+                        config.display.render_ansi,
+                    ));
+
+                    ListItem::new(Line::from(spans)).style(Style::default().bg(background))
                 })
                 .collect();
 
+            let mut state = ListState::default().with_selected(Some(selected - window.start));
+
             let items = List::new(items)
+                .block(data_block(config, &data_path, Borders::NONE))
                 .highlight_style(highlight_style)
                 .highlight_symbol(&config.colors.selected_symbol);
 
-            frame.render_stateful_widget(
-                items,
-                rect_without_bottom_bar,
-                &mut ListState::default().with_selected(Some(selected)),
-            )
+            frame.render_stateful_widget(items, rect_without_bottom_bar, &mut state);
+            app.offset = window.start + state.offset();
         }
         Layout::Table => {
+            // NOTE: the borders take two lines, and the header another one
+            let height = if config.show_table_header {
+                (data_frame_height as usize).saturating_sub(3)
+            } else {
+                (data_frame_height as usize).saturating_sub(2)
+            };
+            let offset = offset.unwrap_or(view_offset(app, selected, rows.len(), height));
+            // NOTE: only the rows that are visible are turned into widgets, to not depend on the
+            // size of the view
+            let window = visible_rows(offset, selected, rows.len(), height);
+
             let (header, rows, constraints) = match value {
                 Value::List { .. } => {
                     let header = Row::new(vec![
                         Cell::from("item")
-                            .style(normal_data_style.add_modifier(Modifier::REVERSED)),
+                            .style(normal_data_style.add_modifier(config.colors.header_modifier)),
                         Cell::from("shape")
-                            .style(normal_shape_style.add_modifier(Modifier::REVERSED)),
+                            .style(normal_shape_style.add_modifier(config.colors.header_modifier)),
                     ]);
-                    let rows: Vec<Row> = repr_data(&value)
+                    let rows: Vec<Row> = rows[window.clone()]
                         .iter()
                         .cloned()
-                        .map(|row| {
+                        .zip(window.clone())
+                        .map(|(row, i)| {
+                            let background = row_background(config, i);
+                            let marked =
+                                is_marked(&marks, element(i), None) || in_range(element(i));
+                            let matched = is_marked(&matches, element(i), None);
+
                             let data_style = match row.data.as_str() {
                                 "record" | "list" => normal_data_style.add_modifier(Modifier::DIM),
-                                _ => normal_data_style,
+                                _ => normal_data_style.patch(style_of(element(i))),
                             };
+                            let data_style =
+                                stripe(underline(mark(data_style, marked), matched), background);
+                            let mut data = vec![];
+                            if failures.contains(&element(i)) {
+                                data.push(Span::styled(
+                                    FAILURE_SYMBOL,
+                                    stripe(failure_style, background),
+                                ));
+                            }
+                            if marked {
+                                data.push(Span::styled(
+                                    config.colors.marked_symbol.clone(),
+                                    data_style,
+                                ));
+                            }
+                            data.extend(data_spans(
+                                row.data,
+                                row.styled,
+                                data_style,
+                                config.display.render_ansi,
+                            ));
 
                             Row::new(vec![
-                                Cell::from(row.data).style(data_style),
-                                Cell::from(row.shape).style(normal_shape_style),
+                                Cell::from(Line::from(data)).style(data_style),
+                                Cell::from(row.shape)
+                                    .style(stripe(mark(normal_shape_style, marked), background)),
                             ])
+                            .style(Style::default().bg(background))
                         })
                         .collect();
 
@@ -388,27 +1872,51 @@ fn render_data(frame: &mut Frame, app: &App, config: &Config) {
                 }
                 Value::Record { .. } => {
                     let header = Row::new(vec![
-                        Cell::from("key").style(normal_name_style.add_modifier(Modifier::REVERSED)),
+                        Cell::from("key")
+                            .style(normal_name_style.add_modifier(config.colors.header_modifier)),
                         Cell::from("field")
-                            .style(normal_data_style.add_modifier(Modifier::REVERSED)),
+                            .style(normal_data_style.add_modifier(config.colors.header_modifier)),
                         Cell::from("shape")
-                            .style(normal_shape_style.add_modifier(Modifier::REVERSED)),
+                            .style(normal_shape_style.add_modifier(config.colors.header_modifier)),
                     ]);
 
-                    let rows: Vec<Row> = repr_data(&value)
+                    let rows: Vec<Row> = rows[window.clone()]
                         .iter()
                         .cloned()
-                        .map(|row| {
+                        .zip(window.clone())
+                        .map(|(row, i)| {
+                            let background = row_background(config, i);
+                            let name = mark_name(value, i, row.name.as_deref());
+                            let marked = is_marked(&marks, i, name);
+                            let matched = is_marked(&matches, i, name);
+
                             let data_style = match row.data.as_str() {
                                 "record" | "list" => normal_data_style.add_modifier(Modifier::DIM),
-                                _ => normal_data_style,
+                                _ => normal_data_style.patch(style_of(i)),
+                            };
+                            let name = row.name.unwrap_or("".into());
+                            let name = if marked {
+                                format!("{}{}", config.colors.marked_symbol, name)
+                            } else {
+                                name
                             };
 
                             Row::new(vec![
-                                Cell::from(row.name.unwrap_or("".into())).style(normal_name_style),
-                                Cell::from(row.data).style(data_style),
-                                Cell::from(row.shape).style(normal_shape_style),
+                                Cell::from(name).style(stripe(
+                                    underline(mark(normal_name_style, marked), matched),
+                                    background,
+                                )),
+                                Cell::from(Line::from(data_spans(
+                                    row.data,
+                                    row.styled,
+                                    stripe(mark(data_style, marked), background),
+                                    config.display.render_ansi,
+                                )))
+                                .style(stripe(mark(data_style, marked), background)),
+                                Cell::from(row.shape)
+                                    .style(stripe(mark(normal_shape_style, marked), background)),
                             ])
+                            .style(Style::default().bg(background))
                         })
                         .collect();
 
@@ -421,16 +1929,41 @@ fn render_data(frame: &mut Frame, app: &App, config: &Config) {
                     (header, rows, constraints)
                 }
                 v => {
-                    let repr = repr_simple_value(&v);
-                    let spans = vec![
-                        Span::styled(repr.data, normal_data_style),
-                        " is of shape ".into(),
-                        Span::styled(repr.shape, normal_shape_style),
-                    ];
+                    let spans = if is_missing {
+                        let repr = repr_missing();
+                        vec![
+                            Span::styled(repr.data, normal_data_style.add_modifier(Modifier::DIM)),
+                            " is a ".into(),
+                            Span::styled(repr.shape, normal_shape_style),
+                        ]
+                    } else {
+                        // NOTE: the whole value is shown at the bottom, wrapped, so it's never
+                        // truncated
+                        let mut repr = repr_simple_value(v, 0, app.raw);
+                        let column = match data_path.last() {
+                            Some(PathMember::String { val, .. }) => Some(val.as_str()),
+                            _ => None,
+                        };
+                        if let Some(rendering) =
+                            substitution(&config.display.substitutions, column, v)
+                        {
+                            repr.data = rendering;
+                            repr.styled = None;
+                        }
+                        let mut spans = data_spans(
+                            repr.data,
+                            repr.styled,
+                            normal_data_style.patch(value_style(v, app, config)),
+                            config.display.render_ansi,
+                        );
+                        spans.push(" is of shape ".into());
+                        spans.push(Span::styled(repr.shape, normal_shape_style));
+                        spans
+                    };
 
                     frame.render_widget(
                         Paragraph::new(Line::from(spans))
-                            .block(Block::default().borders(Borders::ALL))
+                            .block(data_block(config, &data_path, Borders::ALL))
                             .wrap(Wrap { trim: false }),
                         rect_without_bottom_bar,
                     );
@@ -438,20 +1971,19 @@ fn render_data(frame: &mut Frame, app: &App, config: &Config) {
                 }
             };
 
+            let mut state = TableState::default().with_selected(Some(selected - window.start));
+
             let table = if config.show_table_header {
                 Table::new(rows, constraints).header(header.height(1))
             } else {
                 Table::new(rows, constraints)
             }
-            .block(Block::default().borders(Borders::ALL))
+            .block(data_block(config, &data_path, Borders::ALL))
             .highlight_style(highlight_style)
             .highlight_symbol(config.colors.selected_symbol.clone());
 
-            frame.render_stateful_widget(
-                table,
-                rect_without_bottom_bar,
-                &mut TableState::default().with_selected(Some(selected)),
-            )
+            frame.render_stateful_widget(table, rect_without_bottom_bar, &mut state);
+            app.offset = window.start + state.offset();
         }
     }
 }
@@ -473,33 +2005,317 @@ fn render_data(frame: &mut Frame, app: &App, config: &Config) {
 /// ```text
 /// ||cell path: $.foo.bar.2.baz    ...||
 /// ```
+/// - with optional members, e.g. in a ragged table, the members are suffixed with `?`
+/// ```text
+/// ||cell path: $.foo.2.baz?       ...||
+/// ```
+/// - with a split screen, the *cell path* of each pane is under it, the focused one in bold
 fn render_cell_path(frame: &mut Frame, app: &App) {
     let next_to_bottom_bar_rect = Rect::new(0, frame.size().height - 2, frame.size().width, 1);
-    let cell_path = format!(
-        "cell path: $.{}",
-        app.position
-            .members
-            .iter()
+    let cell_path = format!("cell path: {}", repr_cell_path(&app.position.members));
+
+    let split = match &app.split {
+        Some(split) => split,
+        None => {
+            frame.render_widget(
+                Paragraph::new(cell_path).alignment(Alignment::Left),
+                next_to_bottom_bar_rect,
+            );
+            return;
+        }
+    };
+
+    let other = format!(
+        "cell path: {}",
+        repr_cell_path(&split.other_position().members)
+    );
+    let focused = Span::styled(cell_path, Style::default().add_modifier(Modifier::BOLD));
+    let other = Span::raw(other);
+    let (left, right) = if split.right {
+        (other, focused)
+    } else {
+        (focused, other)
+    };
+
+    let half = next_to_bottom_bar_rect.width / 2;
+    let (left_rect, right_rect) = (
+        Rect::new(0, next_to_bottom_bar_rect.y, half, 1),
+        Rect::new(
+            half,
+            next_to_bottom_bar_rect.y,
+            next_to_bottom_bar_rect.width - half,
+            1,
+        ),
+    );
+    frame.render_widget(Paragraph::new(Line::from(left)), left_rect);
+    frame.render_widget(Paragraph::new(Line::from(right)), right_rect);
+}
+
+/// represent a cell path as a string, e.g. `$.foo.0.bar?`
+pub(crate) fn repr_cell_path(members: &[PathMember]) -> String {
+    format!(
+        "$.{}",
+        members
+            .iter()
             .map(|m| {
                 match m {
-                    PathMember::Int { val, .. } => val.to_string(),
-                    PathMember::String { val, .. } => val.to_string(),
+                    PathMember::Int { val, optional, .. } => {
+                        format!("{}{}", val, if *optional { "?" } else { "" })
+                    }
+                    PathMember::String { val, optional, .. } => {
+                        format!("{}{}", val, if *optional { "?" } else { "" })
+                    }
                 }
             })
             .collect::<Vec<String>>()
             .join(".")
+    )
+}
+
+/// render the matches of a deep search in a popup, on top of the data
+///
+/// each match is shown as its full cell path followed by a snippet of the matching value.
+fn render_matches(frame: &mut Frame, app: &App, config: &Config) {
+    let width = frame.size().width * 4 / 5;
+    let height = (app.matches.len() as u16 + 2).min(frame.size().height.saturating_sub(4));
+    let area = Rect::new(
+        (frame.size().width - width) / 2,
+        (frame.size().height.saturating_sub(height + 2)) / 2,
+        width,
+        height,
     );
 
-    frame.render_widget(
-        Paragraph::new(cell_path).alignment(Alignment::Left),
-        next_to_bottom_bar_rect,
+    let items: Vec<ListItem> = app
+        .matches
+        .iter()
+        .map(|m| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    repr_cell_path(&m.path),
+                    Style::default().fg(config.colors.normal.name.foreground),
+                ),
+                ": ".into(),
+                Span::styled(
+                    m.snippet.clone(),
+                    Style::default().fg(config.colors.normal.data.foreground),
+                ),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("{} matches", app.matches.len()))
+                .style(
+                    Style::default()
+                        .fg(config.colors.editor.frame.foreground)
+                        .bg(config.colors.editor.frame.background),
+                ),
+        )
+        .highlight_style(selected_style(config))
+        .highlight_symbol(&config.colors.selected_symbol);
+
+    let mut state = ListState::default().with_selected(Some(app.selected_match));
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// render the differences of a diff in a popup, on top of the data
+///
+/// each difference is shown side by side, as its cell path relative to the two elements, the
+/// base element and the other element. removed, added and changed elements are colored in red,
+/// green and yellow respectively.
+fn render_differences(frame: &mut Frame, app: &App, config: &Config) {
+    let width = frame.size().width * 4 / 5;
+    let height = (app.differences.len() as u16 + 3).min(frame.size().height.saturating_sub(4));
+    let area = Rect::new(
+        (frame.size().width - width) / 2,
+        (frame.size().height.saturating_sub(height + 2)) / 2,
+        width,
+        height,
+    );
+
+    let name_style = Style::default().fg(config.colors.normal.name.foreground);
+    let rows: Vec<Row> = app
+        .differences
+        .iter()
+        .map(|d| {
+            let path = if d.path.is_empty() {
+                "$".to_string()
+            } else {
+                repr_cell_path(&d.path)
+            };
+            let (base, other) = match &d.change {
+                Change::Removed(base) => (
+                    Span::styled(base.clone(), Style::default().fg(Color::Red)),
+                    Span::raw(""),
+                ),
+                Change::Added(other) => (
+                    Span::raw(""),
+                    Span::styled(other.clone(), Style::default().fg(Color::Green)),
+                ),
+                Change::Changed(base, other) => (
+                    Span::styled(base.clone(), Style::default().fg(Color::Yellow)),
+                    Span::styled(other.clone(), Style::default().fg(Color::Yellow)),
+                ),
+            };
+            Row::new(vec![
+                Cell::from(Span::styled(path, name_style)),
+                Cell::from(base),
+                Cell::from(other),
+            ])
+        })
+        .collect();
+
+    let title = if app.differences.len() >= config.diff.max_differences {
+        format!("first {} differences", app.differences.len())
+    } else {
+        format!("{} differences", app.differences.len())
+    };
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(30),
+            Constraint::Percentage(35),
+            Constraint::Percentage(35),
+        ],
+    )
+    .header(Row::new(vec!["path", "base", "other"]).style(name_style.add_modifier(Modifier::BOLD)))
+    .block(
+        Block::default().borders(Borders::ALL).title(title).style(
+            Style::default()
+                .fg(config.colors.editor.frame.foreground)
+                .bg(config.colors.editor.frame.background),
+        ),
+    )
+    .highlight_style(selected_style(config));
+
+    let mut state = TableState::default().with_selected(Some(app.selected_difference));
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+/// render the statistics of a column in a small popup, on top of the data
+///
+/// the numbers are shown in the unit of the column, e.g. filesizes or durations.
+fn render_stats(frame: &mut Frame, stats: &ColumnStats, config: &Config) {
+    let name_style = Style::default().fg(config.colors.normal.name.foreground);
+    let rows: Vec<Row> = [
+        ("count", stats.count.to_string()),
+        ("skipped", stats.skipped.to_string()),
+        ("min", stats.repr(stats.min, true)),
+        ("max", stats.repr(stats.max, true)),
+        ("mean", stats.repr(stats.mean, false)),
+        ("median", stats.repr(stats.median, false)),
+        ("sum", stats.repr(stats.sum, true)),
+    ]
+    .into_iter()
+    .map(|(name, value)| {
+        Row::new(vec![
+            Cell::from(Span::styled(name, name_style)),
+            Cell::from(value),
+        ])
+    })
+    .collect();
+
+    let width = (frame.size().width / 2).max(30).min(frame.size().width);
+    let height = (rows.len() as u16 + 2).min(frame.size().height);
+    let area = Rect::new(
+        (frame.size().width - width) / 2,
+        (frame.size().height.saturating_sub(height + 2)) / 2,
+        width,
+        height,
+    );
+
+    let table = Table::new(rows, [Constraint::Length(8), Constraint::Min(0)]).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("stats of `{}`", stats.column))
+            .style(
+                Style::default()
+                    .fg(config.colors.editor.frame.foreground)
+                    .bg(config.colors.editor.frame.background),
+            ),
+    );
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(table, area);
+}
+
+/// render where the value under the cursor comes from in a popup, on top of the data
+///
+/// the lines of the source that are part of the span of the value are in bold, the others are
+/// only there for context.
+fn render_info(frame: &mut Frame, info: &SourceInfo, config: &Config) {
+    let name_style = Style::default().fg(config.colors.normal.name.foreground);
+    let span = match info.span {
+        Some(span) => format!("{}..{}", span.start, span.end),
+        None => "unknown".into(),
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("type ", name_style),
+            info.ty.clone().into(),
+        ]),
+        Line::from(vec![Span::styled("span ", name_style), span.into()]),
+        Line::from(""),
+    ];
+    if let Some(note) = &info.note {
+        lines.push(Line::from(Span::styled(
+            note.clone(),
+            Style::default().add_modifier(Modifier::ITALIC),
+        )));
+    }
+    let number_width = info
+        .lines
+        .last()
+        .map_or(0, |line| line.number.to_string().len());
+    for line in &info.lines {
+        let style = if line.in_span {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().add_modifier(Modifier::DIM)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:>width$} │ ", line.number, width = number_width),
+                name_style,
+            ),
+            Span::styled(line.text.clone(), style),
+        ]));
+    }
+
+    let width = (frame.size().width * 3 / 4).max(30).min(frame.size().width);
+    let height = (lines.len() as u16 + 2).min(frame.size().height);
+    let area = Rect::new(
+        (frame.size().width - width) / 2,
+        (frame.size().height.saturating_sub(height + 2)) / 2,
+        width,
+        height,
     );
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default().borders(Borders::ALL).title("info").style(
+            Style::default()
+                .fg(config.colors.editor.frame.foreground)
+                .bg(config.colors.editor.frame.background),
+        ),
+    );
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
 }
 
 /// render the status bar at the bottom
 ///
 /// the bar takes the last line of the TUI only and renders, from left to right
-/// - the current mode
+/// - the current mode, in a bold and reversed *lozenge*
 /// - hints about next bindings to press and actions to do
 ///
 /// the color depending of the mode is completely configurable!
@@ -522,6 +2338,10 @@ fn render_cell_path(frame: &mut Frame, app: &App) {
 /// ```text
 /// ||PEEKING ... <esc> to NORMAL | a to peek all | c to peek current view | u to peek under cursor | q to quit||
 /// ```
+/// - in GOTO mode
+/// ```text
+/// ||GOTO    ...                                   <esc> to NORMAL | type an index or a key | ⏎ to jump||
+/// ```
 fn render_status_bar(frame: &mut Frame, app: &App, config: &Config) {
     let bottom_bar_rect = Rect::new(0, frame.size().height - 1, frame.size().width, 1);
 
@@ -530,6 +2350,9 @@ fn render_status_bar(frame: &mut Frame, app: &App, config: &Config) {
         Mode::Insert => Style::default().bg(config.colors.status_bar.insert.background),
         Mode::Peeking => Style::default().bg(config.colors.status_bar.peek.background),
         Mode::Bottom => Style::default().bg(config.colors.status_bar.bottom.background),
+        Mode::Goto => Style::default().bg(config.colors.status_bar.goto.background),
+        Mode::Search => Style::default().bg(config.colors.status_bar.search.background),
+        Mode::Command => Style::default().bg(config.colors.status_bar.command.background),
     };
 
     let style = match app.mode {
@@ -537,19 +2360,26 @@ fn render_status_bar(frame: &mut Frame, app: &App, config: &Config) {
         Mode::Insert => bg_style.fg(config.colors.status_bar.insert.foreground),
         Mode::Peeking => bg_style.fg(config.colors.status_bar.peek.foreground),
         Mode::Bottom => bg_style.fg(config.colors.status_bar.bottom.foreground),
+        Mode::Goto => bg_style.fg(config.colors.status_bar.goto.foreground),
+        Mode::Search => bg_style.fg(config.colors.status_bar.search.foreground),
+        Mode::Command => bg_style.fg(config.colors.status_bar.command.foreground),
     };
 
     let hints = match app.mode {
         Mode::Normal => format!(
-            "{} to {} | {}{}{}{} to move around | {} to peek | {} to transpose | {} to quit",
+            "{} to {} | {}{}{}{} to move around | {} to mark | {} to peek | {} to peek and quit | {} to transpose | {} to go to | {} to search | {} to quit",
             repr_keycode(&config.keybindings.insert),
             Mode::Insert,
             repr_keycode(&config.keybindings.navigation.left),
             repr_keycode(&config.keybindings.navigation.down),
             repr_keycode(&config.keybindings.navigation.up),
             repr_keycode(&config.keybindings.navigation.right),
+            repr_keycode(&config.keybindings.mark),
             repr_keycode(&config.keybindings.peek),
+            repr_keycode(&config.keybindings.peek_under_quick),
             repr_keycode(&config.keybindings.transpose),
+            repr_keycode(&config.keybindings.goto),
+            repr_keycode(&config.keybindings.search),
             repr_keycode(&config.keybindings.quit),
         ),
         Mode::Insert => format!(
@@ -564,27 +2394,136 @@ fn render_status_bar(frame: &mut Frame, app: &App, config: &Config) {
             repr_keycode(&KeyCode::Enter),
         ),
         Mode::Peeking => format!(
-            "{} to {} | {} to peek all | {} to peek current view | {} to peek under cursor | {} to peek the cell path",
+            "{} to {} | {} to peek all | {} to peek current view | {} to peek under cursor | {} to peek the cell path | {} to peek the marks | {} to peek the screen",
             repr_keycode(&config.keybindings.normal),
             Mode::Normal,
             repr_keycode(&config.keybindings.peeking.all),
             repr_keycode(&config.keybindings.peeking.view),
             repr_keycode(&config.keybindings.peeking.under),
             repr_keycode(&config.keybindings.peeking.cell_path),
+            repr_keycode(&config.keybindings.peeking.marks),
+            repr_keycode(&config.keybindings.peeking.screen),
         ),
         Mode::Bottom => format!(
-            "{} to {} | {} to peek | {} to quit",
+            "{} to {} | {} to peek | {} to toggle raw | {} to quit",
             repr_keycode(&config.keybindings.navigation.left),
             Mode::Normal,
             repr_keycode(&config.keybindings.peek),
+            repr_keycode(&config.keybindings.raw),
             repr_keycode(&config.keybindings.quit),
         ),
+        Mode::Goto => format!(
+            "{} to {} | type an index or a key | {} to jump",
+            repr_keycode(&config.keybindings.normal),
+            Mode::Normal,
+            repr_keycode(&KeyCode::Enter),
+        ),
+        Mode::Search if app.matches.is_empty() => format!(
+            "{} to {} | type something to search | {} to search",
+            repr_keycode(&config.keybindings.normal),
+            Mode::Normal,
+            repr_keycode(&KeyCode::Enter),
+        ),
+        Mode::Search => format!(
+            "{} to {} | {}{} to pick a match | {} to jump",
+            repr_keycode(&config.keybindings.normal),
+            Mode::Normal,
+            repr_keycode(&config.keybindings.navigation.down),
+            repr_keycode(&config.keybindings.navigation.up),
+            repr_keycode(&KeyCode::Enter),
+        ),
+        Mode::Command => format!(
+            "{} to {} | type a command, e.g. `write file.json` or `write! file.json` | {} to run",
+            repr_keycode(&config.keybindings.normal),
+            Mode::Normal,
+            repr_keycode(&KeyCode::Enter),
+        ),
     };
 
-    let left = Line::from(Span::styled(
+    let mut left = vec![Span::styled(
         format!(" {} ", app.mode),
-        style.add_modifier(Modifier::REVERSED),
-    ));
+        style.add_modifier(Modifier::REVERSED | Modifier::BOLD),
+    )];
+    if config.read_only {
+        left.push(Span::styled(" [RO]", style.add_modifier(Modifier::BOLD)));
+    }
+    match app.parsed.last().map(|view| &view.grouped_by) {
+        Some(Some(column)) => left.push(Span::styled(
+            format!(" [grouped by {}]", column),
+            style.add_modifier(Modifier::BOLD),
+        )),
+        Some(None) => left.push(Span::styled(
+            format!(" [parsed {}]", repr_cell_path(&app.root_position().members)),
+            style.add_modifier(Modifier::BOLD),
+        )),
+        None => {}
+    }
+    let nb_marks = app.marks_in_view().len();
+    if nb_marks > 0 {
+        left.push(Span::styled(format!(" {} marked", nb_marks), style));
+    }
+    if let Some((start, end)) = app.range_in_view() {
+        left.push(Span::styled(
+            format!(" rows {}–{} selected", start, end),
+            style.add_modifier(Modifier::BOLD),
+        ));
+    }
+    if app.raw {
+        left.push(Span::styled(" raw", style));
+    }
+    if let Some((register, _)) = &app.recording {
+        left.push(Span::styled(
+            format!(" recording @{}", register),
+            style.add_modifier(Modifier::BOLD),
+        ));
+    }
+    if app.step > 1 {
+        left.push(Span::styled(format!(" ×{}", app.step), style));
+    }
+    if app.diff_base.is_some() {
+        left.push(Span::styled(" diffing", style));
+    }
+    if let Some(failures) = app.failures_in_view() {
+        let failed = match app.position.members.last() {
+            Some(PathMember::Int { val, .. }) => failures.iter().find(|(i, _)| i == val),
+            _ => None,
+        };
+        let failure_style = bg_style.fg(Color::Red).add_modifier(Modifier::BOLD);
+        if !failures.is_empty() {
+            left.push(Span::styled(
+                format!(" {} failing", failures.len()),
+                failure_style,
+            ));
+        }
+        // NOTE: the reason is hidden by a status message, e.g. after jumping to the row
+        if let (Some((_, reason)), None) = (failed, &app.status_message) {
+            left.push(Span::styled(format!(" {}", reason), failure_style));
+        }
+    }
+    if !app.matches.is_empty() && app.mode != Mode::Search {
+        left.push(Span::styled(
+            format!(" match {}/{}", app.selected_match + 1, app.matches.len()),
+            style,
+        ));
+    }
+    match &app.status_message {
+        Some(StatusMessage::Info(msg)) => left.push(Span::styled(
+            format!(" {}", msg),
+            style.add_modifier(Modifier::BOLD),
+        )),
+        Some(StatusMessage::Error(msg)) => left.push(Span::styled(
+            format!(" {}", msg),
+            bg_style.fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        None => {}
+    }
+    if let Some(confirmation) = &app.confirmation {
+        left.push(Span::styled(
+            format!(" {}", confirmation.question),
+            style.add_modifier(Modifier::BOLD),
+        ));
+    }
+    let left = Line::from(left);
     let right = Line::from(Span::styled(hints, style));
 
     frame.render_widget(
@@ -602,9 +2541,41 @@ fn render_status_bar(frame: &mut Frame, app: &App, config: &Config) {
 // TODO: add proper assert error messages
 #[cfg(test)]
 mod tests {
-    use nu_protocol::{record, Value};
+    use std::{
+        alloc::{GlobalAlloc, System},
+        cell::Cell,
+        collections::HashMap,
+        rc::Rc,
+    };
 
-    use super::{repr_data, repr_list, repr_record, repr_simple_value, repr_table, DataRowRepr};
+    use nu_protocol::{ast::CellPath, engine::Closure, record, Record, Span, Value};
+
+    use super::{
+        bottom_context, column_widths, column_window, is_marked, is_rendering, mark_name,
+        render_to_string, render_ui, render_ui_safely, repr_context, repr_data, repr_list,
+        repr_nested, repr_record, repr_run, repr_simple_value, repr_table, repr_value,
+        row_background, runs_around, strategy_widths, stripe, substitution, truncate,
+        truncate_middle, truncate_styled, value_style, view_offset, visible_rows, DataRowRepr,
+        MAX_KEY_WIDTH, MAX_RUN_SCAN, MISSING_CELL, PANIC_WHILE_RENDERING, PINNED_SEPARATOR,
+    };
+    use crate::nu::cell_path::{to_path_member_vec, PM};
+    use crate::{
+        app::{App, Mode},
+        config::{
+            ColumnWidth, Config, DisplayConfig, Layout, LogConfig, LogLevel, Substitutions,
+            WidthSpec, MIN_COLUMN_WIDTH,
+        },
+        log::Logger,
+    };
+    use unicode_segmentation::UnicodeSegmentation;
+    use unicode_width::UnicodeWidthStr;
+
+    use ratatui::{
+        backend::TestBackend,
+        buffer::Buffer,
+        style::{Color, Modifier, Style},
+        Terminal,
+    };
 
     #[test]
     fn simple_value() {
@@ -618,7 +2589,35 @@ mod tests {
         ];
 
         for (value, expected) in cases {
-            assert_eq!(repr_simple_value(&value), expected);
+            assert_eq!(repr_simple_value(&value, 80, false), expected);
+        }
+    }
+
+    #[test]
+    fn informative_leaves() {
+        let closure = Value::closure(
+            Closure {
+                block_id: 42,
+                captures: vec![(1, Value::test_int(1)), (2, Value::test_int(2))],
+            },
+            Span::test_data(),
+        );
+        let cell_path = Value::cell_path(
+            CellPath {
+                members: to_path_member_vec(&[PM::S("a"), PM::I(0), PM::S("b")]),
+            },
+            Span::test_data(),
+        );
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (closure, DataRowRepr::unnamed("closure of block 42 capturing 2 variables", "closure")),
+            (Value::block(7, Span::test_data()), DataRowRepr::unnamed("block 7", "block")),
+            (cell_path, DataRowRepr::unnamed("$.a.0.b", "cell-path")),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(repr_simple_value(&value, 80, false), expected);
         }
     }
 
@@ -668,6 +2667,55 @@ mod tests {
     #[test]
     fn value() {}
 
+    #[test]
+    fn nested_previews() {
+        let value = Value::test_record(record! {
+            "a" => Value::test_int(1),
+            "b" => Value::test_list(vec![Value::test_string("x"), Value::test_list(vec![])]),
+            "c" => Value::test_record(record! {
+                "d" => Value::test_list(vec![Value::test_int(2)]),
+            }),
+        });
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (80, 1, "{a: 1, b: […], c: {…}}"),
+            (80, 2, "{a: 1, b: [x, []], c: {d: […]}}"),
+            (80, 3, "{a: 1, b: [x, []], c: {d: [2]}}"),
+            (80, 16, "{a: 1, b: [x, []], c: {d: [2]}}"),
+            (10, 2, "{a: 1, b:…"),
+            (0, 2, "{a: 1, b: [x, []], c: {d: […]}}"),
+        ];
+
+        for (preview_length, depth, expected) in cases {
+            assert_eq!(
+                repr_nested(&value, preview_length, depth, false),
+                expected,
+                "with a length of {} and a depth of {}",
+                preview_length,
+                depth
+            );
+        }
+
+        assert_eq!(
+            repr_value(&value, 80, 0, false),
+            DataRowRepr::unnamed("{3 fields}", "record")
+        );
+        assert_eq!(
+            repr_value(&value, 80, 1, false),
+            DataRowRepr::unnamed("{a: 1, b: […], c: {…}}", "record")
+        );
+        assert_eq!(
+            repr_value(&Value::test_list(vec![]), 80, 2, false),
+            DataRowRepr::unnamed("[]", "list")
+        );
+
+        // NOTE: only the first few elements of a huge list are formatted
+        let huge = Value::test_list(vec![Value::test_int(123_456); 1_000_000]);
+        let preview = repr_nested(&huge, 20, 2, false);
+        assert_eq!(preview, "[123456, 123456, 12…");
+    }
+
     #[test]
     fn data() {
         let data = Value::test_record(record! {
@@ -684,7 +2732,7 @@ mod tests {
             "i" => Value::test_int(123),
         });
 
-        let result = repr_data(&data);
+        let result = repr_data(&data, 80, 0, false);
         let expected: Vec<DataRowRepr> = vec![
             DataRowRepr::named("l", "[3 items]", "list"),
             DataRowRepr::named("r", "{2 fields}", "record"),
@@ -696,7 +2744,7 @@ mod tests {
 
     #[test]
     fn repr_simple_table() {
-        let table = vec![
+        let table = [
             record! {
                 "a" => Value::test_string("x"),
                 "b" => Value::test_int(1),
@@ -711,14 +2759,18 @@ mod tests {
             vec!["a".into(), "b".into()],
             vec!["string".into(), "int".into()],
             vec![vec!["x".into(), "1".into()], vec!["y".into(), "2".into()]],
+            vec![vec![false; 2]; 2],
         );
 
-        assert_eq!(repr_table(&table), expected);
+        assert_eq!(
+            repr_table(&table.iter().collect::<Vec<_>>(), 80, 0, false),
+            expected
+        );
     }
 
     #[test]
     fn repr_table_with_empty_column() {
-        let table = vec![
+        let table = [
             record! {
                 "a" => Value::test_nothing(),
                 "b" => Value::test_int(1),
@@ -733,14 +2785,18 @@ mod tests {
             vec!["a".into(), "b".into()],
             vec!["nothing".into(), "int".into()],
             vec![vec!["".into(), "1".into()], vec!["".into(), "2".into()]],
+            vec![vec![false; 2]; 2],
         );
 
-        assert_eq!(repr_table(&table), expected);
+        assert_eq!(
+            repr_table(&table.iter().collect::<Vec<_>>(), 80, 0, false),
+            expected
+        );
     }
 
     #[test]
     fn repr_table_with_shuffled_columns() {
-        let table = vec![
+        let table = [
             record! {
                 "b" => Value::test_int(1),
                 "a" => Value::test_string("x"),
@@ -755,14 +2811,18 @@ mod tests {
             vec!["b".into(), "a".into()],
             vec!["int".into(), "string".into()],
             vec![vec!["1".into(), "x".into()], vec!["2".into(), "y".into()]],
+            vec![vec![false; 2]; 2],
         );
 
-        assert_eq!(repr_table(&table), expected);
+        assert_eq!(
+            repr_table(&table.iter().collect::<Vec<_>>(), 80, 0, false),
+            expected
+        );
     }
 
     #[test]
     fn repr_table_with_holes() {
-        let table = vec![
+        let table = [
             record! {
                 "a" => Value::test_string("x"),
                 "b" => Value::test_nothing(),
@@ -777,14 +2837,61 @@ mod tests {
             vec!["a".into(), "b".into()],
             vec!["string".into(), "int".into()],
             vec![vec!["x".into(), "".into()], vec!["".into(), "2".into()]],
+            vec![vec![false; 2]; 2],
+        );
+
+        assert_eq!(
+            repr_table(&table.iter().collect::<Vec<_>>(), 80, 0, false),
+            expected
+        );
+    }
+
+    #[test]
+    fn repr_ragged_table() {
+        let table = [
+            record! {
+                "a" => Value::test_string("x"),
+            },
+            record! {
+                "a" => Value::test_string("y"),
+                "b" => Value::test_int(2),
+            },
+            record! {
+                "b" => Value::test_int(3),
+            },
+            // NOTE: a string that looks like a missing cell is not missing
+            record! {
+                "a" => Value::test_string(MISSING_CELL),
+                "b" => Value::test_int(4),
+            },
+        ];
+
+        let expected = (
+            vec!["a".into(), "b".into()],
+            vec!["string".into(), "int".into()],
+            vec![
+                vec!["x".into(), MISSING_CELL.into()],
+                vec!["y".into(), "2".into()],
+                vec![MISSING_CELL.into(), "3".into()],
+                vec![MISSING_CELL.into(), "4".into()],
+            ],
+            vec![
+                vec![false, true],
+                vec![false, false],
+                vec![true, false],
+                vec![false, false],
+            ],
         );
 
-        assert_eq!(repr_table(&table), expected);
+        assert_eq!(
+            repr_table(&table.iter().collect::<Vec<_>>(), 80, 0, false),
+            expected
+        );
     }
 
     #[test]
     fn repr_table_with_mixed_numeric_types() {
-        let table = vec![
+        let table = [
             record! {
                 "a" => Value::test_string("x"),
                 "b" => Value::test_int(1),
@@ -802,8 +2909,1165 @@ mod tests {
                 vec!["x".into(), "1".into()],
                 vec!["y".into(), "2.34".into()],
             ],
+            vec![vec![false; 2]; 2],
+        );
+
+        assert_eq!(
+            repr_table(&table.iter().collect::<Vec<_>>(), 80, 0, false),
+            expected
+        );
+    }
+
+    #[test]
+    fn offset_of_the_view() {
+        let mut app = App {
+            offset: 42,
+            ..Default::default()
+        };
+
+        assert_eq!(view_offset(&app, 100, 1000, 20), 42);
+
+        app.recenter = true;
+
+        #[rustfmt::skip]
+        let cases = vec![
+            ((100, 1000, 20), 90),
+            ((5, 1000, 20), 0),
+            ((995, 1000, 20), 980),
+            ((3, 5, 20), 0),
+        ];
+
+        for ((selected, nb_rows, height), expected) in cases {
+            assert_eq!(
+                view_offset(&app, selected, nb_rows, height),
+                expected,
+                "centering row {} out of {} in a view of height {}",
+                selected,
+                nb_rows,
+                height
+            );
+        }
+    }
+
+    #[test]
+    fn striped_rows() {
+        let mut config = Config::default();
+        config.colors.row = Color::Black;
+        config.colors.row_alt = Color::DarkGray;
+
+        assert_eq!(row_background(&config, 0), Color::Black);
+        assert_eq!(row_background(&config, 1), Color::DarkGray);
+        assert_eq!(row_background(&config, 42), Color::Black);
+        assert_eq!(row_background(&config, 43), Color::DarkGray);
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (Style::default(), Style::default().bg(Color::DarkGray)),
+            (Style::default().bg(Color::Reset), Style::default().bg(Color::DarkGray)),
+            (Style::default().bg(Color::Red), Style::default().bg(Color::Red)),
+            (Style::default().fg(Color::Red), Style::default().fg(Color::Red).bg(Color::DarkGray)),
+        ];
+
+        for (style, expected) in cases {
+            assert_eq!(stripe(style, Color::DarkGray), expected);
+        }
+    }
+
+    #[test]
+    fn marked_rows() {
+        let marks = to_path_member_vec(&[PM::I(1), PM::S("foo")]);
+
+        #[rustfmt::skip]
+        let cases = vec![
+            ((0, None), false),
+            ((1, None), true),
+            ((1, Some("bar")), false),
+            ((0, Some("foo")), true),
+            ((5, Some("foo")), true),
+        ];
+
+        for ((index, name), expected) in cases {
+            assert_eq!(
+                is_marked(&marks, index, name),
+                expected,
+                "row {} with name {:?}",
+                index,
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn colorized_values() {
+        let mut app = App::default();
+        let mut config = Config::default();
+
+        let values = vec![
+            Value::test_bool(true),
+            Value::test_bool(false),
+            Value::test_string("./src"),
+            Value::test_int(1),
+            Value::test_string("https://www.nushell.sh"),
+            Value::test_string("not a https://url"),
+        ];
+
+        for value in &values {
+            assert_eq!(value_style(value, &mut app, &config), Style::default());
+        }
+
+        config.display.colorize_values = true;
+        let expected = vec![
+            Style::default().fg(Color::Green),
+            Style::default().fg(Color::Red),
+            app.path_styles.style("./src").unwrap_or_default(),
+            Style::default(),
+            Style::default().add_modifier(Modifier::UNDERLINED),
+            Style::default(),
+        ];
+        for (value, expected) in values.iter().zip(expected) {
+            assert_eq!(value_style(value, &mut app, &config), expected);
+        }
+    }
+
+    #[test]
+    fn runs_of_identical_values() {
+        let vals: Vec<Value> = [1, 1, 1, 2, 3, 3, 1]
+            .iter()
+            .map(|x| Value::test_int(*x))
+            .collect();
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (0, 10, (vec![0..3, 3..4, 4..6, 6..7], 0)),
+            (2, 10, (vec![0..3, 3..4, 4..6, 6..7], 0)),
+            (5, 10, (vec![0..3, 3..4, 4..6, 6..7], 2)),
+            (5, 1, (vec![3..4, 4..6, 6..7], 1)),
+            (0, 1, (vec![0..3, 3..4], 0)),
+        ];
+
+        for (selected, nb_runs, expected) in cases {
+            assert_eq!(
+                runs_around(&vals, selected, nb_runs),
+                expected,
+                "runs around {} with {} runs",
+                selected,
+                nb_runs
+            );
+        }
+
+        assert_eq!(runs_around(&[], 0, 10), (vec![], 0));
+
+        // NOTE: lists and records are never grouped
+        let vals = vec![Value::test_list(vec![]), Value::test_list(vec![])];
+        assert_eq!(runs_around(&vals, 0, 10), (vec![0..1, 1..2], 0));
+
+        // NOTE: the runs are cut far away from the selected element
+        let vals = vec![Value::test_int(0); 2 * MAX_RUN_SCAN + 10];
+        assert_eq!(
+            runs_around(&vals, MAX_RUN_SCAN + 5, 10).0[0],
+            5..(2 * MAX_RUN_SCAN + 6)
+        );
+
+        let vals = vec![Value::test_int(1); 100];
+        assert_eq!(
+            repr_run(&vals, &(0..100), 42, 80, 0, false),
+            DataRowRepr::unnamed("0‥99  (100 × 1)  [42]", "int")
         );
+        assert_eq!(
+            repr_run(&vals, &(0..100), 100, 80, 0, false),
+            DataRowRepr::unnamed("0‥99  (100 × 1)", "int")
+        );
+        assert_eq!(
+            repr_run(&vals, &(3..4), 3, 80, 0, false),
+            DataRowRepr::unnamed("1", "int")
+        );
+    }
+
+    #[test]
+    fn raw_simple_value() {
+        #[rustfmt::skip]
+        let cases = vec![
+            (Value::test_float(1.0), "1", "1.0"),
+            (Value::test_float(0.1), "0.1", "0.1"),
+            (Value::test_int(123456), "123456", "123456"),
+            (Value::test_filesize(2048), "2.0 KiB", "2048"),
+            (Value::test_duration(1_500_000_000), "1sec 500ms", "1500000000ns"),
+            (Value::test_string("foo\tbar\n"), "foo\tbar\n", "foo\\tbar\\n"),
+        ];
 
-        assert_eq!(repr_table(&table), expected);
+        for (value, formatted, raw) in cases {
+            assert_eq!(repr_simple_value(&value, 0, false).data, formatted);
+            assert_eq!(repr_simple_value(&value, 0, true).data, raw);
+        }
+
+        assert_ne!(
+            repr_simple_value(&Value::test_float(1.0), 0, false),
+            repr_simple_value(&Value::test_float(1.0), 0, true),
+        );
+    }
+
+    #[test]
+    fn truncate_on_graphemes() {
+        #[rustfmt::skip]
+        let cases = vec![
+            (("foo", 0), "foo"),
+            (("foo", 3), "foo"),
+            (("foobar", 4), "foo…"),
+            (("", 2), ""),
+            // emojis, including a family made of several code points joined by ZWJs
+            (("😀😃😄😁", 3), "😀😃…"),
+            (("👨‍👩‍👧‍👦👨‍👩‍👧‍👦👨‍👩‍👧‍👦", 2), "👨‍👩‍👧‍👦…"),
+            (("👨‍👩‍👧‍👦👨‍👩‍👧‍👦", 2), "👨‍👩‍👧‍👦👨‍👩‍👧‍👦"),
+            // CJK
+            (("日本語のテキスト", 4), "日本語…"),
+            // combining characters, e.g. `e` followed by U+0301
+            (("e\u{301}e\u{301}e\u{301}", 2), "e\u{301}…"),
+            (("e\u{301}e\u{301}", 2), "e\u{301}e\u{301}"),
+        ];
+
+        for ((s, length), expected) in cases {
+            assert_eq!(
+                truncate(s, length),
+                expected,
+                "truncating {:?} to {} graphemes",
+                s,
+                length
+            );
+        }
+    }
+
+    #[test]
+    fn truncated_simple_value() {
+        assert_eq!(
+            repr_simple_value(&Value::test_string("some long string"), 5, false),
+            DataRowRepr::unnamed("some…", "string")
+        );
+        assert_eq!(
+            repr_simple_value(&Value::test_int(123456), 4, false),
+            DataRowRepr::unnamed("123…", "int")
+        );
+        assert_eq!(
+            repr_simple_value(&Value::test_string("some long string"), 0, false),
+            DataRowRepr::unnamed("some long string", "string")
+        );
+    }
+
+    #[test]
+    fn widths_of_columns() {
+        let columns = vec!["a".to_string(), "日本語".to_string()];
+        let shapes = vec!["int".to_string(), "string".to_string()];
+
+        #[rustfmt::skip]
+        let cells = vec![
+            vec!["1".to_string(), "テキストテキスト".to_string()],
+            vec!["123456789".to_string(), "x".to_string()],
+        ];
+        assert_eq!(column_widths(&columns, &shapes, &cells), vec![9, 16]);
+
+        let cells = vec![vec!["1".to_string(), "👨‍👩‍👧‍👦".to_string()]];
+        assert_eq!(column_widths(&columns, &shapes, &cells), vec![7, 15]);
+    }
+
+    #[test]
+    fn column_width_strategies() {
+        let natural = vec![5, 20, 8];
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (ColumnWidth::Auto, 60, vec![5, 20, 8]),
+            (ColumnWidth::Equal, 60, vec![19, 19, 19]),
+            // NOTE: the columns are never narrower than the minimum, even if they do not fit
+            (ColumnWidth::Equal, 6, vec![MIN_COLUMN_WIDTH; 3]),
+            (
+                ColumnWidth::Custom(vec![WidthSpec::Percent(50)]),
+                60,
+                vec![29, 14, 14],
+            ),
+            (
+                ColumnWidth::Custom(vec![WidthSpec::Fixed(10), WidthSpec::Fixed(4)]),
+                60,
+                vec![10, 4, 43],
+            ),
+            (
+                ColumnWidth::Custom(vec![
+                    WidthSpec::Percent(100),
+                    WidthSpec::Fixed(10),
+                    WidthSpec::Fixed(10),
+                    WidthSpec::Fixed(10),
+                ]),
+                60,
+                vec![59, 10, 10],
+            ),
+        ];
+
+        for (strategy, available, expected) in cases {
+            assert_eq!(
+                strategy_widths(&strategy, &natural, available),
+                expected,
+                "{} in {} columns",
+                strategy,
+                available
+            );
+        }
+    }
+
+    /// render the whole UI on a test terminal
+    fn render(value: Value, config: &Config, width: u16, height: u16) -> Buffer {
+        let mut app = App::from_value(value);
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| render_ui(frame, &mut app, config, None))
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    /// the x coordinates where *needle* appears in each row of the *buffer*, one cell per grapheme
+    fn positions(buffer: &Buffer, needle: &str) -> Vec<(u16, u16)> {
+        let needle: Vec<&str> = needle.graphemes(true).collect();
+
+        let mut positions = vec![];
+        for y in 0..buffer.area.height {
+            let row: Vec<&str> = (0..buffer.area.width)
+                .map(|x| buffer.get(x, y).symbol())
+                .collect();
+            for x in 0..=row.len().saturating_sub(needle.len()) {
+                if row[x..x + needle.len()] == needle[..] {
+                    positions.push((x as u16, y));
+                }
+            }
+        }
+        positions
+    }
+
+    #[test]
+    fn contexts() {
+        #[rustfmt::skip]
+        let cases = vec![
+            (vec![], " $ "),
+            (vec![PM::S("foo")], " foo "),
+            (vec![PM::S("foo"), PM::I(2)], " item 2 "),
+        ];
+
+        for (members, expected) in cases {
+            assert_eq!(repr_context(&to_path_member_vec(&members)), expected);
+        }
+    }
+
+    #[test]
+    fn sticky_header_and_context() {
+        let rows: Vec<Value> = (0..50)
+            .map(|i| {
+                Value::test_record(record! {
+                    "name" => Value::test_string(format!("row {}", i)),
+                })
+            })
+            .collect();
+        let value = Value::test_record(record! { "rows" => Value::test_list(rows.clone()) });
+
+        let render_at = |value: Value, config: &Config, members: &[PM]| {
+            let mut app = App::from_value(value);
+            app.position.members = to_path_member_vec(members);
+            app.recenter = true;
+            let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+            terminal
+                .draw(|frame| render_ui(frame, &mut app, config, None))
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+
+        let config = Config {
+            show_context: true,
+            ..Config::default()
+        };
+
+        // NOTE: the header stays under the context, and the selected row is never hidden
+        let buffer = render_at(value.clone(), &config, &[PM::S("rows"), PM::I(42)]);
+        assert_eq!(positions(&buffer, " rows "), vec![(1, 0)]);
+        assert_eq!(
+            positions(&buffer, "name (string)").first().map(|p| p.1),
+            Some(1)
+        );
+        assert_eq!(positions(&buffer, "row 42").len(), 1);
+
+        let buffer = render_at(value.clone(), &config, &[PM::S("rows"), PM::I(49)]);
+        assert_eq!(positions(&buffer, "row 49").len(), 1);
+
+        // NOTE: without borders, the context takes a line of its own
+        let config = Config {
+            show_context: true,
+            layout: Layout::Compact,
+            ..Config::default()
+        };
+        let list = Value::test_list((0..50).map(Value::test_int).collect());
+        let buffer = render_at(list, &config, &[PM::I(49)]);
+        assert_eq!(positions(&buffer, " $ ").first().map(|p| p.1), Some(0));
+        assert_eq!(positions(&buffer, "(int) 49").len(), 1);
+    }
+
+    #[test]
+    fn redraw_unchanged_table() {
+        let value = Value::test_list(
+            (0..20)
+                .map(|i| {
+                    Value::test_record(record! {
+                        "a" => Value::test_int(i),
+                        "b" => Value::test_string(format!("row {}", i)),
+                    })
+                })
+                .collect(),
+        );
+        let config = Config::default();
+        let mut app = App::from_value(value);
+        let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+
+        let mut draw = |app: &mut App| {
+            terminal
+                .draw(|frame| render_ui(frame, app, &config, None))
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+
+        let first = draw(&mut app);
+        let repr = app.table_cache.as_ref().unwrap().repr.clone();
+        assert_eq!(draw(&mut app), first);
+        // NOTE: the cells have not been formatted again
+        assert!(Rc::ptr_eq(&app.table_cache.as_ref().unwrap().repr, &repr));
+
+        app.raw = true;
+        draw(&mut app);
+        assert!(!Rc::ptr_eq(&app.table_cache.as_ref().unwrap().repr, &repr));
+    }
+
+    #[test]
+    fn redraw_unchanged_rows() {
+        let value = Value::test_list(
+            (0..20)
+                .map(|i| Value::test_string(format!("item {}", i)))
+                .collect(),
+        );
+        let config = Config::default();
+        let mut app = App::from_value(value);
+        let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+
+        let mut draw = |app: &mut App| {
+            terminal
+                .draw(|frame| render_ui(frame, app, &config, None))
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+
+        let first = draw(&mut app);
+        let repr = app.rows_cache.as_ref().unwrap().repr.clone();
+        assert_eq!(draw(&mut app), first);
+        // NOTE: the rows have not been formatted again
+        assert!(Rc::ptr_eq(&app.rows_cache.as_ref().unwrap().repr, &repr));
+
+        app.preview_depth += 1;
+        draw(&mut app);
+        assert!(!Rc::ptr_eq(&app.rows_cache.as_ref().unwrap().repr, &repr));
+    }
+
+    thread_local! {
+        /// the number of bytes allocated by the current thread, see [`CountingAllocator`]
+        static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// an allocator that counts the bytes allocated by each thread, to measure the cost of a
+    /// frame without the noise of the other tests
+    struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            let _ = ALLOCATED.try_with(|a| a.set(a.get() + layout.size()));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn allocations_of_unchanged_frames() {
+        let n = 10_000;
+        let values = vec![
+            Value::test_list(
+                (0..n)
+                    .map(|i| {
+                        Value::test_record(record! {
+                            "a" => Value::test_int(i),
+                            "b" => Value::test_string(format!("row {}", i)),
+                        })
+                    })
+                    .collect(),
+            ),
+            Value::test_list(
+                (0..n)
+                    .map(|i| Value::test_list(vec![Value::test_int(i); 3]))
+                    .collect(),
+            ),
+            Value::test_record(
+                (0..n)
+                    .map(|i| {
+                        (
+                            format!("f{}", i),
+                            Value::test_string(format!("field {}", i)),
+                        )
+                    })
+                    .collect(),
+            ),
+        ];
+
+        for value in values {
+            let config = Config::default();
+            let mut app = App::from_value(value);
+            let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+            let mut draw = || {
+                let before = ALLOCATED.with(Cell::get);
+                terminal
+                    .draw(|frame| render_ui(frame, &mut app, &config, None))
+                    .unwrap();
+                ALLOCATED.with(Cell::get) - before
+            };
+
+            // NOTE: a frame of an unchanged view does not depend on the size of the view
+            let first = draw();
+            let second = draw();
+            assert!(
+                second * 20 < first,
+                "{} bytes allocated for the first frame, {} for the next one",
+                first,
+                second
+            );
+        }
+    }
+
+    #[test]
+    fn truncate_in_the_middle() {
+        #[rustfmt::skip]
+        let cases = vec![
+            (("foo", 3), "foo"),
+            (("foo", 5), "foo"),
+            (("veryLongFieldName", 13), "veryLo…ldName"),
+            (("veryLongFieldName", 2), "v…"),
+            (("foo", 0), ""),
+            // wide characters never overflow the width
+            (("日本語のテキスト", 9), "日本…スト"),
+            (("日本語のテキスト", 8), "日本…ト"),
+        ];
+
+        for ((s, width), expected) in cases {
+            assert_eq!(truncate_middle(s, width), expected, "truncating {:?}", s);
+            assert!(truncate_middle(s, width).width() <= width);
+        }
+    }
+
+    #[test]
+    fn visible_rows_of_a_view() {
+        #[rustfmt::skip]
+        let cases = vec![
+            ((0, 0, 10, 5), 0..5),
+            ((0, 7, 10, 5), 3..8),
+            ((4, 2, 10, 5), 2..7),
+            ((8, 9, 10, 5), 8..10),
+            ((0, 0, 3, 5), 0..3),
+        ];
+
+        for ((offset, selected, nb_rows, height), expected) in cases {
+            assert_eq!(visible_rows(offset, selected, nb_rows, height), expected);
+        }
+    }
+
+    #[test]
+    fn aligned_keys_in_compact_layout() {
+        let config = Config {
+            layout: Layout::Compact,
+            ..Config::default()
+        };
+
+        let value = Value::test_record(record! {
+            "a" => Value::test_string("x"),
+            "日本語" => Value::test_string("x"),
+            "medium" => Value::test_string("x"),
+            "a_very_long_key_that_goes_past_the_maximum_width" => Value::test_string("x"),
+        });
+        let buffer = render(value, &config, 80, 10);
+
+        let shapes = positions(&buffer, "(string)");
+        assert_eq!(shapes.len(), 4, "expected one string per row");
+        assert!(
+            shapes.iter().all(|(x, _)| *x == shapes[0].0),
+            "the values are not aligned: {:?}",
+            shapes
+        );
+        assert_eq!(shapes[0].0 as usize, MAX_KEY_WIDTH + 2);
+        assert_eq!(positions(&buffer, "a_very_long_…ximum_width").len(), 1);
+
+        // NOTE: only the visible keys are used to align the values
+        let value = Value::test_record(record! {
+            "a" => Value::test_string("x"),
+            "bb" => Value::test_string("x"),
+        });
+        let buffer = render(value, &config, 80, 10);
+        assert_eq!(positions(&buffer, "(string)"), vec![(4, 0), (4, 1)]);
+    }
+
+    #[test]
+    fn peek_the_screen() {
+        let value = Value::test_record(record! {
+            "日本" => Value::test_string("テキスト"),
+            "b" => Value::test_int(1),
+        });
+        let mut app = App::from_value(value);
+        app.screen_size = (30, 6);
+        app.mode = Mode::Peeking;
+
+        let config = Config {
+            layout: Layout::Compact,
+            ..Config::default()
+        };
+        let screen = render_to_string(&mut app, &config);
+        assert_eq!(
+            screen,
+            [
+                "日本: (string) テキスト",
+                "b   : (int) 1",
+                "",
+                "",
+                "cell path: $.日本"
+            ]
+            .join("\n")
+        );
+        assert_eq!(app.mode, Mode::Peeking, "the mode should be left untouched");
+
+        let config = Config {
+            peek_status_bar: true,
+            ..config
+        };
+        let screen = render_to_string(&mut app, &config);
+        // NOTE: the hints of NORMAL mode are shown, not the ones of PEEKING mode
+        assert!(screen.lines().last().unwrap().contains("to INSERT"));
+        assert!(screen.lines().all(|l| l == l.trim_end()));
+    }
+
+    #[test]
+    fn recover_from_panics() {
+        let file =
+            std::env::temp_dir().join(format!("nu_plugin_explore-log-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+        let config = Config {
+            log: LogConfig {
+                file: Some(file.to_string_lossy().to_string()),
+                level: LogLevel::Error,
+            },
+            ..Config::default()
+        };
+
+        let mut app = App::from_value(Value::test_record(record! {
+            "a" => Value::test_int(1),
+        }));
+        app.logger = Logger::new(&config.log);
+        let mut terminal = Terminal::new(TestBackend::new(60, 10)).unwrap();
+        let mut draw = |app: &mut App| {
+            terminal
+                .draw(|frame| render_ui_safely(frame, app, &config, None))
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+
+        PANIC_WHILE_RENDERING.with(|p| p.set(true));
+        let buffer = draw(&mut app);
+        PANIC_WHILE_RENDERING.with(|p| p.set(false));
+
+        assert!(!is_rendering());
+        assert_eq!(positions(&buffer, "rendering error").len(), 1);
+        assert_eq!(
+            positions(&buffer, "error: a deliberate panic of the renderer").len(),
+            1
+        );
+        assert_eq!(positions(&buffer, "cell path: $.a").len(), 1);
+
+        let log = std::fs::read_to_string(&file).unwrap();
+        assert!(log.ends_with(" panic at $.a: a deliberate panic of the renderer\n"));
+
+        // NOTE: the next frame is rendered as usual
+        let buffer = draw(&mut app);
+        assert!(positions(&buffer, "rendering error").is_empty());
+
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn render_wide_characters() {
+        let config = Config::default();
+
+        let value = Value::test_record(record! {
+            "日本語" => Value::test_string("テキスト"),
+            "family" => Value::test_string("👨‍👩‍👧‍👦 family"),
+            "zwj" => Value::test_string("a\u{200d}b"),
+            "ascii" => Value::test_string("foo"),
+        });
+        let buffer = render(value, &config, 80, 10);
+
+        let shapes = positions(&buffer, "string");
+        assert_eq!(
+            shapes.len(),
+            4,
+            "expected one string per row, found {:?}",
+            shapes
+        );
+        assert!(
+            shapes.iter().all(|(x, _)| *x == shapes[0].0),
+            "the shapes are not aligned: {:?}",
+            shapes
+        );
+
+        let data = [
+            positions(&buffer, "テ"),
+            positions(&buffer, "👨‍👩‍👧‍👦"),
+            positions(&buffer, "foo"),
+        ];
+        for d in &data {
+            assert_eq!(d.len(), 1);
+        }
+        assert!(
+            data.iter().all(|d| d[0].0 == data[0][0].0),
+            "the data is not aligned: {:?}",
+            data
+        );
+
+        let value = Value::test_list(vec![
+            Value::test_record(record! {
+                "a" => Value::test_string("日本語"),
+                "b" => Value::test_int(1),
+            }),
+            Value::test_record(record! {
+                "a" => Value::test_string("x"),
+                "b" => Value::test_int(2),
+            }),
+        ]);
+        let buffer = render(value, &config, 80, 10);
+
+        let header = positions(&buffer, "b (int)");
+        let ints = [positions(&buffer, "1"), positions(&buffer, "2")];
+        assert_eq!(header.len(), 1);
+        for int in &ints {
+            assert_eq!(int.len(), 1);
+            assert_eq!(
+                int[0].0, header[0].0,
+                "the cells are not aligned with the header"
+            );
+        }
+    }
+
+    #[test]
+    fn ansi_strings() {
+        let red = Style::default().fg(Color::Indexed(1));
+
+        let value = Value::test_string("\x1b]0;title\x07\x1b[31mred\x1b[0m and normal");
+        assert_eq!(
+            repr_simple_value(&value, 80, false),
+            DataRowRepr {
+                name: None,
+                shape: "string".into(),
+                data: "red and normal".into(),
+                styled: Some(vec![
+                    ("red".into(), red),
+                    (" and normal".into(), Style::default()),
+                ]),
+            }
+        );
+
+        assert_eq!(
+            repr_simple_value(&value, 6, false),
+            DataRowRepr {
+                name: None,
+                shape: "string".into(),
+                data: "red a…".into(),
+                styled: Some(vec![
+                    ("red".into(), red),
+                    (" a".into(), Style::default()),
+                    ("…".into(), Style::default()),
+                ]),
+            }
+        );
+    }
+
+    #[test]
+    fn truncate_styled_segments() {
+        let bold = Style::default().add_modifier(ratatui::style::Modifier::BOLD);
+        let segments = vec![
+            ("日本".to_string(), bold),
+            ("語abc".to_string(), Style::default()),
+        ];
+
+        assert_eq!(truncate_styled(&segments, 0), segments);
+        assert_eq!(truncate_styled(&segments, 6), segments);
+        assert_eq!(
+            truncate_styled(&segments, 2),
+            vec![
+                ("日".to_string(), bold),
+                ("…".to_string(), Style::default())
+            ]
+        );
+        assert_eq!(
+            truncate_styled(&segments, 4),
+            vec![
+                ("日本".to_string(), bold),
+                ("語".to_string(), Style::default()),
+                ("…".to_string(), Style::default())
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_view_hint() {
+        let config = Config::default();
+        for layout in [Layout::Table, Layout::Compact] {
+            let config = Config {
+                layout,
+                ..config.clone()
+            };
+            let buffer = render(Value::test_record(record! {}), &config, 60, 10);
+            assert_eq!(
+                positions(&buffer, "empty record — press i to start editing").len(),
+                1
+            );
+
+            let buffer = render(Value::test_list(vec![]), &config, 60, 10);
+            assert_eq!(
+                positions(&buffer, "empty list — press i to start editing").len(),
+                1
+            );
+        }
+
+        let config = Config {
+            read_only: true,
+            ..config
+        };
+        let buffer = render(Value::test_record(record! {}), &config, 60, 10);
+        assert_eq!(positions(&buffer, "empty record").len(), 1);
+        assert!(positions(&buffer, "press").is_empty());
+    }
+
+    #[test]
+    fn window_of_columns() {
+        #[rustfmt::skip]
+        let cases = vec![
+            ((vec![5, 5, 5, 5], 13, None, None, 0), (vec![Some(0), Some(1)], 0)),
+            ((vec![5, 5, 5, 5], 13, None, Some(3), 0), (vec![Some(2), Some(3)], 2)),
+            ((vec![5, 5, 5, 5], 13, None, Some(0), 2), (vec![Some(0), Some(1)], 0)),
+            ((vec![5, 5, 5, 5], 13, None, None, 2), (vec![Some(2), Some(3)], 2)),
+            // NOTE: no room is left on the right
+            ((vec![5, 5], 100, None, None, 1), (vec![Some(0), Some(1)], 0)),
+            ((vec![50], 10, None, None, 0), (vec![Some(0)], 0)),
+            ((vec![5, 5, 5, 5], 20, Some(2), None, 0), (vec![Some(2), None, Some(0), Some(1)], 0)),
+            ((vec![5, 5, 5, 5], 20, Some(2), Some(3), 0), (vec![Some(2), None, Some(1), Some(3)], 1)),
+            ((vec![5, 5, 5, 5], 20, Some(2), Some(2), 1), (vec![Some(2), None, Some(1), Some(3)], 1)),
+            ((vec![5], 20, Some(0), None, 0), (vec![Some(0)], 0)),
+        ];
+
+        for ((widths, width, pinned, selected, offset), expected) in cases {
+            assert_eq!(
+                column_window(&widths, width, pinned, selected, offset),
+                expected,
+                "widths: {:?}, width: {}, pinned: {:?}, selected: {:?}, offset: {}",
+                widths,
+                width,
+                pinned,
+                selected,
+                offset
+            );
+        }
+    }
+
+    #[test]
+    fn pinned_column() {
+        let row = |i: usize| {
+            Value::test_record(record! {
+                "name" => Value::test_string(format!("row {}", i)),
+                "a" => Value::test_string("aaaaaaaaaaaa"),
+                "b" => Value::test_string("bbbbbbbbbbbb"),
+                "c" => Value::test_string("cccccccccccc"),
+            })
+        };
+        let config = Config::default();
+        let mut app = App::from_value(Value::test_list(vec![row(0), row(1)]));
+        let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+        let mut draw = |app: &mut App| {
+            terminal
+                .draw(|frame| render_ui(frame, app, &config, None))
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+
+        // NOTE: the last column is scrolled to, the first one is gone
+        app.column = Some(3);
+        let buffer = draw(&mut app);
+        assert!(positions(&buffer, "name (string)").is_empty());
+        assert_eq!(positions(&buffer, "c (string)").len(), 1);
+        assert!(positions(&buffer, PINNED_SEPARATOR).is_empty());
+
+        app.table_settings_mut(&[]).pinned = Some("name".into());
+        let buffer = draw(&mut app);
+        let name = positions(&buffer, "name (string)");
+        let separator = positions(&buffer, PINNED_SEPARATOR);
+        let c = positions(&buffer, "c (string)");
+        assert_eq!(name.len(), 1);
+        assert_eq!(c.len(), 1);
+        assert!(positions(&buffer, "a (string)").is_empty());
+        // NOTE: the separator is between the pinned column and the other ones, on every row
+        assert_eq!(separator.len(), 3);
+        assert!(name[0].0 < separator[0].0 && separator[0].0 < c[0].0);
+        assert_eq!(positions(&buffer, "row 1").len(), 1);
+    }
+
+    #[test]
+    fn marks_of_duplicate_columns() {
+        let mut rec = Record::new();
+        for (col, val) in [("x", 1), ("x", 2), ("x", 3), ("y", 4)] {
+            rec.push(col, Value::test_int(val));
+        }
+        let value = Value::test_record(rec);
+        let marks = to_path_member_vec(&[PM::I(1), PM::S("y")]);
+
+        #[rustfmt::skip]
+        let cases = vec![
+            ((0, "x"), false),
+            ((1, "x"), true),
+            ((2, "x"), false),
+            ((3, "y"), true),
+        ];
+        for ((index, name), expected) in cases {
+            let name = mark_name(&value, index, Some(name));
+            assert_eq!(is_marked(&marks, index, name), expected, "row {}", index);
+        }
+    }
+
+    #[test]
+    fn split_screen() {
+        let config = Config::default();
+        let mut app = App::from_value(Value::test_record(record! {
+            "a" => Value::test_record(record! { "x" => Value::test_string("foo") }),
+            "b" => Value::test_record(record! { "x" => Value::test_string("bar") }),
+        }));
+        app.toggle_split().unwrap();
+        app.switch_pane().unwrap();
+        app.position.members = to_path_member_vec(&[PM::S("b"), PM::S("x")]);
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 10)).unwrap();
+        terminal
+            .draw(|frame| render_ui(frame, &mut app, &config, None))
+            .unwrap();
+        let buffer = terminal.backend().buffer().clone();
+
+        // NOTE: the left pane is still at the top level, the focused right one is in `b`
+        let bar = positions(&buffer, "bar");
+        assert_eq!(bar.len(), 2);
+        assert!(positions(&buffer, "{x: bar}")[0].0 < 30);
+        assert!(bar.iter().any(|(x, _)| *x >= 30));
+        assert!(positions(&buffer, "{x: foo}")[0].0 < 30);
+        assert!(positions(&buffer, "foo").iter().all(|(x, _)| *x < 30));
+
+        let (x, y) = positions(&buffer, "cell path: $.a")[0];
+        assert!(x < 30);
+        assert!(!buffer.get(x, y).modifier.contains(Modifier::BOLD));
+        let (x, y) = positions(&buffer, "cell path: $.b.x")[0];
+        assert!(x >= 30);
+        assert!(buffer.get(x, y).modifier.contains(Modifier::BOLD));
+
+        // NOTE: rendering the other pane leaves the focused one untouched
+        assert_eq!(
+            app.position.members,
+            to_path_member_vec(&[PM::S("b"), PM::S("x")])
+        );
+    }
+
+    #[test]
+    fn monochrome() {
+        let mut config = Config {
+            display: DisplayConfig {
+                colorize_values: true,
+                ..Config::default().display
+            },
+            ..Config::default()
+        };
+        let mut app = App::from_value(Value::test_record(record! {
+            "a" => Value::test_bool(true),
+            "b" => Value::test_bool(false),
+            "c" => Value::test_string("https://www.nushell.sh"),
+        }));
+        app.toggle_mark();
+
+        let draw = |app: &mut App, config: &Config| {
+            let mut terminal = Terminal::new(TestBackend::new(60, 10)).unwrap();
+            terminal
+                .draw(|frame| render_ui_safely(frame, app, config, None))
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+
+        let buffer = draw(&mut app, &config);
+        assert!(buffer
+            .content
+            .iter()
+            .any(|cell| cell.fg != Color::Reset || cell.bg != Color::Reset));
+
+        config.display.monochrome = true;
+        let buffer = draw(&mut app, &config);
+        for cell in &buffer.content {
+            assert_eq!((cell.fg, cell.bg), (Color::Reset, Color::Reset));
+        }
+
+        // NOTE: the selected and marked row is reversed and in italic, the other ones are not
+        let (x, y) = positions(&buffer, "true")[0];
+        let modifier = buffer.get(x, y).modifier;
+        assert!(modifier.contains(Modifier::REVERSED | Modifier::ITALIC));
+        let (x, y) = positions(&buffer, "false")[0];
+        let modifier = buffer.get(x, y).modifier;
+        assert!(!modifier.intersects(Modifier::REVERSED | Modifier::ITALIC));
+        let (x, y) = positions(&buffer, "shape")[0];
+        assert!(buffer.get(x, y).modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn context_of_the_bottom() {
+        let record = Value::test_record(record! {
+            "a" => Value::test_int(1),
+            "b" => Value::test_int(2),
+            "c" => Value::test_int(3),
+        });
+        let names = |context: Option<(String, Vec<(String, bool)>)>| {
+            context.map(|(label, siblings)| {
+                let siblings: Vec<String> = siblings
+                    .into_iter()
+                    .map(|(name, current)| if current { format!("[{}]", name) } else { name })
+                    .collect();
+                format!("{}: {}", label, siblings.join(" "))
+            })
+        };
+
+        assert_eq!(
+            names(bottom_context(
+                &record,
+                &to_path_member_vec(&[PM::S("b")])[0]
+            )),
+            Some("record of 3 fields: a [b] c".into())
+        );
+        let list = Value::test_list((0..10).map(Value::test_int).collect());
+        assert_eq!(
+            names(bottom_context(&list, &to_path_member_vec(&[PM::I(5)])[0])),
+            Some("list of 10 items: … 2 3 4 [5] 6 7 8 …".into())
+        );
+        assert_eq!(
+            names(bottom_context(&list, &to_path_member_vec(&[PM::I(0)])[0])),
+            Some("list of 10 items: [0] 1 2 3 …".into())
+        );
+        assert_eq!(
+            bottom_context(&list, &to_path_member_vec(&[PM::S("a")])[0]),
+            None
+        );
+
+        let draw = |app: &mut App, config: &Config| {
+            let mut terminal = Terminal::new(TestBackend::new(60, 10)).unwrap();
+            terminal
+                .draw(|frame| render_ui(frame, app, config, None))
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+
+        let config = Config::default();
+        let mut app = App::from_value(Value::test_record(record! {
+            "x" => record.clone(),
+        }));
+        app.position.members = to_path_member_vec(&[PM::S("x"), PM::S("b")]);
+        app.hit_bottom();
+        let buffer = draw(&mut app, &config);
+        let (x, y) = positions(&buffer, "record of 3 fields:")[0];
+        assert_eq!(y, 0, "the context is on top of the value");
+        assert!(buffer.get(x, y).modifier.contains(Modifier::DIM));
+        let (x, y) = positions(&buffer, " b ")[0];
+        assert!(buffer.get(x + 1, y).modifier.contains(Modifier::BOLD));
+        assert!(!buffer.get(x + 1, y).modifier.contains(Modifier::DIM));
+        assert!(!positions(&buffer, "2 is of shape int").is_empty());
+
+        let no_context = Config {
+            display: DisplayConfig {
+                bottom_context: false,
+                ..Config::default().display
+            },
+            ..Config::default()
+        };
+        let buffer = draw(&mut app, &no_context);
+        assert!(positions(&buffer, "record of").is_empty());
+
+        // NOTE: a simple value at the root has no parent
+        let mut app = App::from_value(Value::test_int(1));
+        app.hit_bottom();
+        let buffer = draw(&mut app, &config);
+        assert!(positions(&buffer, "record of").is_empty());
+        assert!(positions(&buffer, "list of").is_empty());
+        assert!(!positions(&buffer, "1 is of shape int").is_empty());
+    }
+
+    #[test]
+    fn render_with_substitutions() {
+        let substitutions = Substitutions::from([
+            (
+                "status".to_string(),
+                HashMap::from([
+                    ("ok".to_string(), "✔".to_string()),
+                    ("error".to_string(), "✘".to_string()),
+                ]),
+            ),
+            (
+                "bool".to_string(),
+                HashMap::from([("true".to_string(), "yes".to_string())]),
+            ),
+        ]);
+
+        #[rustfmt::skip]
+        let cases = vec![
+            (Some("status"), Value::test_string("ok"), Some("✔")),
+            (Some("status"), Value::test_string("unknown"), None),
+            (None, Value::test_string("ok"), None),
+            (Some("done"), Value::test_bool(true), Some("yes")),
+            (None, Value::test_bool(false), None),
+            (Some("status"), Value::test_list(vec![]), None),
+        ];
+        for (column, value, expected) in cases {
+            assert_eq!(
+                substitution(&substitutions, column, &value),
+                expected.map(|s| s.to_string()),
+                "{:?} in {:?}",
+                value,
+                column
+            );
+        }
+
+        let config = Config {
+            display: DisplayConfig {
+                substitutions,
+                ..Config::default().display
+            },
+            ..Config::default()
+        };
+        let value = Value::test_list(vec![
+            Value::test_record(record! { "status" => Value::test_string("ok") }),
+            Value::test_record(record! { "status" => Value::test_string("error") }),
+        ]);
+        let mut app = App::from_value(value.clone());
+        app.screen_size = (40, 10);
+        let screen = render_to_string(&mut app, &config);
+        assert!(screen.contains('✔') && screen.contains('✘'), "{}", screen);
+        assert!(!screen.contains("error"), "{}", screen);
+        assert_eq!(*app.value, value, "only the rendering changes");
+
+        app.position.members = to_path_member_vec(&[PM::I(0), PM::S("status")]);
+        let screen = render_to_string(&mut app, &config);
+        assert!(screen.contains('✔'), "{}", screen);
     }
 }